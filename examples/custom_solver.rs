@@ -0,0 +1,63 @@
+// a solver of its own next to the built-in one: it only looks at one number at a time,
+// so it gets stuck on boards the subset reasoning of the built-in solver gets through
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use minesweeper::analyze;
+use minesweeper::solver::{BoardView, Cell, Deductions, Solver, Standard};
+use minesweeper::MSGame;
+
+struct SingleNumbers;
+
+impl Solver for SingleNumbers {
+	// a number with as many hidden tiles around it as it has mines makes them all mines,
+	// one whose mines are all among the proven ones makes the rest safe
+	fn deduce(&self, view: &BoardView) -> Deductions {
+		let mut mines = BTreeSet::new();
+		let mut safe = BTreeSet::new();
+		loop {
+			let before = (mines.len(), safe.len());
+			for y in 0..view.height() {
+				for x in 0..view.width() {
+					let Cell::Open(n) = view.get(x, y) else {
+						continue;
+					};
+					let hidden: Vec<_> = view
+						.neighbors(x, y)
+						.filter(|&(nx, ny)| matches!(view.get(nx, ny), Cell::Hidden | Cell::Flagged))
+						.map(|(nx, ny)| (ny, nx))
+						.collect();
+					let known = hidden.iter().filter(|pos| mines.contains(*pos)).count();
+					if hidden.len() == n as usize {
+						mines.extend(hidden);
+					} else if known == n as usize {
+						safe.extend(hidden.into_iter().filter(|pos| !mines.contains(pos)));
+					}
+				}
+			}
+			if (mines.len(), safe.len()) == before {
+				break;
+			}
+		}
+		// kept as (y, x) so the sets are in reading order
+		Deductions {
+			safe: safe.into_iter().map(|(y, x)| (x, y)).collect(),
+			mines: mines.into_iter().map(|(y, x)| (x, y)).collect(),
+		}
+	}
+}
+
+// the check `--no-guess` boards go through, with the game reasoning with `solver`:
+// opens the start tile and then whatever the solver proves safe, returns whether that clears the board
+fn no_guess(solver: Rc<dyn Solver>, seed: u64) -> bool {
+	let mut game = MSGame::new(16, 16, 40, seed).expect("16x16 has room for 40 mines").with_solver(solver);
+	analyze::solve(&mut game).expect("the solver only gives tiles on the board")
+}
+
+fn main() {
+	let seeds = 1..=200;
+	let cleared = |solver: Rc<dyn Solver>| seeds.clone().filter(|&seed| no_guess(solver.clone(), seed)).count();
+	println!("boards cleared without guessing out of {}:", seeds.clone().count());
+	println!("single numbers: {}", cleared(Rc::new(SingleNumbers)));
+	println!("built-in:       {}", cleared(Rc::new(Standard)));
+}
@@ -0,0 +1,28 @@
+// plays a seeded board with only the library, the same code runs on wasm32-unknown-unknown:
+// cargo build --example wasm_core --no-default-features --target wasm32-unknown-unknown
+use minesweeper::{boardfile, solver, MSGame, TurnResult};
+
+fn main() {
+	let mut game = MSGame::new(16, 16, 32, 1234).expect("16x16 has room for 32 mines");
+	let mut turn = game.open_at(8, 8).expect("the middle is on the board");
+	if matches!(turn, TurnResult::Lose) {
+		println!("the first tile was a mine");
+		return;
+	}
+	// keep opening what the numbers prove is safe
+	while matches!(turn, TurnResult::Continue) {
+		let safe = solver::deduce(&game.view()).safe;
+		if safe.is_empty() {
+			break;
+		}
+		for (x, y) in safe {
+			turn = game.open_at(x, y).expect("the solver only gives tiles on the board");
+		}
+	}
+	if matches!(turn, TurnResult::Win) {
+		println!("cleared without guessing");
+	} else {
+		println!("stuck, a guess is needed");
+	}
+	print!("{}", boardfile::to_text(&game));
+}
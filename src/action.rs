@@ -0,0 +1,178 @@
+#[cfg(feature = "tui")]
+use console::Key;
+
+// what a key asks for, so the game doesn't need to know where its input comes from
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+	// typed in front of an action to do it that many times
+	Digit(u8),
+	Move(Direction),
+	// several tiles at once, without wrapping around
+	Jump(Direction),
+	RowStart,
+	RowEnd,
+	// the first and last tile of the board
+	BoardStart,
+	BoardEnd,
+	Open,
+	// open everything left once every mine has a flag
+	Finish,
+	Flag,
+	// the second kind of flag, for anti-mines
+	AntiFlag,
+	NextFrontier,
+	// opened numbers that still need work
+	NextNumber,
+	PreviousNumber,
+	ToggleForced,
+	CheckFlags,
+	SuggestGuess,
+	// what can be seen around the cursor, in words
+	Describe,
+	// the cursor's row or column as text
+	ReadRow,
+	ReadColumn,
+	Restart,
+	Quit,
+	// needs a second press
+	Surrender,
+	// these only change how the board is drawn
+	ToggleCompact,
+	ToggleMinimap,
+	ToggleDim,
+	ToggleHighlight,
+	ToggleRates,
+	ToggleFrontier,
+	// rows drawn as columns
+	ToggleTranspose,
+	// the best run on a seeded board
+	ToggleGhost,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Direction {
+	Up,
+	Down,
+	Left,
+	Right
+}
+
+// the keys only exist in the terminal game
+#[cfg(feature = "tui")]
+impl Action {
+	// None for keys that do nothing
+	pub fn from_key(key: &Key) -> Option<Self> {
+		let action = match key {
+			Key::Char(c @ '0'..='9') => Self::Digit(*c as u8 - b'0'),
+			Key::ArrowUp => Self::Move(Direction::Up),
+			Key::ArrowDown => Self::Move(Direction::Down),
+			Key::ArrowLeft => Self::Move(Direction::Left),
+			Key::ArrowRight => Self::Move(Direction::Right),
+			// shifted arrows arrive as these, see input::spawn_reader
+			Key::Char('K') => Self::Jump(Direction::Up),
+			Key::Char('J') => Self::Jump(Direction::Down),
+			Key::Char('H') => Self::Jump(Direction::Left),
+			Key::Char('L') => Self::Jump(Direction::Right),
+			Key::Home | Key::Char('^') => Self::RowStart,
+			Key::End | Key::Char('$') => Self::RowEnd,
+			Key::Char('g') => Self::BoardStart,
+			Key::Char('G') => Self::BoardEnd,
+			Key::Char(' ') => Self::Open,
+			Key::Enter => Self::Finish,
+			Key::Char('f') => Self::Flag,
+			Key::Char('F') => Self::AntiFlag,
+			Key::Char('n') => Self::NextFrontier,
+			Key::Tab => Self::NextNumber,
+			Key::BackTab => Self::PreviousNumber,
+			Key::Char('c') => Self::ToggleForced,
+			Key::Char('a') => Self::CheckFlags,
+			Key::Char('p') => Self::SuggestGuess,
+			Key::Char('i') => Self::Describe,
+			Key::Char('R') => Self::ReadRow,
+			Key::Char('C') => Self::ReadColumn,
+			Key::Char('r') => Self::Restart,
+			Key::Escape | Key::Char('q') => Self::Quit,
+			Key::Char('!') => Self::Surrender,
+			Key::Char('z') => Self::ToggleCompact,
+			Key::Char('m') => Self::ToggleMinimap,
+			Key::Char('d') => Self::ToggleDim,
+			Key::Char('h') => Self::ToggleHighlight,
+			Key::Char('e') => Self::ToggleRates,
+			Key::Char('b') => Self::ToggleFrontier,
+			Key::Char('t') => Self::ToggleTranspose,
+			Key::Char('v') => Self::ToggleGhost,
+			_ => return None,
+		};
+		Some(action)
+	}
+
+	// the key that asks for this action, for writing actions down as keys
+	pub fn key(self) -> Key {
+		match self {
+			Self::Digit(digit) => Key::Char((b'0' + digit) as char),
+			Self::Move(Direction::Up) => Key::ArrowUp,
+			Self::Move(Direction::Down) => Key::ArrowDown,
+			Self::Move(Direction::Left) => Key::ArrowLeft,
+			Self::Move(Direction::Right) => Key::ArrowRight,
+			Self::Jump(Direction::Up) => Key::Char('K'),
+			Self::Jump(Direction::Down) => Key::Char('J'),
+			Self::Jump(Direction::Left) => Key::Char('H'),
+			Self::Jump(Direction::Right) => Key::Char('L'),
+			Self::RowStart => Key::Home,
+			Self::RowEnd => Key::End,
+			Self::BoardStart => Key::Char('g'),
+			Self::BoardEnd => Key::Char('G'),
+			Self::Open => Key::Char(' '),
+			Self::Finish => Key::Enter,
+			Self::Flag => Key::Char('f'),
+			Self::AntiFlag => Key::Char('F'),
+			Self::NextFrontier => Key::Char('n'),
+			Self::NextNumber => Key::Tab,
+			Self::PreviousNumber => Key::BackTab,
+			Self::ToggleForced => Key::Char('c'),
+			Self::CheckFlags => Key::Char('a'),
+			Self::SuggestGuess => Key::Char('p'),
+			Self::Describe => Key::Char('i'),
+			Self::ReadRow => Key::Char('R'),
+			Self::ReadColumn => Key::Char('C'),
+			Self::Restart => Key::Char('r'),
+			Self::Quit => Key::Char('q'),
+			Self::Surrender => Key::Char('!'),
+			Self::ToggleCompact => Key::Char('z'),
+			Self::ToggleMinimap => Key::Char('m'),
+			Self::ToggleDim => Key::Char('d'),
+			Self::ToggleHighlight => Key::Char('h'),
+			Self::ToggleRates => Key::Char('e'),
+			Self::ToggleFrontier => Key::Char('b'),
+			Self::ToggleTranspose => Key::Char('t'),
+			Self::ToggleGhost => Key::Char('v'),
+		}
+	}
+}
+
+#[cfg(all(test, feature = "tui"))]
+mod tests {
+	use super::*;
+	use crate::{boardfile, TurnResult};
+
+	#[test]
+	fn keys_written_down_read_back_the_same() {
+		let special = [Key::ArrowUp, Key::ArrowDown, Key::ArrowLeft, Key::ArrowRight, Key::Home, Key::End, Key::Enter, Key::Tab, Key::BackTab, Key::Escape];
+		let keys = special.into_iter().chain((' '..='~').map(Key::Char));
+		for action in keys.filter_map(|key| Action::from_key(&key)) {
+			assert!(Action::from_key(&action.key()) == Some(action), "{:?}", action.key());
+		}
+	}
+
+	#[test]
+	fn a_game_played_with_keys() {
+		let mut game = boardfile::parse("test", "*...\n....\n...*\n").unwrap();
+		let keys = [Key::Char('G'), Key::Char('f'), Key::Char('g'), Key::Char('f'), Key::Char('3'), Key::ArrowRight, Key::Enter];
+		let mut turn = TurnResult::Continue;
+		for key in keys {
+			turn = game.act(Action::from_key(&key).expect("a key with an action"));
+		}
+		assert!(matches!(turn, TurnResult::Win));
+		assert_eq!((game.cursor_x, game.cursor_y), (3, 0));
+	}
+}
@@ -0,0 +1,109 @@
+#[cfg(feature = "tui")]
+use console::style;
+
+use crate::error::MinesweeperError;
+use crate::MSGame;
+#[cfg(feature = "tui")]
+use crate::{pos_label, TileContents, TileVis};
+
+// opens the start cell and keeps opening everything the game's solver can prove safe.
+// returns true if that clears the board
+pub fn solve(game: &mut MSGame) -> Result<bool, MinesweeperError> {
+	let Some((x, y)) = game.start_cell() else {
+		return Ok(true);
+	};
+	game.open_at(x, y)?;
+	loop {
+		let deductions = game.solver.deduce(&game.view());
+		if deductions.safe.is_empty() {
+			break;
+		}
+		for (x, y) in deductions.safe {
+			game.open_at(x, y)?;
+		}
+	}
+	Ok(game.safe_left == 0)
+}
+
+// prints whether the board can be solved without guessing, returns the exit code
+#[cfg(feature = "tui")]
+pub fn run(mut game: MSGame) -> i32 {
+	let start = game.start_cell();
+	let solved = match solve(&mut game) {
+		Ok(solved) => solved,
+		Err(err) => {
+			eprintln!("{}", err);
+			return err.exit_code();
+		},
+	};
+	if solved {
+		println!("solvable without guessing");
+		return 0;
+	}
+	let stuck = game.frontier();
+	if let Some((x, y)) = start {
+		println!("starting from {}", pos_label(x, y));
+	}
+	println!("requires guessing, stuck at {} frontier tiles (marked ?):", stuck.len());
+	for y in 0..game.height {
+		let row: Vec<String> = (0..game.width)
+			.map(|x| {
+				let tile = game.get(x, y);
+				match (tile.visibility, tile.contents) {
+					_ if stuck.contains(&(x, y)) => style("?").black().on_yellow().to_string(),
+					(TileVis::Open, TileContents::Safe(0)) => " ".into(),
+					(TileVis::Open, TileContents::Safe(n)) => n.to_string(),
+					_ => "#".into(),
+				}
+			})
+			.collect();
+		println!("{}", row.join(" "));
+	}
+	1
+}
+
+#[cfg(test)]
+mod tests {
+	use std::rc::Rc;
+
+	use super::*;
+	use crate::boardfile;
+	use crate::solver::{BoardView, Deductions, Solver};
+
+	// proves nothing, so only what the start cell opens gets opened
+	struct Nothing;
+
+	impl Solver for Nothing {
+		fn deduce(&self, _: &BoardView) -> Deductions {
+			Deductions::default()
+		}
+	}
+
+	#[test]
+	fn solvable_board() {
+		let mut game = boardfile::parse("test", "*..\n...\n...\n...\n").unwrap();
+		assert!(solve(&mut game).unwrap());
+		assert_eq!(game.safe_left, 0);
+		#[cfg(feature = "tui")]
+		assert_eq!(run(boardfile::parse("test", "*..\n...\n...\n...\n").unwrap()), 0);
+	}
+
+	#[test]
+	fn fifty_fifty_needs_a_guess() {
+		// the two top tiles both touch only the same two 1s
+		let board = "*.\n..\n..\n..\n";
+		let mut game = boardfile::parse("test", board).unwrap();
+		assert!(!solve(&mut game).unwrap());
+		assert_eq!(game.frontier(), [(0, 0), (1, 0)]);
+		#[cfg(feature = "tui")]
+		assert_eq!(run(boardfile::parse("test", board).unwrap()), 1);
+	}
+
+	#[test]
+	fn solves_with_the_games_solver() {
+		let board = "*...\n....\n...*\n";
+		assert!(solve(&mut boardfile::parse("test", board).unwrap()).unwrap());
+		let mut game = boardfile::parse("test", board).unwrap().with_solver(Rc::new(Nothing));
+		assert!(!solve(&mut game).unwrap());
+	}
+}
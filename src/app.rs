@@ -0,0 +1,933 @@
+use std::cmp::Reverse;
+use std::io::{self, IsTerminal};
+use std::mem;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use console::{Key, Term};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+
+use crate::action::Action;
+use crate::coop::Coop;
+use crate::endscreen::Summary;
+use crate::error::MinesweeperError;
+use crate::ghost::{self, Ghost};
+use crate::glyphs::Glyphs;
+use crate::lang::{tr, trf, Msg};
+use crate::leaderboard::{self, Leaderboard};
+use crate::macros::{self, MacroKey, Macros};
+use crate::notices::Notices;
+use crate::options::{apply_variants, new_game, Options};
+use crate::placement::Placement;
+use crate::race::{self, Race};
+use crate::records::Records;
+use crate::recovery::{self, Recovery};
+use crate::render::{self, BrailleRenderer, GridRenderer, MinimapCache, Renderer, Status, View};
+use crate::replay::Replay;
+use crate::score::{self, Score};
+use crate::spectate;
+use crate::submit;
+use crate::svg;
+use crate::theme::Theme;
+use crate::{history, input, screen, GameEvent, MSGame, TurnResult};
+
+const FLASH_TIME: Duration = Duration::from_millis(150);
+const REVEAL_FRAME_TIME: Duration = Duration::from_millis(15);
+// how often the timer is checked while waiting for a key
+const TICK_TIME: Duration = Duration::from_millis(200);
+// the longest pauses listed on the end screen
+const PAUSES_SHOWN: usize = 5;
+
+// one interactive game on the terminal, from setting up the screen to the end screen.
+// `run` plays it, `report` keeps the records and `finish` shows the end screen, so anything
+// wrapping the game can stop after `run` and do its own reporting
+pub struct App {
+	options: Options,
+	game: MSGame,
+	race: Option<Race>,
+	score: Option<Score>,
+	view: View,
+	braille: BrailleRenderer,
+	grid: GridRenderer,
+	plain: bool,
+	altscreen: bool,
+	stdout: Term,
+	keys: Receiver<Key>,
+	// the keys come from someone at the keyboard, not from a replay or piped commands, so keys that arrive
+	// together are a held key or a burst over a slow link
+	typed: bool,
+	// a watched replay plays someone else's game, it leaves no records
+	watching: bool,
+	recording: Option<Replay>,
+	macros: Macros,
+	coop: Option<Coop>,
+	// messages waiting for the status bar, from the game or from anything around it
+	notices: Notices,
+	// only boards picked with --seed go on the leaderboard, not the random ones after a restart
+	seeded: bool,
+	lines_drawn: usize,
+	// the game as it was before the first move, for another attempt at the same board
+	fresh: MSGame,
+	// how many times this board has been played in a row
+	attempt: usize,
+	// pause after this long without keys, only when a player is at the keyboard and no one else is waiting
+	idle_after: Option<Duration>,
+	// the best run on this seeded board, drawn along with the game
+	ghost: Option<Ghost>,
+	// time played before a crash, the timer of a resumed game goes on from it
+	resumed: Duration,
+	// a status bar item from whatever wraps the game, like the level of a campaign
+	label: Option<String>,
+	// the game ends as quit at this time, it counts down in the status bar
+	deadline: Option<SystemTime>,
+	// when each board-changing move was made, counted from the start, and where
+	move_times: Vec<(Duration, (usize, usize))>,
+	// how sending a daily win with --submit went, once the server has answered
+	submission: Option<Receiver<String>>,
+}
+
+// what the end screen was woken up by
+enum EndEvent {
+	Key(Key),
+	// the line about how sending the time went
+	Sent(String),
+	Closed,
+}
+
+// how the game went
+pub struct GameOutcome {
+	pub result: TurnResult,
+	pub elapsed: Duration,
+	pub seed: Option<u64>,
+	// the seed was picked by the player, not by a restart
+	pub seeded: bool,
+}
+
+fn elapsed_secs(start_time: SystemTime) -> u64 {
+	SystemTime::now()
+		.duration_since(start_time)
+		.unwrap()
+		.as_secs()
+}
+
+impl App {
+	// sets up the screen and the game, which for a race means waiting for the other player
+	pub fn new(options: Options, glyphs: Glyphs, watching: Option<Replay>) -> Result<Self, MinesweeperError> {
+		let theme = Theme::by_name(&options.theme)?;
+		let plain = render::plain_output(options.plain);
+		if plain {
+			console::set_colors_enabled(false);
+		}
+		let mut view = View {
+			rates: options.rates,
+			compact: options.compact,
+			minimap: options.minimap,
+			dim_satisfied: options.dim_satisfied,
+			highlight: options.highlight,
+			frontier: options.frontier,
+			transpose: options.transpose.unwrap_or(false),
+		};
+		let braille = BrailleRenderer {
+			theme: theme.clone(),
+			glyphs,
+			plain,
+			center: options.center,
+			fog: options.fog,
+		};
+		let grid = GridRenderer {
+			theme,
+			glyphs,
+			plain,
+			wide: options.wide,
+			checkerboard: options.checkerboard,
+			grid_lines: options.grid_lines,
+			center: options.center,
+			fog: options.fog,
+			minimap: MinimapCache::default(),
+		};
+		let stdout = Term::buffered_stdout();
+		let altscreen = options.altscreen && !plain;
+		if altscreen {
+			screen::enter(&stdout);
+		}
+		let (mut game, race) = match (options.host, &options.connect) {
+			(Some(port), _) => {
+				let game = new_game(&options)?;
+				let race = race::host(&options.host_address, port, &game)?;
+				(game, Some(race))
+			},
+			(None, Some(addr)) => {
+				let (race, game) = race::connect(addr)?;
+				(game, Some(race))
+			},
+			(None, None) => (new_game(&options)?, None),
+		};
+		apply_variants(&mut game, &options)?;
+		// a replay places the rush mines again from the seed of the board
+		if options.record.is_some() && options.rush.is_some() && game.seed.is_none() {
+			return Err("rush mines can't be recorded on a board without a seed, add a '# seed' line to the board file".into());
+		}
+		if options.transpose.is_none() && !plain {
+			let (rows, cols) = stdout.size();
+			view.transpose = render::prefer_transposed((rows as usize, cols as usize), game.width, game.height);
+		}
+		let keys = match &watching {
+			Some(replay) => replay.spawn_player(),
+			None if io::stdin().is_terminal() => input::spawn_reader(),
+			None => input::spawn_command_reader(),
+		};
+		let typed = watching.is_none() && io::stdin().is_terminal();
+		let idle_after = options.idle.filter(|_| typed && race.is_none());
+		let seeded = options.seed.is_some() && options.board.is_none() && watching.is_none();
+		// seeded games are always recorded, a new best time becomes the ghost for the next attempts
+		let ghosting = seeded && race.is_none();
+		let mut app = Self {
+			score: options.arcade.then(Score::new),
+			seeded,
+			recording: (options.record.is_some() || ghosting).then(|| Replay::new(&options, &game)),
+			watching: watching.is_some(),
+			macros: Macros::load(),
+			coop: options.coop.then(|| Coop::new(&options.coop_keys)),
+			notices: Notices::default(),
+			options,
+			fresh: game.clone(),
+			game,
+			race,
+			view,
+			braille,
+			grid,
+			plain,
+			altscreen,
+			stdout,
+			keys,
+			typed,
+			lines_drawn: 0,
+			attempt: 1,
+			idle_after,
+			ghost: None,
+			resumed: Duration::ZERO,
+			label: None,
+			deadline: None,
+			move_times: Vec::new(),
+			submission: None,
+		};
+		if ghosting {
+			app.ghost = app.game.seed.and_then(|seed| {
+				let coop = app.options.coop.then(|| Coop::new(&app.options.coop_keys));
+				Ghost::load(&leaderboard::code(&app.difficulty(), seed), &app.game, coop)
+			});
+		}
+		Ok(app)
+	}
+
+	// plays until the game is won, lost or quit
+	pub fn run(&mut self) -> GameOutcome {
+		let mut start_time = SystemTime::now() - mem::take(&mut self.resumed);
+		recovery::update(&self.game, &self.options, SystemTime::now().duration_since(start_time).unwrap_or_default());
+		self.publish(start_time, &TurnResult::Continue);
+		self.draw(&self.status(start_time, None));
+		// a key pressed during an animation, to be handled next
+		let mut pending_key = None;
+		// the timer value on screen, and the countdown when there is one
+		let mut drawn_secs = 0;
+		let mut drawn_left = self.time_left().map(|left| left.as_secs());
+		// when the next mine is added in rush mode
+		let mut next_rush = self.options.rush.map(|every| start_time + every);
+		let mut rush_rng = StdRng::seed_from_u64(self.game.seed.unwrap_or_else(|| thread_rng().gen()));
+		// when the last key was pressed, nothing counts as idle before the first one
+		let mut last_key: Option<SystemTime> = None;
+		// when the timer was paused for lack of keys
+		let mut idle_since: Option<SystemTime> = None;
+
+		let result = loop {
+			// checked on every pass, keys coming in steadily would keep the timeout below from ever seeing it
+			if idle_since.is_none() && self.out_of_time() {
+				break TurnResult::Quit;
+			}
+			if let (Some(every), Some(next), None) = (self.options.rush, next_rush, idle_since) {
+				if SystemTime::now() >= next {
+					next_rush = Some(next + every);
+					let added = self.game.add_mine(&mut rush_rng);
+					if let Some(recording) = &mut self.recording {
+						recording.rush(SystemTime::now().duration_since(start_time).unwrap_or_default(), &self.game);
+					}
+					if added {
+						self.notices.push(tr(Msg::MineAdded));
+						let text = self.notices.current();
+						let mut status = self.status(start_time, text);
+						if !self.plain {
+							status.counter_flash = true;
+							self.draw(&status);
+							thread::sleep(FLASH_TIME);
+							status.counter_flash = false;
+						}
+						self.draw(&status);
+					}
+				}
+			}
+			let key = match pending_key.take() {
+				Some(key) => key,
+				None => match self.keys.recv_timeout(TICK_TIME) {
+					Ok(key) => key,
+					// the paused screen stays as it is until a key
+					Err(RecvTimeoutError::Timeout) if idle_since.is_some() => continue,
+					Err(RecvTimeoutError::Timeout) => {
+						let idle = last_key
+							.zip(self.idle_after)
+							.is_some_and(|(last, after)| last.elapsed().unwrap_or_default() >= after);
+						if idle {
+							idle_since = Some(SystemTime::now());
+							let mut status = self.status(start_time, Some(tr(Msg::Idle).into()));
+							status.idle = true;
+							self.draw(&status);
+							continue;
+						}
+						// only the timers, the message and the ghost can change without a key, redraw when they do
+						let expired = self.notices.expire();
+						let elapsed = SystemTime::now().duration_since(start_time).unwrap_or_default();
+						let ghost_moved = self.ghost.as_mut().is_some_and(|ghost| ghost.advance(elapsed) && ghost.shown);
+						let race_message = self.race.as_mut().and_then(Race::poll);
+						let race_update = race_message.is_some();
+						if let Some(text) = race_message {
+							self.notices.push(text);
+						}
+						let secs = elapsed_secs(start_time);
+						let left = self.time_left().map(|left| left.as_secs());
+						// plain output only gets a new frame when something happened
+						if race_update || !self.plain && (expired || ghost_moved || secs != drawn_secs || left != drawn_left) {
+							drawn_secs = secs;
+							drawn_left = left;
+							let text = self.notices.current();
+							self.draw(&self.status(start_time, text));
+						}
+						continue;
+					},
+					// the key reader stops on ctrl+c
+					Err(RecvTimeoutError::Disconnected) => Key::Escape,
+				},
+			};
+			// a key that came in while waiting for it after the time ran out is too late
+			if idle_since.is_none() && self.out_of_time() {
+				break TurnResult::Quit;
+			}
+			// the key that ends a pause only resumes the game, the paused time is left out of the timer
+			if let Some(since) = idle_since.take() {
+				let paused = since.elapsed().unwrap_or_default();
+				start_time += paused;
+				next_rush = next_rush.map(|next| next + paused);
+				self.deadline = self.deadline.map(|deadline| deadline + paused);
+				last_key = Some(SystemTime::now());
+				let text = self.notices.current();
+				self.draw(&self.status(start_time, text));
+				continue;
+			}
+			// with an area opened for them the clock waits for the player
+			if last_key.is_none() && self.options.open_start && self.game.moves == 0 {
+				start_time = SystemTime::now();
+				next_rush = self.options.rush.map(|every| start_time + every);
+			}
+			last_key = Some(SystemTime::now());
+			// the arrows move the way the board is drawn, the game and the replay only see the board's own directions
+			let key = match self.view.transposes(&self.game) && !self.watching {
+				true => self.coop.as_ref().and_then(|coop| coop.transposed(&key)).unwrap_or_else(|| input::transposed(key)),
+				false => key,
+			};
+			// messages stay until the next key
+			self.notices.dismiss();
+			let moves = self.game.moves;
+			// the second player's keys move their own cursor, ahead of everything else
+			let partner = self.coop.as_ref().and_then(|coop| coop.action(&key));
+			// macros only come from the first player, a watched replay already has them played out
+			let macro_key = if self.watching || partner.is_some() { MacroKey::Other } else { self.macros.handle(&key) };
+			let view = &mut self.view;
+			let turn = match macro_key {
+				MacroKey::Handled(text) => {
+					self.notices.extend(text);
+					TurnResult::Continue
+				},
+				MacroKey::Play(actions) => self.play_macro(actions, start_time),
+				MacroKey::Other => match partner.or_else(|| Action::from_key(&key)) {
+					Some(Action::ToggleCompact) => {
+						view.compact = !view.compact;
+						TurnResult::Continue
+					},
+					Some(Action::ToggleMinimap) => {
+						view.minimap = !view.minimap;
+						TurnResult::Continue
+					},
+					Some(Action::ToggleDim) => {
+						view.dim_satisfied = !view.dim_satisfied;
+						TurnResult::Continue
+					},
+					Some(Action::ToggleHighlight) => {
+						view.highlight = !view.highlight;
+						TurnResult::Continue
+					},
+					Some(Action::ToggleRates) => {
+						view.rates = !view.rates;
+						TurnResult::Continue
+					},
+					Some(Action::ToggleFrontier) => {
+						view.frontier = !view.frontier;
+						TurnResult::Continue
+					},
+					Some(Action::ToggleTranspose) => {
+						view.transpose = !view.transpose;
+						TurnResult::Continue
+					},
+					// read out in the app, which knows what the fog hides
+					Some(action @ (Action::ReadRow | Action::ReadColumn)) => {
+						self.notices.push(self.game.read_line(action == Action::ReadRow, self.options.fog));
+						TurnResult::Continue
+					},
+					Some(Action::ToggleGhost) => {
+						if let Some(ghost) = &mut self.ghost {
+							ghost.shown = !ghost.shown;
+						}
+						TurnResult::Continue
+					},
+					// both players have to stay on the same board
+					Some(Action::Restart) if self.race.is_some() => TurnResult::Continue,
+					Some(Action::Surrender) if self.race.is_some() => {
+						self.notices.push(tr(Msg::NoSurrenderInRace));
+						TurnResult::Continue
+					},
+					Some(Action::Restart) => {
+						let mut game = self.game.regenerate(thread_rng().gen());
+						if let Err(err) = apply_variants(&mut game, &self.options) {
+							self.notices.push(err.to_string());
+						}
+						if self.game.forced.is_some() && game.forced.is_none() {
+							game.toggle_forced();
+						}
+						self.replace_game(game);
+						start_time = SystemTime::now();
+						next_rush = self.options.rush.map(|every| start_time + every);
+						// the replay of the new board starts over with its seed, so do its rush mines
+						rush_rng = StdRng::seed_from_u64(self.game.seed.unwrap_or_else(|| thread_rng().gen()));
+						if self.options.open_start {
+							last_key = None;
+						}
+						self.notices.push(tr(Msg::NewGame));
+						TurnResult::Continue
+					},
+					action => {
+						let turn = match action {
+							Some(action) if partner.is_some() => self.game.act_as_partner(action),
+							Some(action) => self.game.act(action),
+							None => {
+								self.notices.push(tr(Msg::UnknownKey));
+								TurnResult::Continue
+							},
+						};
+						let recorded = action.filter(|_| partner.is_none());
+						self.notices.extend(recorded.and_then(|action| self.macros.record(action)));
+						if let Some(recording) = &mut self.recording {
+							recording.key(SystemTime::now().duration_since(start_time).unwrap_or_default(), key, &self.game);
+						}
+						turn
+					},
+				},
+			};
+			// a restart starts the count over, it isn't a move
+			if self.game.moves > moves {
+				let at = partner.and(self.game.partner).unwrap_or((self.game.cursor_x, self.game.cursor_y));
+				self.move_times.push((SystemTime::now().duration_since(start_time).unwrap_or_default(), at));
+			}
+			let mut revealed = false;
+			for event in self.game.events.drain(..) {
+				if let GameEvent::Opened(opened) = event {
+					revealed = true;
+					if let Some(coop) = &mut self.coop {
+						coop.opened[partner.is_some() as usize] += opened;
+					}
+				}
+				if let Some(score) = &mut self.score {
+					score.handle(&event);
+				}
+			}
+			if let Some(race) = &mut self.race {
+				self.notices.extend(race.poll());
+				race.send_progress(&self.game);
+				if !matches!(turn, TurnResult::Continue) {
+					race.finish(matches!(turn, TurnResult::Win));
+				}
+			}
+			self.publish(start_time, &turn);
+			recovery::update(&self.game, &self.options, SystemTime::now().duration_since(start_time).unwrap_or_default());
+			if let Some(ghost) = &mut self.ghost {
+				ghost.advance(SystemTime::now().duration_since(start_time).unwrap_or_default());
+			}
+			self.notices.extend(self.game.message.take());
+			let text = self.notices.current();
+			let mut status = self.status(start_time, text);
+			drawn_secs = status.elapsed;
+			if revealed && self.options.animation && !self.plain {
+				// replay the flood fill one layer at a time, any key skips to the end
+				let layers = self.game.reveal_order.last().map_or(0, |&(_, _, layer)| layer);
+				for layer in 0..layers {
+					status.reveal_layer = Some(layer);
+					self.draw(&status);
+					if let Ok(key) = self.keys.recv_timeout(REVEAL_FRAME_TIME) {
+						pending_key = Some(key);
+						break;
+					}
+				}
+				status.reveal_layer = None;
+			}
+			if let Some(radius) = self.options.fog {
+				let hidden_by_fog = self.game.reveal_order.iter().any(|&(x, y, _)| self.game.fogged(radius, x, y));
+				if revealed && hidden_by_fog && pending_key.is_none() && !self.plain {
+					// show how far the flood fill went
+					status.fog_flash = true;
+					self.draw(&status);
+					if let Ok(key) = self.keys.recv_timeout(self.options.fog_flash) {
+						pending_key = Some(key);
+					}
+					status.fog_flash = false;
+				}
+				status.fog_lifted = !matches!(turn, TurnResult::Continue);
+			}
+			if let TurnResult::Lose = turn {
+				if self.options.bell && !self.plain {
+					self.stdout.write_str("\x07").unwrap();
+					self.stdout.flush().unwrap();
+				}
+				if self.options.flash && !self.plain {
+					status.flash = true;
+					self.draw(&status);
+					thread::sleep(FLASH_TIME);
+					status.flash = false;
+				}
+			}
+			// keys that are already waiting are handled before drawing again, so holding a key or a burst
+			// of them doesn't redraw the board for every one
+			if self.typed && pending_key.is_none() && matches!(turn, TurnResult::Continue) {
+				if let Ok(key) = self.keys.try_recv() {
+					pending_key = Some(key);
+					continue;
+				}
+			}
+			self.lines_drawn = self.draw(&status);
+			match turn {
+				TurnResult::Continue => (),
+				_ => {
+					// the rest of a burst was meant for the game that just ended, not the end screen
+					while self.typed && self.keys.try_recv().is_ok() {}
+					break turn;
+				},
+			}
+		};
+		GameOutcome {
+			result,
+			elapsed: SystemTime::now().duration_since(start_time).unwrap(),
+			seed: self.game.seed,
+			seeded: self.seeded,
+		}
+	}
+
+	// keeps the records, the leaderboard, the history and the replay, and sums the game up for the end screen
+	pub fn report(&mut self, outcome: GameOutcome) -> Summary {
+		recovery::clear();
+		self.submission = None;
+		let (game, options, elapsed) = (&self.game, &self.options, outcome.elapsed);
+		let mut summary = Summary {
+			result: outcome.result,
+			elapsed,
+			three_bv: game.three_bv(),
+			moves: game.moves,
+			fatal: game.exploded,
+			best_time: None,
+			new_best: false,
+			score: None,
+			high_score: None,
+			new_high_score: false,
+			race: self.race.as_ref().and_then(Race::result),
+			coop: self.coop.as_ref().map(|coop| trf(Msg::CoopOpened, &[&coop.opened[0], &coop.opened[1]])),
+			clicks: game.click_budget.map(|budget| (game.clicks, budget)),
+			review: match (&game.review, game.exploded) {
+				(Some(review), Some(_)) => review.loss_lines(),
+				_ => Vec::new(),
+			},
+			seed_rank: None,
+			pauses: self.longest_pauses(),
+			best_rate: None,
+			new_best_rate: false,
+			best_efficiency: None,
+			new_best_efficiency: false,
+		};
+
+		let mut difficulty = self.difficulty();
+		// another attempt at a board whose mines have been seen
+		if self.attempt > 1 {
+			difficulty.push_str("-retry");
+		}
+		let mut records = Records::load();
+		let counts = !self.watching;
+		if counts && matches!(summary.result, TurnResult::Win) {
+			summary.best_time = records.best_time(&difficulty);
+			summary.new_best = records.submit_time(&difficulty, elapsed.as_secs());
+			if let (true, 1, Some(seed)) = (outcome.seeded, self.attempt, outcome.seed) {
+				let code = leaderboard::code(&difficulty, seed);
+				let mut board = Leaderboard::load();
+				let best = board.best(&code);
+				let rank = board.submit(&code, elapsed.as_secs_f64());
+				board.save();
+				summary.seed_rank = Some(leaderboard::rank_line(rank, &code, elapsed.as_secs_f64(), best));
+				if let (true, Some(url), Some(name)) = (options.daily, &options.submit, &options.name) {
+					self.submission = Some(submit::send(url, name, &code, seed, elapsed.as_secs_f64(), summary.three_bv));
+				}
+				if let (Some(1), Some(recording)) = (rank, &mut self.recording) {
+					if let Err(err) = ghost::save(&code, recording, &summary.result, elapsed) {
+						eprintln!("{}", err);
+					}
+				}
+			}
+			summary.best_rate = records.best_rate(&difficulty);
+			summary.new_best_rate = records.submit_rate(&difficulty, summary.rate());
+			summary.best_efficiency = records.best_efficiency(&difficulty);
+			summary.new_best_efficiency = records.submit_efficiency(&difficulty, summary.efficiency());
+		}
+		if let Some(score) = &mut self.score {
+			if let TurnResult::Win = summary.result {
+				score.win(elapsed.as_secs(), score::par_time(game.width, game.height, game.mines));
+			}
+			summary.score = Some(score.breakdown());
+			// quitting doesn't count towards high scores
+			if counts && !matches!(summary.result, TurnResult::Quit) {
+				summary.new_high_score = records.submit_score(&difficulty, score.total());
+				summary.high_score = records.high_score(&difficulty);
+			}
+		}
+		if counts {
+			records.save();
+		}
+		if let (Some(recording), Some(path)) = (&mut self.recording, &options.record) {
+			if let Err(err) = recording.save(path, &summary.result, elapsed) {
+				eprintln!("{}", err);
+			}
+		}
+		if let Some(path) = &options.export_svg {
+			if let Err(err) = svg::write(game, options, path) {
+				eprintln!("{}", err);
+			}
+		}
+		// quitting before doing anything isn't a game
+		if options.history && (game.moves > 0 || !matches!(summary.result, TurnResult::Quit)) {
+			history::append(game, options, &summary.result, elapsed);
+		}
+		summary
+	}
+
+	// shows the end screen and puts the terminal back
+	// unless R is pressed for another attempt at the same board, then the game is set up for that
+	// and this returns true
+	pub fn finish(&mut self, summary: &Summary) -> bool {
+		let stdout = &self.stdout;
+		summary.draw(stdout, (stdout.size().0 as usize).saturating_sub(self.lines_drawn));
+		let mut sent = None;
+		if self.altscreen {
+			if !matches!(summary.result, TurnResult::Quit) {
+				// both racers have to be on the same game, and a replay has no more keys
+				let can_retry = self.race.is_none() && !self.watching;
+				let prompt = tr(if can_retry { Msg::PressRetry } else { Msg::PressAnyKey });
+				stdout.write_line(prompt).unwrap();
+				stdout.flush().unwrap();
+				let mut heatmap = false;
+				loop {
+					let message = match self.end_event() {
+						EndEvent::Sent(line) => {
+							sent = Some(line.clone());
+							Some(line)
+						},
+						EndEvent::Key(Key::Char('R')) if can_retry => {
+							self.retry();
+							return true;
+						},
+						// switches between the board as it ended and the order its tiles were opened in
+						EndEvent::Key(Key::Char('o')) => {
+							heatmap = !heatmap;
+							heatmap.then(|| tr(Msg::HeatmapHint).into())
+						},
+						// puts the cursor where one of the longest pauses ended
+						EndEvent::Key(Key::Char(c @ '1'..='9')) if (c as usize - '1' as usize) < summary.pauses.len() => {
+							let n = c as usize - '1' as usize;
+							let (pause, (x, y)) = summary.pauses[n];
+							(self.game.cursor_x, self.game.cursor_y) = (x, y);
+							Some(trf(Msg::PauseShown, &[&(n + 1), &format!("{:.1}", pause.as_secs_f64())]))
+						},
+						_ => break,
+					};
+					let start_time = SystemTime::now() - summary.elapsed;
+					let mut status = self.status(start_time, message);
+					status.heatmap = heatmap;
+					self.lines_drawn = self.draw(&status);
+					summary.draw(stdout, (stdout.size().0 as usize).saturating_sub(self.lines_drawn));
+					stdout.write_line(prompt).unwrap();
+					stdout.flush().unwrap();
+				}
+			}
+			screen::leave(stdout);
+			// leave the short version behind on the normal screen
+			summary.draw(stdout, 0);
+		}
+		// the end screen is gone, so a server that hasn't answered yet isn't waited for
+		let line = sent.or_else(|| match self.submission.as_ref()?.try_recv() {
+			Ok(line) => Some(line),
+			Err(TryRecvError::Empty) => Some(tr(Msg::SubmitPending).into()),
+			Err(TryRecvError::Disconnected) => None,
+		});
+		if let Some(line) = line {
+			stdout.write_line(&line).unwrap();
+			stdout.flush().unwrap();
+		}
+		false
+	}
+
+	fn end_event(&self) -> EndEvent {
+		let Some(submission) = &self.submission else {
+			return self.keys.recv().map_or(EndEvent::Closed, EndEvent::Key);
+		};
+		loop {
+			if let Ok(line) = submission.try_recv() {
+				return EndEvent::Sent(line);
+			}
+			match self.keys.recv_timeout(TICK_TIME) {
+				Ok(key) => return EndEvent::Key(key),
+				Err(RecvTimeoutError::Disconnected) => return EndEvent::Closed,
+				Err(RecvTimeoutError::Timeout) => (),
+			}
+		}
+	}
+
+	// carries on with a game saved by a crash. it doesn't go on the leaderboard and can't be replayed
+	// since the moves before the crash are gone
+	pub fn resume(&mut self, recovery: &Recovery) -> Result<(), String> {
+		recovery.apply(&mut self.game)?;
+		self.seeded = false;
+		self.recording = None;
+		self.ghost = None;
+		self.resumed = recovery.elapsed;
+		Ok(())
+	}
+
+	// a new board of another size, for the next level of a campaign
+	pub fn set_board(&mut self, width: usize, height: usize, mines: usize) -> Result<(), MinesweeperError> {
+		self.options.width = width;
+		self.options.height = height;
+		self.options.mines = mines;
+		let mut game = new_game(&self.options)?;
+		apply_variants(&mut game, &self.options)?;
+		self.replace_game(game);
+		Ok(())
+	}
+
+	// the board with this seed, for wrappers that play a list of known boards. it doesn't go on the leaderboard
+	pub fn set_seeded_board(&mut self, width: usize, height: usize, mines: usize, seed: u64) -> Result<(), MinesweeperError> {
+		self.options.seed = Some(seed);
+		self.set_board(width, height, mines)
+	}
+
+	pub fn set_label(&mut self, label: Option<String>) {
+		self.label = label;
+	}
+
+	pub fn deadline(&self) -> Option<SystemTime> {
+		self.deadline
+	}
+
+	pub fn set_deadline(&mut self, deadline: Option<SystemTime>) {
+		self.deadline = deadline;
+	}
+
+	// a message for the status bar from whatever wraps the game
+	pub fn notify(&mut self, text: String) {
+		self.notices.push(text);
+	}
+
+	// how much of the board has been cleared, from 0 to 1
+	pub fn progress(&self) -> f64 {
+		let total = self.fresh.safe_left.max(1);
+		1.0 - self.game.safe_left.min(total) as f64 / total as f64
+	}
+
+	fn time_left(&self) -> Option<Duration> {
+		self.deadline.map(|deadline| deadline.duration_since(SystemTime::now()).unwrap_or_default())
+	}
+
+	fn out_of_time(&self) -> bool {
+		self.deadline.is_some_and(|deadline| SystemTime::now() >= deadline)
+	}
+
+	// shows `lines` under the board as it ended and waits for a key, false if it was q or escape.
+	// without the alternate screen the lines are only printed, the same way the end screen doesn't wait there
+	pub fn interlude(&mut self, elapsed: Duration, lines: &[String], prompt: Msg) -> bool {
+		let stdout = &self.stdout;
+		if self.altscreen {
+			self.lines_drawn = self.draw(&self.status(SystemTime::now() - elapsed, None));
+		}
+		for line in lines {
+			stdout.write_line(line).unwrap();
+		}
+		if !self.altscreen {
+			stdout.flush().unwrap();
+			return true;
+		}
+		stdout.write_line(tr(prompt)).unwrap();
+		stdout.flush().unwrap();
+		!matches!(self.keys.recv(), Ok(Key::Char('q') | Key::Escape) | Err(_))
+	}
+
+	// puts the terminal back after games that were wrapped instead of finished, leaving `lines` behind
+	pub fn close(&self, lines: &[String]) {
+		if self.altscreen {
+			screen::leave(&self.stdout);
+			for line in lines {
+				self.stdout.write_line(line).unwrap();
+			}
+			self.stdout.flush().unwrap();
+		}
+	}
+
+	// the longest waits before a move and where each move was made, longest first
+	fn longest_pauses(&self) -> Vec<(Duration, (usize, usize))> {
+		let mut pauses: Vec<_> = self
+			.move_times
+			.iter()
+			.scan(Duration::ZERO, |last, &(at, pos)| Some((at.saturating_sub(mem::replace(last, at)), pos)))
+			.collect();
+		pauses.sort_by_key(|&(pause, _)| Reverse(pause));
+		pauses.truncate(PAUSES_SHOWN);
+		pauses
+	}
+
+	// starts over on another board, nothing carries over from the last one
+	fn replace_game(&mut self, game: MSGame) {
+		self.game = game;
+		self.fresh = self.game.clone();
+		self.move_times.clear();
+		self.attempt = 1;
+		if let Some(coop) = &mut self.coop {
+			coop.opened = [0; 2];
+		}
+		self.score = self.options.arcade.then(Score::new);
+		self.seeded = false;
+		self.ghost = None;
+		if self.recording.is_some() {
+			self.recording = Some(Replay::new(&self.options, &self.game));
+		}
+	}
+
+	// the same mines as when the board started, with every tile hidden again
+	fn retry(&mut self) {
+		self.game = self.fresh.clone();
+		self.move_times.clear();
+		self.attempt += 1;
+		if let Some(ghost) = &mut self.ghost {
+			ghost.restart();
+		}
+		self.score = self.options.arcade.then(Score::new);
+		if let Some(coop) = &mut self.coop {
+			coop.opened = [0; 2];
+		}
+		if self.recording.is_some() {
+			self.recording = Some(Replay::new(&self.options, &self.game));
+		}
+	}
+
+	// rewrites the state file for a spectator, after a failure it stops trying
+	fn publish(&mut self, start_time: SystemTime, turn: &TurnResult) {
+		let Some(path) = &self.options.publish else {
+			return;
+		};
+		if let Err(err) = spectate::publish(path, &self.game, elapsed_secs(start_time), turn) {
+			self.notices.push(format!("could not write '{}': {}", path, err));
+			self.options.publish = None;
+		}
+	}
+
+	// the name the records and the leaderboard keep this board under, variants get their own
+	fn difficulty(&self) -> String {
+		match self.options.mode() {
+			Some(mode) => format!("{}-{}", self.game.difficulty(), mode),
+			None => self.game.difficulty(),
+		}
+	}
+
+	fn status(&self, start_time: SystemTime, message: Option<String>) -> Status {
+		let mut status = Status {
+			elapsed: elapsed_secs(start_time),
+			message,
+			..Default::default()
+		};
+		status.items.extend(self.label.clone());
+		if let Some(left) = self.time_left() {
+			// 0:00 only once the time is up
+			let secs = left.as_secs_f64().ceil() as u64;
+			status.items.push(trf(Msg::TimeLeft, &[&format!("{}:{:02}", secs / 60, secs % 60)]));
+		}
+		if let Some(score) = &self.score {
+			status.items.push(score.status());
+		}
+		if let Some(race) = &self.race {
+			status.items.push(race.status());
+		}
+		if let Some(ghost) = &self.ghost {
+			let elapsed = SystemTime::now().duration_since(start_time).unwrap_or_default();
+			status.items.extend(ghost.status(self.game.safe_left, elapsed));
+			status.ghost = ghost.cursor();
+		}
+		if let Some(coop) = &self.coop {
+			status.items.push(trf(Msg::CoopStatus, &[&coop.opened[0], &coop.opened[1]]));
+		}
+		if let Placement::Gradient { from, to, .. } = self.options.placement {
+			status.items.push(trf(Msg::GradientStatus, &[&from, &to]));
+		}
+		if self.options.no_cascade {
+			status.items.push(tr(Msg::NoCascade).into());
+		}
+		if self.attempt > 1 {
+			status.items.push(trf(Msg::Attempt, &[&self.attempt]));
+		}
+		if let Some(count) = self.game.count {
+			status.items.push(trf(Msg::PendingCount, &[&count]));
+		}
+		if let Some(register) = self.macros.recording() {
+			status.items.push(trf(Msg::MacroStatus, &[&register]));
+		}
+		status
+	}
+
+	// plays a macro as if its keys were pressed, stopping when the game ends
+	// or an action can't be done and leaves a message
+	fn play_macro(&mut self, actions: Vec<Action>, start_time: SystemTime) -> TurnResult {
+		// the count is used up here, escape drops it from the game the same way in a replay
+		let count = self.game.count.take();
+		if count.is_some() {
+			if let Some(recording) = &mut self.recording {
+				recording.key(SystemTime::now().duration_since(start_time).unwrap_or_default(), Key::Escape, &self.game);
+			}
+		}
+		let times = count.unwrap_or(1).min(macros::MAX_REPEATS);
+		for action in actions.repeat(times) {
+			let turn = self.game.act(action);
+			if let Some(recording) = &mut self.recording {
+				recording.key(SystemTime::now().duration_since(start_time).unwrap_or_default(), action.key(), &self.game);
+			}
+			if !matches!(turn, TurnResult::Continue) || self.game.message.is_some() {
+				return turn;
+			}
+		}
+		TurnResult::Continue
+	}
+
+	fn draw(&self, status: &Status) -> usize {
+		if self.view.compact {
+			self.braille.draw(&self.game, &self.view, status, &self.stdout)
+		} else {
+			self.grid.draw(&self.game, &self.view, status, &self.stdout)
+		}
+	}
+}
@@ -0,0 +1,89 @@
+use std::mem;
+use std::time::{Duration, Instant};
+
+use crate::glyphs::Glyphs;
+use crate::render::{GridRenderer, MinimapCache, Status, View};
+use crate::theme::Theme;
+use crate::{MSGame, Tile, TileVis};
+
+const SIZE: usize = 1000;
+const MINES: usize = SIZE * SIZE / 5;
+const ROUNDS: usize = 5;
+
+// times setting up, flood filling and drawing a million tile board, the best of a few rounds each,
+// and the memory the tiles take
+pub fn run(glyphs: Glyphs) -> i32 {
+	let mut game = MSGame::new(SIZE, SIZE, MINES, 1).expect("the bench board fits its mines");
+	report("new board", best(|| {
+		MSGame::new(SIZE, SIZE, MINES, 1).expect("the bench board fits its mines");
+	}));
+	report("count neighbors", best(|| game.count_neighbors()));
+	// without mines one opened tile floods the whole board
+	let empty = MSGame::new(SIZE, SIZE, 0, 1).expect("an empty board fits");
+	report("flood fill", best_on(&empty, |game| {
+		game.open_at(0, 0).expect("the corner is on the board");
+	}));
+	println!(
+		"{:<16} {:>8.1} MB (a Vec<Tile> takes {:.1} MB)",
+		"board memory",
+		game.board.bytes() as f64 / 1e6,
+		(SIZE * SIZE * mem::size_of::<Tile>()) as f64 / 1e6
+	);
+
+	let grid = GridRenderer {
+		theme: Theme::by_name("classic").expect("the classic theme exists"),
+		glyphs,
+		plain: true,
+		wide: false,
+		checkerboard: false,
+		grid_lines: false,
+		center: false,
+		fog: None,
+		minimap: MinimapCache::default(),
+	};
+	let view = View::default();
+	let status = Status::default();
+	// plain output has no size, so the whole board goes in the frame
+	report("draw hidden", best(|| {
+		grid.frame(&game, &view, &status, None);
+	}));
+	for y in 0..SIZE {
+		for x in 0..SIZE {
+			let mut tile = game.get(x, y);
+			tile.visibility = TileVis::Open;
+			game.set(x, y, tile);
+		}
+	}
+	report("draw open", best(|| {
+		grid.frame(&game, &view, &status, None);
+	}));
+	0
+}
+
+fn best(mut f: impl FnMut()) -> Duration {
+	(0..ROUNDS)
+		.map(|_| {
+			let start = Instant::now();
+			f();
+			start.elapsed()
+		})
+		.min()
+		.unwrap_or_default()
+}
+
+// like best, on a fresh copy of `game` every round that isn't part of the time
+fn best_on(game: &MSGame, mut f: impl FnMut(&mut MSGame)) -> Duration {
+	(0..ROUNDS)
+		.map(|_| {
+			let mut game = game.clone();
+			let start = Instant::now();
+			f(&mut game);
+			start.elapsed()
+		})
+		.min()
+		.unwrap_or_default()
+}
+
+fn report(name: &str, time: Duration) {
+	println!("{:<16} {:>8.1} ms", name, time.as_secs_f64() * 1000.0);
+}
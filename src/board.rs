@@ -0,0 +1,133 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rand::prelude::SliceRandom;
+use rand::Rng;
+
+use crate::{Tile, TileContents, TileVis};
+
+// one byte per tile: the low 6 bits hold the contents (neighbor count, MINE or ANTI_MINE),
+// the top 2 bits hold the visibility
+const MINE: u8 = 0x3f;
+const ANTI_MINE: u8 = 0x3e;
+const CONTENTS_MASK: u8 = 0x3f;
+const VIS_SHIFT: u8 = 6;
+const HIDDEN: u8 = 0;
+const FLAG: u8 = 1;
+const OPEN: u8 = 2;
+const ANTI_FLAG: u8 = 3;
+
+// versions are handed out across all boards, so two boards with the same version are the same
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(0);
+
+fn next_version() -> u64 {
+	NEXT_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Clone)]
+pub struct Board {
+	cells: Vec<u8>,
+	// changes with every change to the tiles, for whatever is worked out from them to be kept until then
+	version: u64,
+}
+
+impl Board {
+	pub fn new(size: usize, mines: usize) -> Self {
+		let mut cells = Vec::with_capacity(size);
+		cells.resize(size.saturating_sub(mines), pack(Tile::new(false)));
+		cells.resize(size, pack(Tile::new(true)));
+		Self { cells, version: next_version() }
+	}
+
+	pub fn from_mask(mask: &[bool]) -> Self {
+		Self {
+			cells: mask.iter().map(|&mine| pack(Tile::new(mine))).collect(),
+			version: next_version(),
+		}
+	}
+
+	// moves the tiles in order onto the cells that aren't void, filling the void ones with `void_tile`
+	pub fn spread(&self, void: &[bool], void_tile: Tile) -> Self {
+		let mut tiles = self.cells.iter();
+		let cells = void
+			.iter()
+			.map(|&v| if v { pack(void_tile) } else { *tiles.next().expect("more playable cells than tiles") })
+			.collect();
+		Self { cells, version: next_version() }
+	}
+
+	pub fn shuffle<R: Rng>(&mut self, rng: &mut R) {
+		self.cells.shuffle(rng);
+		self.version = next_version();
+	}
+
+	pub fn get(&self, i: usize) -> Tile {
+		unpack(self.cells[i])
+	}
+
+	pub fn set(&mut self, i: usize, tile: Tile) {
+		let cell = pack(tile);
+		if self.cells[i] != cell {
+			self.cells[i] = cell;
+			self.version = next_version();
+		}
+	}
+
+	// either kind of mine
+	pub fn is_mine(&self, i: usize) -> bool {
+		matches!(self.cells[i] & CONTENTS_MASK, MINE | ANTI_MINE)
+	}
+
+	// replaces the number of every safe cell, keeping mines and visibility
+	pub fn set_counts(&mut self, counts: &[u8]) {
+		for (cell, &count) in self.cells.iter_mut().zip(counts) {
+			if !matches!(*cell & CONTENTS_MASK, MINE | ANTI_MINE) {
+				*cell = *cell & !CONTENTS_MASK | count & CONTENTS_MASK;
+			}
+		}
+		self.version = next_version();
+	}
+
+	pub fn version(&self) -> u64 {
+		self.version
+	}
+
+	pub fn len(&self) -> usize {
+		self.cells.len()
+	}
+
+	// the memory the tiles take, for the benchmark
+	#[cfg(feature = "tui")]
+	pub fn bytes(&self) -> usize {
+		self.cells.capacity() * std::mem::size_of::<u8>()
+	}
+}
+
+fn pack(tile: Tile) -> u8 {
+	let contents = match tile.contents {
+		TileContents::Safe(n) => n & CONTENTS_MASK,
+		TileContents::Mine => MINE,
+		TileContents::AntiMine => ANTI_MINE,
+	};
+	let vis = match tile.visibility {
+		TileVis::Hidden => HIDDEN,
+		TileVis::Flag => FLAG,
+		TileVis::Open => OPEN,
+		TileVis::AntiFlag => ANTI_FLAG,
+	};
+	contents | vis << VIS_SHIFT
+}
+
+fn unpack(cell: u8) -> Tile {
+	let contents = match cell & CONTENTS_MASK {
+		MINE => TileContents::Mine,
+		ANTI_MINE => TileContents::AntiMine,
+		n => TileContents::Safe(n),
+	};
+	let visibility = match cell >> VIS_SHIFT {
+		FLAG => TileVis::Flag,
+		OPEN => TileVis::Open,
+		ANTI_FLAG => TileVis::AntiFlag,
+		_ => TileVis::Hidden,
+	};
+	Tile { contents, visibility }
+}
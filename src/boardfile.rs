@@ -0,0 +1,312 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::MinesweeperError;
+use crate::{MSGame, TileContents, TileVis};
+
+// a board file has one line per row, with `*` for mines, `.` for safe tiles, `O` for safe tiles that
+// start opened and `X` for tiles that aren't part of the board. lines starting with `#` are comments, except `# seed <n>`
+// which records the seed the board came from
+pub fn load(path: &str) -> Result<MSGame, MinesweeperError> {
+	let bytes = fs::read(path).map_err(|err| MinesweeperError::io(path, err))?;
+	if is_mbf(path, &bytes) {
+		return parse_mbf(path, &bytes);
+	}
+	let text = String::from_utf8(bytes).map_err(|_| format!("'{}' is neither a board file nor an MBF board", path))?;
+	parse(path, &text)
+}
+
+// writes the board as an MBF board when the file ends in .mbf, as a board file otherwise
+pub fn save(game: &MSGame, path: &str) -> Result<(), MinesweeperError> {
+	let bytes = match has_mbf_extension(path) {
+		true => to_mbf(game)?,
+		false => to_text(game).into_bytes(),
+	};
+	fs::write(path, bytes).map_err(|err| format!("could not write '{}': {}", path, err).into())
+}
+
+// the MBF board format that Minesweeper Arbiter and other clones exchange boards in: the width and the height
+// in a byte each, the number of mines in two bytes (big endian), then the column and row of every mine in a byte
+// each, from 0. the format has no magic bytes, so besides the extension a file is taken for one when it is
+// exactly as long as its mine count says and has bytes no text file would
+fn is_mbf(path: &str, bytes: &[u8]) -> bool {
+	if has_mbf_extension(path) {
+		return true;
+	}
+	let [width, height, high, low, ..] = *bytes else {
+		return false;
+	};
+	let mines = u16::from_be_bytes([high, low]) as usize;
+	let text = bytes.iter().all(|&byte| byte >= b' ' || b"\t\r\n".contains(&byte));
+	width > 0 && height > 0 && bytes.len() == 4 + 2 * mines && !text
+}
+
+fn has_mbf_extension(path: &str) -> bool {
+	Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("mbf"))
+}
+
+fn parse_mbf(path: &str, bytes: &[u8]) -> Result<MSGame, MinesweeperError> {
+	let error = |reason: String| MinesweeperError::ParseBinary { path: path.into(), reason };
+	let [width, height, high, low, ref mines @ ..] = *bytes else {
+		return Err(error(format!("an MBF board starts with 4 bytes of size and mine count, the file has {}", bytes.len())));
+	};
+	let (width, height) = (width as usize, height as usize);
+	let count = u16::from_be_bytes([high, low]) as usize;
+	if width == 0 || height == 0 {
+		return Err(error(format!("the board is {}x{}, it must be at least 1x1", width, height)));
+	}
+	if mines.len() != 2 * count {
+		let problem = if mines.len() < 2 * count { "is cut off" } else { "goes on past them" };
+		return Err(error(format!("{} mines need {} bytes after the header, the file {} at {}", count, 2 * count, problem, mines.len())));
+	}
+	if count >= width * height {
+		return Err(error(format!("{} mines don't fit on a {}x{} board with a safe tile left", count, width, height)));
+	}
+	let mut mask = vec![false; width * height];
+	for (n, pos) in mines.chunks(2).enumerate() {
+		let (x, y) = (pos[0] as usize, pos[1] as usize);
+		if x >= width || y >= height {
+			return Err(error(format!("mine {} at ({}, {}) is off the {}x{} board", n + 1, x, y, width, height)));
+		}
+		if mask[x + y * width] {
+			return Err(error(format!("mine {} at ({}, {}) is there twice", n + 1, x, y)));
+		}
+		mask[x + y * width] = true;
+	}
+	Ok(MSGame::from_mask(width, height, &mask, Vec::new()))
+}
+
+pub fn to_mbf(game: &MSGame) -> Result<Vec<u8>, String> {
+	if !game.void.is_empty() {
+		return Err("MBF boards are rectangles, they can't have gaps".into());
+	}
+	let opened = |i: usize| matches!(game.board.get(i).visibility, TileVis::Open);
+	if (0..game.board.len()).any(opened) {
+		return Err("MBF boards start with every tile hidden, they can't have opened tiles".into());
+	}
+	let (Ok(width), Ok(height), Ok(count)) = (u8::try_from(game.width), u8::try_from(game.height), u16::try_from(game.mines)) else {
+		return Err(format!("MBF boards are at most 255x255 with 65535 mines, not {}", game.difficulty()));
+	};
+	let mut bytes = vec![width, height];
+	bytes.extend(count.to_be_bytes());
+	for y in 0..game.height {
+		for x in (0..game.width).filter(|&x| matches!(game.get(x, y).contents, TileContents::Mine | TileContents::AntiMine)) {
+			bytes.extend([x as u8, y as u8]);
+		}
+	}
+	Ok(bytes)
+}
+
+pub fn to_text(game: &MSGame) -> String {
+	let mut text = String::new();
+	if let Some(seed) = game.seed {
+		text.push_str(&format!("# seed {}\n", seed));
+	}
+	for y in 0..game.height {
+		for x in 0..game.width {
+			text.push(match game.get(x, y).contents {
+				_ if game.is_void(x, y) => 'X',
+				TileContents::Mine | TileContents::AntiMine => '*',
+				TileContents::Safe(_) if matches!(game.get(x, y).visibility, TileVis::Open) => 'O',
+				TileContents::Safe(_) => '.',
+			});
+		}
+		text.push('\n');
+	}
+	text
+}
+
+pub fn parse(path: &str, text: &str) -> Result<MSGame, MinesweeperError> {
+	let grid = parse_grid(path, text, &['*', '.', 'O', 'X'])?;
+	let mask: Vec<_> = grid.cells.iter().map(|&c| c == '*').collect();
+	let void = if grid.cells.contains(&'X') {
+		grid.cells.iter().map(|&c| c == 'X').collect()
+	} else {
+		Vec::new()
+	};
+	let mut game = MSGame::from_mask(grid.width, grid.height, &mask, void);
+	for (i, _) in grid.cells.iter().enumerate().filter(|(_, &c)| c == 'O') {
+		let mut tile = game.board.get(i);
+		tile.visibility = TileVis::Open;
+		game.board.set(i, tile);
+	}
+	if game.safe_hidden() == 0 {
+		return Err(format!("{}: every safe tile is opened, there is nothing left to play", path).into());
+	}
+	game.safe_left = game.safe_hidden();
+	game.seed = grid.seed;
+	Ok(game)
+}
+
+// a shape file is laid out like a board file, with `.` for tiles on the board and `X` for the rest.
+// returns the size and which tiles are void
+pub fn load_shape(path: &str) -> Result<(usize, usize, Vec<bool>), MinesweeperError> {
+	let text = fs::read_to_string(path).map_err(|err| MinesweeperError::io(path, err))?;
+	let grid = parse_grid(path, &text, &['.', 'X'])?;
+	if !grid.cells.contains(&'.') {
+		return Err(format!("{}: the shape has no tiles", path).into());
+	}
+	Ok((grid.width, grid.height, grid.cells.iter().map(|&c| c == 'X').collect()))
+}
+
+// a template is laid out like a board file, with `*` for tiles that must be mines, `.` for tiles that
+// must be safe, `?` for free tiles that get the rest of the mines and `X` for tiles that aren't on the board.
+pub struct Template {
+	pub width: usize,
+	pub height: usize,
+	// Some(true) for a mine, Some(false) for safe and None for free
+	pub pins: Vec<Option<bool>>,
+	pub void: Vec<bool>,
+}
+
+pub fn load_template(path: &str) -> Result<Template, MinesweeperError> {
+	let text = fs::read_to_string(path).map_err(|err| MinesweeperError::io(path, err))?;
+	let grid = parse_grid(path, &text, &['*', '.', '?', 'X'])?;
+	let pins = grid.cells.iter().map(|&c| match c {
+		'*' => Some(true),
+		'?' => None,
+		_ => Some(false),
+	});
+	let void = if grid.cells.contains(&'X') {
+		grid.cells.iter().map(|&c| c == 'X').collect()
+	} else {
+		Vec::new()
+	};
+	Ok(Template {
+		width: grid.width,
+		height: grid.height,
+		pins: pins.collect(),
+		void,
+	})
+}
+
+struct Grid {
+	width: usize,
+	height: usize,
+	cells: Vec<char>,
+	seed: Option<u64>,
+}
+
+fn parse_grid(path: &str, text: &str, allowed: &[char]) -> Result<Grid, MinesweeperError> {
+	let error = |line_num: usize, reason: String| MinesweeperError::ParseBoard {
+		path: path.into(),
+		line: line_num + 1,
+		reason,
+	};
+	let mut width = None;
+	let mut height = 0;
+	let mut cells = Vec::new();
+	let mut seed = None;
+
+	for (line_num, line) in text.lines().enumerate() {
+		let line = line.trim_end();
+		if let Some(value) = line.strip_prefix("# seed ") {
+			seed = value.parse().ok();
+		}
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		for c in line.chars() {
+			if !allowed.contains(&c) {
+				return Err(error(line_num, format!("unexpected character '{}'", c)));
+			}
+			cells.push(c);
+		}
+		let len = line.chars().count();
+		match width {
+			None => width = Some(len),
+			Some(width) if width != len => {
+				return Err(error(line_num, format!("expected {} tiles, found {}", width, len)));
+			},
+			_ => (),
+		}
+		height += 1;
+	}
+	let Some(width) = width else {
+		return Err(format!("{}: the file has no tiles", path).into());
+	};
+	Ok(Grid { width, height, cells, seed })
+}
+
+#[cfg(test)]
+mod tests {
+	use std::env;
+
+	use super::*;
+
+	// a board with a seed, opened tiles and a gap
+	const BOARD: &str = "# seed 42\n*..O\n.X.*\nOO..\n";
+
+	fn mbf_error(bytes: &[u8]) -> String {
+		parse_mbf("test.mbf", bytes).err().unwrap().to_string()
+	}
+
+	#[test]
+	fn text_boards_round_trip() {
+		let game = parse("test", BOARD).unwrap();
+		assert_eq!((game.width, game.height, game.mines, game.seed), (4, 3, 2, Some(42)));
+		assert_eq!(game.safe_left, 6);
+		assert_eq!(to_text(&game), BOARD);
+		// comments and trailing spaces are dropped
+		let game = parse("test", "# made by hand\n*.  \n..\n").unwrap();
+		assert_eq!(to_text(&game), "*.\n..\n");
+	}
+
+	#[test]
+	fn mbf_boards_round_trip() {
+		// 3x2 with mines at (2, 0) and (0, 1)
+		let bytes = [3, 2, 0, 2, 2, 0, 0, 1];
+		let game = parse_mbf("test.mbf", &bytes).unwrap();
+		assert_eq!(to_text(&game), "..*\n*..\n");
+		assert_eq!(to_mbf(&game).unwrap(), bytes);
+
+		// a board file written as MBF reads back as the same board
+		let game = parse("test", "*...\n...*\n.*..\n").unwrap();
+		let bytes = to_mbf(&game).unwrap();
+		assert_eq!(bytes, [4, 3, 0, 3, 0, 0, 3, 1, 1, 2]);
+		assert_eq!(to_text(&parse_mbf("test.mbf", &bytes).unwrap()), to_text(&game));
+	}
+
+	#[test]
+	fn saved_boards_load_the_same() {
+		let dir = env::temp_dir().join(format!("minesweeper-boardfile-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let game = parse("test", "*...\n...*\n.*..\n").unwrap();
+		for name in ["board.txt", "board.mbf"] {
+			let path = dir.join(name);
+			let path = path.to_str().unwrap();
+			save(&game, path).unwrap();
+			assert_eq!(to_text(&load(path).unwrap()), to_text(&game), "{}", name);
+		}
+		// an MBF board is recognized without the extension
+		fs::rename(dir.join("board.mbf"), dir.join("board")).unwrap();
+		assert_eq!(to_text(&load(dir.join("board").to_str().unwrap()).unwrap()), to_text(&game));
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn mbf_is_told_apart_from_text() {
+		assert!(is_mbf("board", &[2, 2, 0, 1, 1, 1]));
+		assert!(is_mbf("board.MBF", b"*.\n.."));
+		assert!(!is_mbf("board", b"*.\n..\n"));
+		assert!(!is_mbf("board", &[2, 2, 0, 1, 1]));
+	}
+
+	#[test]
+	fn broken_mbf_boards_are_rejected() {
+		assert_eq!(mbf_error(&[3, 2]), "test.mbf: an MBF board starts with 4 bytes of size and mine count, the file has 2");
+		assert_eq!(mbf_error(&[0, 2, 0, 0]), "test.mbf: the board is 0x2, it must be at least 1x1");
+		assert_eq!(mbf_error(&[3, 2, 0, 2, 0, 0]), "test.mbf: 2 mines need 4 bytes after the header, the file is cut off at 2");
+		assert_eq!(mbf_error(&[3, 2, 0, 1, 0, 0, 1]), "test.mbf: 1 mines need 2 bytes after the header, the file goes on past them at 3");
+		assert_eq!(mbf_error(&[1, 1, 0, 1, 0, 0]), "test.mbf: 1 mines don't fit on a 1x1 board with a safe tile left");
+		assert_eq!(mbf_error(&[3, 2, 0, 1, 3, 0]), "test.mbf: mine 1 at (3, 0) is off the 3x2 board");
+		assert_eq!(mbf_error(&[3, 2, 0, 2, 1, 1, 1, 1]), "test.mbf: mine 2 at (1, 1) is there twice");
+	}
+
+	#[test]
+	fn only_plain_rectangles_become_mbf() {
+		let err = |text: &str| to_mbf(&parse("test", text).unwrap()).unwrap_err();
+		assert_eq!(err("*X\n..\n"), "MBF boards are rectangles, they can't have gaps");
+		assert_eq!(err("*O\n..\n"), "MBF boards start with every tile hidden, they can't have opened tiles");
+	}
+}
@@ -0,0 +1,176 @@
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+use console::{Key, Term};
+
+use crate::date;
+use crate::history;
+use crate::lang::{tr, trf, Msg};
+
+// lines around the list: title, column names, page, keys and message
+const FRAME_LINES: usize = 5;
+
+// what to do with the game picked in the history
+pub enum Choice {
+	// the options that made the board, and its seed
+	Play(Vec<String>, u64),
+	Watch(String),
+}
+
+struct Entry {
+	timestamp: u64,
+	// the difficulty and the mode, like the records use
+	preset: String,
+	result: String,
+	elapsed: String,
+	seed: Option<u64>,
+	args: Vec<String>,
+	replay: Option<String>,
+}
+
+// lists the recent games, newest first. returns the game picked, None after quitting.
+// without a terminal the list is printed once instead
+pub fn run() -> Result<Option<Choice>, String> {
+	let text = history::read().map_err(|err| format!("could not read the history: {}", err))?;
+	let entries = entries(&text);
+	if entries.is_empty() {
+		return Err(tr(Msg::NoHistory).into());
+	}
+	if !io::stdout().is_terminal() {
+		for entry in &entries {
+			println!("{}", row(entry));
+		}
+		return Ok(None);
+	}
+
+	let term = Term::stdout();
+	let mut presets: Vec<_> = entries.iter().map(|entry| entry.preset.clone()).collect();
+	presets.sort();
+	presets.dedup();
+	// None shows every preset
+	let mut filter: Option<usize> = None;
+	let mut selected = 0;
+	let mut message = String::new();
+	let _ = term.hide_cursor();
+	let choice = loop {
+		let shown: Vec<_> = entries
+			.iter()
+			.filter(|entry| filter.is_none_or(|f| entry.preset == presets[f]))
+			.collect();
+		let page_size = (term.size().0 as usize).saturating_sub(FRAME_LINES).max(1);
+		let pages = shown.len().div_ceil(page_size);
+		let page = selected / page_size;
+
+		let mut frame = vec![
+			trf(Msg::HistoryTitle, &[&shown.len(), &filter.map_or(tr(Msg::AllPresets), |f| &presets[f])]),
+			format!(
+				"  {}",
+				columns(tr(Msg::ColumnDate), tr(Msg::ColumnPreset), tr(Msg::ColumnResult), tr(Msg::ColumnTime), "")
+			),
+		];
+		for (i, entry) in shown.iter().enumerate().skip(page * page_size).take(page_size) {
+			let marker = if i == selected { ">" } else { " " };
+			frame.push(format!("{} {}", marker, row(entry)));
+		}
+		frame.push(trf(Msg::HistoryPage, &[&(page + 1), &pages]));
+		frame.push(tr(Msg::HistoryKeys).into());
+		frame.push(std::mem::take(&mut message));
+		let _ = term.clear_screen();
+		let _ = term.write_str(&frame.join("\n"));
+		let _ = term.flush();
+
+		let Ok(key) = term.read_key() else {
+			break None;
+		};
+		match key {
+			Key::ArrowUp => selected = selected.saturating_sub(1),
+			Key::ArrowDown => selected = (selected + 1).min(shown.len() - 1),
+			Key::ArrowLeft | Key::PageUp => selected = selected.saturating_sub(page_size),
+			Key::ArrowRight | Key::PageDown => selected = (selected + page_size).min(shown.len() - 1),
+			Key::Char('f') => {
+				filter = match filter {
+					None => Some(0),
+					Some(f) if f + 1 < presets.len() => Some(f + 1),
+					Some(_) => None,
+				};
+				selected = 0;
+			},
+			Key::Enter => {
+				let entry = shown[selected];
+				let Some(seed) = entry.seed else {
+					message = tr(Msg::NoSeed).into();
+					continue;
+				};
+				if entry.args.is_empty() {
+					message = tr(Msg::NoOptions).into();
+					continue;
+				}
+				let replay = entry.replay.clone().filter(|path| Path::new(path).exists());
+				let _ = term.write_str(&format!("\r{}", tr(if replay.is_some() { Msg::PlayOrWatch } else { Msg::PlayAgain })));
+				let _ = term.flush();
+				match (term.read_key(), replay) {
+					(Ok(Key::Char('p')), _) => break Some(Choice::Play(entry.args.clone(), seed)),
+					(Ok(Key::Char('w')), Some(path)) => break Some(Choice::Watch(path)),
+					_ => (),
+				}
+			},
+			Key::Escape | Key::Char('q') => break None,
+			_ => (),
+		}
+	};
+	let _ = term.clear_screen();
+	let _ = term.show_cursor();
+	Ok(choice)
+}
+
+fn row(entry: &Entry) -> String {
+	let result = match entry.result.as_str() {
+		"win" => tr(Msg::ResultWin),
+		"loss" => tr(Msg::ResultLoss),
+		"surrender" => tr(Msg::ResultSurrender),
+		_ => tr(Msg::ResultQuit),
+	};
+	let time = format!("{}s", entry.elapsed);
+	let replay = if entry.replay.is_some() { tr(Msg::HasReplay) } else { "" };
+	columns(&date::minutes(entry.timestamp), &entry.preset, result, &time, replay)
+}
+
+fn columns(date: &str, preset: &str, result: &str, time: &str, replay: &str) -> String {
+	format!("{:<16}  {:<24}  {:<8}  {:>8}  {}", date, preset, result, time, replay)
+}
+
+// the columns are looked up by name, so files from before a column was added still read
+fn entries(text: &str) -> Vec<Entry> {
+	let mut lines = text.lines();
+	let columns: Vec<_> = lines.next().unwrap_or_default().split(',').collect();
+	let mut entries: Vec<_> = lines
+		.filter(|line| !line.is_empty())
+		.map(|line| {
+			let fields = history::fields(line);
+			let get = |name: &str| {
+				let i = columns.iter().position(|&c| c == name);
+				i.and_then(|i| fields.get(i)).map_or("", String::as_str)
+			};
+			let size = format!("{}x{}x{}", get("width"), get("height"), get("mines"));
+			let mut args: Vec<_> = get("options").split_whitespace().map(String::from).collect();
+			// older games only know their size, which is all there is to a game without a mode
+			if args.is_empty() && get("mode").is_empty() {
+				args = ["--width", get("width"), "--height", get("height"), "--mines", get("mines")].map(String::from).into();
+			}
+			Entry {
+				timestamp: get("timestamp").parse().unwrap_or_default(),
+				preset: match get("mode") {
+					"" => size,
+					mode => format!("{}-{}", size, mode),
+				},
+				result: get("result").into(),
+				elapsed: get("elapsed").into(),
+				seed: get("seed").parse().ok(),
+				args,
+				replay: Some(get("replay").to_string()).filter(|path| !path.is_empty()),
+			}
+		})
+		.collect();
+	entries.reverse();
+	entries
+}
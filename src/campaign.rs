@@ -0,0 +1,256 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::app::App;
+use crate::glyphs::Glyphs;
+use crate::json;
+use crate::lang::{tr, trf, Msg};
+use crate::options::Options;
+use crate::records::data_dir;
+use crate::TurnResult;
+
+// width, height and mines of each level. the boards grow a little at a time and get denser,
+// from one mine in eight tiles to one in five
+const LEVELS: [(usize, usize, usize); 10] = [
+	(5, 5, 3),
+	(6, 6, 5),
+	(8, 8, 9),
+	(9, 9, 12),
+	(10, 10, 16),
+	(12, 12, 24),
+	(14, 12, 30),
+	(16, 14, 40),
+	(16, 16, 48),
+	(20, 16, 64),
+];
+const LIVES: usize = 3;
+
+// how far a campaign has come, kept in the data directory between sessions:
+//
+//   {"version": 1, "level": 3, "lives": 2, "elapsed": 95.4}
+//
+// levels count from 1, the elapsed time is every game played in the campaign added up
+struct Campaign {
+	level: usize,
+	lives: usize,
+	elapsed: Duration,
+}
+
+impl Campaign {
+	fn new() -> Self {
+		Self {
+			level: 1,
+			lives: LIVES,
+			elapsed: Duration::ZERO,
+		}
+	}
+
+	// the saved campaign, None if there isn't one
+	fn load() -> Result<Option<Self>, String> {
+		let Some(path) = path() else {
+			return Ok(None);
+		};
+		let Ok(text) = fs::read_to_string(&path) else {
+			return Ok(None);
+		};
+		Self::parse(&text).map(Some).map_err(|()| format!("'{}' {}", path.display(), tr(Msg::CampaignInvalid)))
+	}
+
+	fn parse(text: &str) -> Result<Self, ()> {
+		let state = json::parse(text).map_err(|_| ())?;
+		if state.get("version") != Some(&json::Value::Number(1.0)) {
+			return Err(());
+		}
+		let field = |name: &str| state.get(name).and_then(json::Value::as_usize).ok_or(());
+		let (level, lives) = (field("level")?, field("lives")?);
+		if !(1..=LEVELS.len()).contains(&level) || !(1..=LIVES).contains(&lives) {
+			return Err(());
+		}
+		let elapsed = match state.get("elapsed") {
+			Some(json::Value::Number(secs)) if *secs >= 0.0 => Duration::from_secs_f64(*secs),
+			_ => return Err(()),
+		};
+		Ok(Self { level, lives, elapsed })
+	}
+
+	fn save(&self) -> Result<(), String> {
+		let path = path().ok_or("no data directory for the campaign")?;
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir).map_err(|err| format!("could not create '{}': {}", dir.display(), err))?;
+		}
+		fs::write(&path, self.to_json()).map_err(|err| format!("could not write '{}': {}", path.display(), err))
+	}
+
+	fn to_json(&self) -> String {
+		format!(
+			"{{\"version\": 1, \"level\": {}, \"lives\": {}, \"elapsed\": {:.3}}}\n",
+			self.level,
+			self.lives,
+			self.elapsed.as_secs_f64(),
+		)
+	}
+
+	// a finished campaign starts over from the first level next time
+	fn clear() {
+		if let Some(path) = path() {
+			let _ = fs::remove_file(path);
+		}
+	}
+
+	fn secs(&self) -> String {
+		format!("{:.1}", self.elapsed.as_secs_f64())
+	}
+}
+
+fn path() -> Option<PathBuf> {
+	Some(data_dir()?.join("campaign.json"))
+}
+
+fn size(level: usize) -> String {
+	let (width, height, mines) = LEVELS[level - 1];
+	format!("{}x{}x{}", width, height, mines)
+}
+
+// plays the levels from where the saved campaign left off until they are all cleared,
+// the lives run out or the player stops, returns the exit code
+pub fn run(mut options: Options, glyphs: Glyphs) -> i32 {
+	let saved = match Campaign::load() {
+		Ok(saved) => saved,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	let resumed = saved.is_some();
+	let mut campaign = saved.unwrap_or_else(Campaign::new);
+	(options.width, options.height, options.mines) = LEVELS[campaign.level - 1];
+	let mut app = match App::new(options, glyphs, None) {
+		Ok(app) => app,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	if resumed {
+		let line = trf(Msg::CampaignResumed, &[&campaign.level, &campaign.lives]);
+		if !app.interlude(Duration::ZERO, &[line], Msg::PressContinue) {
+			app.close(&[]);
+			return 0;
+		}
+	}
+	loop {
+		app.set_label(Some(trf(Msg::CampaignStatus, &[&campaign.level, &LEVELS.len(), &campaign.lives])));
+		let outcome = app.run();
+		let elapsed = outcome.elapsed;
+		let result = match outcome.result {
+			TurnResult::Win => TurnResult::Win,
+			TurnResult::Quit => TurnResult::Quit,
+			// giving up on a level costs a life like losing it
+			_ => TurnResult::Lose,
+		};
+		app.report(outcome);
+		campaign.elapsed += elapsed;
+		let line = match result {
+			TurnResult::Quit => {
+				return stop(&app, &campaign);
+			},
+			TurnResult::Win if campaign.level == LEVELS.len() => {
+				Campaign::clear();
+				let lines = [trf(Msg::CampaignComplete, &[&LEVELS.len(), &campaign.secs()])];
+				app.interlude(elapsed, &lines, Msg::PressExit);
+				app.close(&lines);
+				return 0;
+			},
+			TurnResult::Win => {
+				campaign.level += 1;
+				let secs = format!("{:.1}", elapsed.as_secs_f64());
+				trf(Msg::CampaignCleared, &[&(campaign.level - 1), &secs, &size(campaign.level)])
+			},
+			_ if campaign.lives == 1 => {
+				Campaign::clear();
+				let lines = [trf(Msg::CampaignOver, &[&(campaign.level - 1), &LEVELS.len(), &campaign.secs()])];
+				app.interlude(elapsed, &lines, Msg::PressExit);
+				app.close(&lines);
+				return 0;
+			},
+			_ => {
+				campaign.lives -= 1;
+				trf(Msg::CampaignLifeLost, &[&campaign.lives, &campaign.level])
+			},
+		};
+		if let Err(err) = campaign.save() {
+			eprintln!("{}", err);
+		}
+		if !app.interlude(elapsed, &[line], Msg::PressContinue) {
+			return stop(&app, &campaign);
+		}
+		let (width, height, mines) = LEVELS[campaign.level - 1];
+		if let Err(err) = app.set_board(width, height, mines) {
+			app.close(&[]);
+			eprintln!("{}", err);
+			return 2;
+		}
+	}
+}
+
+// saves the campaign to go on with next time and puts the terminal back
+fn stop(app: &App, campaign: &Campaign) -> i32 {
+	app.close(&[]);
+	match campaign.save() {
+		Ok(()) => {
+			println!("{}", trf(Msg::CampaignSaved, &[&campaign.level, &campaign.lives, &campaign.secs()]));
+			0
+		},
+		Err(err) => {
+			eprintln!("{}", err);
+			1
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::MSGame;
+
+	#[test]
+	fn levels_grow_and_get_denser() {
+		for pair in LEVELS.windows(2) {
+			let ((w1, h1, m1), (w2, h2, m2)) = (pair[0], pair[1]);
+			assert!(w2 * h2 > w1 * h1 && m2 > m1, "{:?} then {:?}", pair[0], pair[1]);
+			assert!(m2 * w1 * h1 >= m1 * w2 * h2, "{:?} then {:?}", pair[0], pair[1]);
+		}
+		for (width, height, mines) in LEVELS {
+			// the first level rounds down to 3 mines, a little under one in eight
+			assert!(mines * 9 >= width * height && mines * 5 <= width * height, "{}x{}x{}", width, height, mines);
+			assert!(MSGame::new(width, height, mines, 1).is_ok());
+		}
+		assert_eq!(size(1), "5x5x3");
+		assert_eq!(size(LEVELS.len()), "20x16x64");
+	}
+
+	#[test]
+	fn saved_campaigns_read_back() {
+		let campaign = Campaign { level: 4, lives: 2, elapsed: Duration::from_millis(95_400) };
+		assert_eq!(campaign.to_json(), "{\"version\": 1, \"level\": 4, \"lives\": 2, \"elapsed\": 95.400}\n");
+		let read = Campaign::parse(&campaign.to_json()).unwrap();
+		assert_eq!((read.level, read.lives, read.elapsed), (4, 2, Duration::from_millis(95_400)));
+		assert_eq!(read.secs(), "95.4");
+	}
+
+	#[test]
+	fn broken_campaigns_are_refused() {
+		for text in [
+			"{\"version\": 2, \"level\": 4, \"lives\": 2, \"elapsed\": 1}",
+			"{\"version\": 1, \"level\": 0, \"lives\": 2, \"elapsed\": 1}",
+			"{\"version\": 1, \"level\": 11, \"lives\": 2, \"elapsed\": 1}",
+			"{\"version\": 1, \"level\": 4, \"lives\": 4, \"elapsed\": 1}",
+			"{\"version\": 1, \"level\": 4, \"lives\": 2, \"elapsed\": -1}",
+			"{\"version\": 1, \"level\": 4, \"lives\": 2}",
+			"not json",
+		] {
+			assert!(Campaign::parse(text).is_err(), "{}", text);
+		}
+	}
+}
@@ -0,0 +1,123 @@
+use std::fmt::Write as _;
+use std::fs;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::action::Action;
+use crate::coop::Coop;
+use crate::glyphs::Glyphs;
+use crate::json;
+use crate::lang::{tr, trf, Msg};
+use crate::options::{apply_variants, new_game, Options};
+use crate::render::{GridRenderer, MinimapCache, Status, View};
+use crate::replay::{self, Replay};
+use crate::theme::Theme;
+use crate::{date, TurnResult};
+
+// the smallest terminal the cast asks for, the usual default size
+const MIN_COLS: usize = 80;
+const MIN_ROWS: usize = 24;
+
+// draws every move of a replay the way the game would have and writes the frames to an
+// asciinema v2 cast file, returns the exit code
+pub fn export(replay: &Replay, options: &Options, glyphs: Glyphs, path: &str) -> i32 {
+	match cast(replay, options, glyphs) {
+		Ok(text) => match fs::write(path, text) {
+			Ok(()) => {
+				println!("wrote {}", path);
+				0
+			},
+			Err(err) => {
+				eprintln!("could not write '{}': {}", path, err);
+				1
+			},
+		},
+		Err(err) => {
+			eprintln!("{}", err);
+			2
+		},
+	}
+}
+
+fn cast(replay: &Replay, options: &Options, glyphs: Glyphs) -> Result<String, String> {
+	let mut game = new_game(options).map_err(|err| err.to_string())?;
+	apply_variants(&mut game, options).map_err(|err| err.to_string())?;
+	let coop = options.coop.then(|| Coop::new(&options.coop_keys));
+	let mut rush_rng = StdRng::seed_from_u64(game.seed.unwrap_or_default());
+	let grid = GridRenderer {
+		theme: Theme::by_name(&options.theme)?,
+		glyphs,
+		plain: false,
+		wide: options.wide,
+		checkerboard: options.checkerboard,
+		grid_lines: options.grid_lines,
+		center: options.center,
+		fog: options.fog,
+		minimap: MinimapCache::default(),
+	};
+	let mut view = View {
+		rates: options.rates,
+		minimap: options.minimap,
+		dim_satisfied: options.dim_satisfied,
+		highlight: options.highlight,
+		frontier: options.frontier,
+		transpose: options.transpose.unwrap_or(false),
+		..Default::default()
+	};
+	let mut status = Status::default();
+	// room for the whole board, with the gaps between tiles and a line to spare
+	let cols = MIN_COLS.max(game.width * 2 + 1);
+	let rows = MIN_ROWS.max(game.height + 4);
+	let size = Some((rows, cols));
+	// the frames come out colored whether or not this is printed to a terminal
+	console::set_colors_enabled(true);
+
+	let mut text = format!(
+		"{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}, \"title\": \"minesweeper {}\"}}\n",
+		cols, rows, date::now(), game.difficulty(),
+	);
+	push_frame(&mut text, 0, &grid.frame(&game, &view, &status, size).0);
+	let mut result = TurnResult::Continue;
+	let mut last_ms = 0;
+	for (ms, key) in replay.moves() {
+		match key {
+			Some(key) => match Action::from_key(key) {
+				// the view toggles are drawn the same as in the game, the compact view isn't in casts
+				Some(Action::ToggleMinimap) => view.minimap = !view.minimap,
+				Some(Action::ToggleDim) => view.dim_satisfied = !view.dim_satisfied,
+				Some(Action::ToggleHighlight) => view.highlight = !view.highlight,
+				Some(Action::ToggleRates) => view.rates = !view.rates,
+				Some(Action::ToggleFrontier) => view.frontier = !view.frontier,
+				Some(Action::ToggleTranspose) => view.transpose = !view.transpose,
+				_ => result = replay::play_key(&mut game, coop.as_ref(), key),
+			},
+			None => {
+				game.add_mine(&mut rush_rng);
+			},
+		}
+		game.events.clear();
+		status.elapsed = ms / 1000;
+		status.message = game.message.take();
+		status.fog_lifted = !matches!(result, TurnResult::Continue);
+		push_frame(&mut text, ms, &grid.frame(&game, &view, &status, size).0);
+		last_ms = ms;
+		if !matches!(result, TurnResult::Continue) {
+			break;
+		}
+	}
+	let ending = match result {
+		TurnResult::Win => tr(Msg::YouWin).to_string(),
+		TurnResult::Lose | TurnResult::Surrender => tr(Msg::GameOver).to_string(),
+		_ => trf(Msg::QuitAfter, &[&format!("{:.1}", last_ms as f64 / 1000.0)]),
+	};
+	let (screen, _) = grid.frame(&game, &view, &status, size);
+	push_frame(&mut text, last_ms + 1000, &format!("{}{}\n", screen, ending));
+	Ok(text)
+}
+
+// one output event that clears the screen and draws `screen`, terminals in raw mode need \r\n
+fn push_frame(text: &mut String, ms: u64, screen: &str) {
+	let screen = format!("\x1b[H\x1b[2J{}", screen.replace('\n', "\r\n"));
+	writeln!(text, "[{:.3}, \"o\", \"{}\"]", ms as f64 / 1000.0, json::escape(&screen)).unwrap();
+}
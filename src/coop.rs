@@ -0,0 +1,105 @@
+use console::Key;
+
+use crate::action::Action;
+use crate::action::Direction;
+
+// up, left, down, right, open and flag for the second player, none of them taken by the first one
+pub const DEFAULT_KEYS: &str = "okl;ju";
+
+// two players on one keyboard, each with their own cursor on the same board.
+// the first player keeps the normal keys, the second one gets six keys of their own
+pub struct Coop {
+	keys: Vec<char>,
+	// safe tiles each player has opened, flood fills included
+	pub opened: [usize; 2],
+}
+
+impl Coop {
+	// `keys` has already been checked by `check_keys`
+	pub fn new(keys: &str) -> Self {
+		Self {
+			keys: keys.chars().collect(),
+			opened: [0; 2],
+		}
+	}
+
+	// the second player's key that moves the same way on the board as `key` does on a transposed one,
+	// None for the first player's keys
+	pub fn transposed(&self, key: &Key) -> Option<Key> {
+		let Key::Char(c) = key else {
+			return None;
+		};
+		let i = self.keys.iter().position(|k| k == c)?;
+		// up and left trade places, and so do down and right
+		let i = match i {
+			0..=3 => i ^ 1,
+			_ => i,
+		};
+		Some(Key::Char(self.keys[i]))
+	}
+
+	// what a key does for the second player, None for the first player's keys
+	pub fn action(&self, key: &Key) -> Option<Action> {
+		let Key::Char(c) = key else {
+			return None;
+		};
+		let action = match self.keys.iter().position(|k| k == c)? {
+			0 => Action::Move(Direction::Up),
+			1 => Action::Move(Direction::Left),
+			2 => Action::Move(Direction::Down),
+			3 => Action::Move(Direction::Right),
+			4 => Action::Open,
+			_ => Action::Flag,
+		};
+		Some(action)
+	}
+}
+
+pub fn check_keys(keys: &str) -> Result<(), String> {
+	let chars: Vec<char> = keys.chars().collect();
+	let distinct = chars.iter().enumerate().all(|(i, c)| !chars[..i].contains(c));
+	if chars.len() != 6 || !distinct {
+		return Err(format!("--coop-keys needs 6 different keys (up, left, down, right, open, flag), not '{}'", keys));
+	}
+	if chars.iter().any(|c| c.is_ascii_digit()) {
+		return Err("--coop-keys can't use digits, they are counts".into());
+	}
+	// the second player's keys come first, a shared one would take an action away from the first player
+	if let Some(c) = chars.iter().find(|&&c| Action::from_key(&Key::Char(c)).is_some()) {
+		return Err(format!("--coop-keys can't use '{}', the first player has it", c));
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn default_keys_are_free() {
+		assert_eq!(check_keys(DEFAULT_KEYS), Ok(()));
+	}
+
+	#[test]
+	fn keys_are_checked() {
+		assert!(check_keys("okl;j").is_err());
+		assert!(check_keys("okl;jj").is_err());
+		assert_eq!(check_keys("okl;j1"), Err("--coop-keys can't use digits, they are counts".into()));
+		assert_eq!(check_keys("wasder"), Err("--coop-keys can't use 'a', the first player has it".into()));
+	}
+
+	#[test]
+	fn keys_act_for_the_second_player() {
+		let coop = Coop::new(DEFAULT_KEYS);
+		assert!(coop.action(&Key::Char('o')) == Some(Action::Move(Direction::Up)));
+		assert!(coop.action(&Key::Char(';')) == Some(Action::Move(Direction::Right)));
+		assert!(coop.action(&Key::Char('j')) == Some(Action::Open));
+		assert!(coop.action(&Key::Char('u')) == Some(Action::Flag));
+		assert!(coop.action(&Key::Char('f')).is_none());
+		assert!(coop.action(&Key::ArrowUp).is_none());
+		// up and left trade places on a transposed board
+		assert_eq!(coop.transposed(&Key::Char('o')), Some(Key::Char('k')));
+		assert_eq!(coop.transposed(&Key::Char('l')), Some(Key::Char(';')));
+		assert_eq!(coop.transposed(&Key::Char('j')), Some(Key::Char('j')));
+	}
+}
@@ -0,0 +1,42 @@
+use std::time::SystemTime;
+
+// dates in UTC from unix time, without a time zone database
+
+pub fn now() -> u64 {
+	SystemTime::now()
+		.duration_since(SystemTime::UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_secs()
+}
+
+// YYYY-MM-DD
+pub fn date(secs: u64) -> String {
+	let (year, month, day) = civil(secs / 86400);
+	format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// YYYY-MM-DD HH:MM
+pub fn minutes(secs: u64) -> String {
+	let time = secs % 86400;
+	format!("{} {:02}:{:02}", date(secs), time / 3600, time / 60 % 60)
+}
+
+// YYYY-MM-DDTHH:MM:SSZ
+pub fn rfc3339(secs: u64) -> String {
+	let time = secs % 86400;
+	format!("{}T{:02}:{:02}:{:02}Z", date(secs), time / 3600, time / 60 % 60, time % 60)
+}
+
+// days since 1970 to a year, month and day, from Howard Hinnant's date algorithms
+fn civil(days: u64) -> (i64, i64, i64) {
+	let z = days as i64 + 719468;
+	let era = z.div_euclid(146097);
+	let doe = z.rem_euclid(146097);
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + (month <= 2) as i64;
+	(year, month, day)
+}
@@ -0,0 +1,44 @@
+use std::env;
+use std::io;
+use std::io::IsTerminal;
+
+use console::Key;
+use console::Term;
+
+use crate::glyphs::Glyphs;
+
+// prints what was detected about the terminal, then echoes key presses until q,
+// for including in bug reports
+pub fn run(glyphs: &Glyphs) {
+	let stdout = Term::stdout();
+	let features = stdout.features();
+	println!("os: {} ({})", env::consts::OS, env::consts::FAMILY);
+	println!("TERM: {}", env::var("TERM").unwrap_or_else(|_| "(unset)".into()));
+	println!("stdout is a terminal: {}", io::stdout().is_terminal());
+	println!("stdin is a terminal: {}", io::stdin().is_terminal());
+	println!("attended: {}", features.is_attended());
+	println!("msys tty: {}", features.is_msys_tty());
+	println!("colors supported: {}", features.colors_supported());
+	println!("colors enabled: {}", console::colors_enabled());
+	println!("wants emoji: {}", features.wants_emoji());
+	println!("glyphs: {}", if glyphs.unicode { "unicode" } else { "ascii" });
+	match stdout.size_checked() {
+		Some((rows, cols)) => println!("size: {} columns, {} rows", cols, rows),
+		None => println!("size: unknown"),
+	}
+	if !features.is_attended() {
+		return;
+	}
+	println!();
+	println!("press keys to see how they are read, q to stop");
+	loop {
+		match stdout.read_key() {
+			Ok(Key::Char('q')) => break,
+			Ok(key) => println!("{:?}", key),
+			Err(err) => {
+				println!("error reading key: {}", err);
+				break;
+			},
+		}
+	}
+}
@@ -0,0 +1,229 @@
+use std::io::{self, IsTerminal};
+
+use console::{Key, Term};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+
+use crate::action::Action;
+use crate::glyphs::Glyphs;
+use crate::lang::{tr, trf, Msg};
+use crate::options::Options;
+use crate::render::{self, GridRenderer, MinimapCache, Renderer, Status, View};
+use crate::theme::Theme;
+use crate::{input, screen, MSGame, TileVis};
+
+// a position with a pattern on its frontier, one line per row:
+// `O` for an opened tile (its number comes from the mines around it), `*` for a mine the numbers prove
+// and `.` for a safe tile they prove, which together are the answer, and `x` for a mine and `#` for a safe
+// tile the numbers don't decide. the edges of a drill are the edges of the board
+struct Drill {
+	name: &'static str,
+	rows: &'static [&'static str],
+}
+
+const DRILLS: [Drill; 5] = [
+	Drill {
+		name: "1-1",
+		rows: &["x#.", "OO."],
+	},
+	Drill {
+		name: "1-2",
+		rows: &["#x*#", "OOO#"],
+	},
+	Drill {
+		name: "1-2-1",
+		rows: &["*.*", "OOO"],
+	},
+	Drill {
+		name: "1-2-2-1",
+		rows: &[".**.", "OOOO"],
+	},
+	Drill {
+		name: "1-2-1-edge",
+		rows: &["*.*.#", "OOOOx"],
+	},
+];
+
+// one dealt drill, turned and flipped at random
+struct Instance {
+	game: MSGame,
+	// the character of each tile in the drill, in the order of the board
+	cells: Vec<char>,
+}
+
+impl Drill {
+	fn deal(&self, rng: &mut StdRng) -> Instance {
+		let (height, width) = (self.rows.len(), self.rows[0].len());
+		let (transpose, flip_x, flip_y) = (rng.gen(), rng.gen(), rng.gen());
+		let (width, height) = if transpose { (height, width) } else { (width, height) };
+		let mut cells = Vec::with_capacity(width * height);
+		for y in 0..height {
+			for x in 0..width {
+				let x = if flip_x { width - 1 - x } else { x };
+				let y = if flip_y { height - 1 - y } else { y };
+				let (col, row) = if transpose { (y, x) } else { (x, y) };
+				cells.push(self.rows[row].as_bytes()[col] as char);
+			}
+		}
+		let mask: Vec<_> = cells.iter().map(|&c| c == '*' || c == 'x').collect();
+		let mut game = MSGame::from_mask(width, height, &mask, Vec::new());
+		for (i, _) in cells.iter().enumerate().filter(|(_, &c)| c == 'O') {
+			let mut tile = game.board.get(i);
+			tile.visibility = TileVis::Open;
+			game.board.set(i, tile);
+		}
+		game.safe_left = game.safe_hidden();
+		// the mines in the corners the numbers don't reach would give the answer away
+		game.blind = true;
+		// start on the first tile of the answer
+		if let Some(i) = cells.iter().position(|&c| c == '*' || c == '.') {
+			(game.cursor_x, game.cursor_y) = (i % width, i / width);
+		}
+		Instance { game, cells }
+	}
+}
+
+impl Instance {
+	// whether the board is right so far: Some(None) once the answer is complete, Some(Some(why)) after a mistake
+	fn verdict(&self) -> Option<Option<Msg>> {
+		let mut done = true;
+		for (i, &c) in self.cells.iter().enumerate() {
+			let visibility = self.game.board.get(i).visibility;
+			match (c, visibility) {
+				('*' | 'x', TileVis::Open) => return Some(Some(Msg::DrillMine)),
+				('#' | 'x', TileVis::Open | TileVis::Flag) => return Some(Some(Msg::DrillGuess)),
+				('.', TileVis::Flag) => return Some(Some(Msg::DrillSafe)),
+				('*', TileVis::Flag) | ('.', TileVis::Open) => (),
+				('*' | '.', _) => done = false,
+				_ => (),
+			}
+		}
+		done.then_some(None)
+	}
+
+	// flags and opens the answer, next to whatever the mistake was
+	fn show_answer(&mut self) {
+		for (i, &c) in self.cells.iter().enumerate() {
+			let mut tile = self.game.board.get(i);
+			match (c, tile.visibility) {
+				('*', TileVis::Hidden) => tile.visibility = TileVis::Flag,
+				('.', TileVis::Hidden) => tile.visibility = TileVis::Open,
+				_ => continue,
+			}
+			self.game.board.set(i, tile);
+		}
+	}
+}
+
+// deals drills of the named pattern, or of all of them mixed, until q is pressed. returns the exit code
+pub fn run(name: &str, options: &Options, glyphs: Glyphs) -> i32 {
+	let picked: Vec<_> = match name {
+		"all" => (0..DRILLS.len()).collect(),
+		_ => match DRILLS.iter().position(|drill| drill.name == name) {
+			Some(i) => vec![i],
+			None => {
+				let names: Vec<_> = DRILLS.iter().map(|drill| drill.name).collect();
+				eprintln!("unknown drill '{}', available drills: all, {}", name, names.join(", "));
+				return 2;
+			},
+		},
+	};
+	let theme = match Theme::by_name(&options.theme) {
+		Ok(theme) => theme,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	let plain = render::plain_output(options.plain);
+	if plain {
+		console::set_colors_enabled(false);
+	}
+	let grid = GridRenderer {
+		theme,
+		glyphs,
+		plain,
+		wide: options.wide,
+		checkerboard: options.checkerboard,
+		grid_lines: false,
+		center: options.center,
+		fog: None,
+		minimap: MinimapCache::default(),
+	};
+	let view = View {
+		dim_satisfied: options.dim_satisfied,
+		highlight: options.highlight,
+		frontier: options.frontier,
+		transpose: options.transpose.unwrap_or(false),
+		..Default::default()
+	};
+	let stdout = Term::buffered_stdout();
+	let altscreen = options.altscreen && !plain;
+	if altscreen {
+		screen::enter(&stdout);
+	}
+	let keys = if io::stdin().is_terminal() { input::spawn_reader() } else { input::spawn_command_reader() };
+	let mut rng = StdRng::seed_from_u64(options.seed.unwrap_or_else(|| thread_rng().gen()));
+	// right answers and drills dealt, for each pattern
+	let mut tally = vec![(0, 0); DRILLS.len()];
+	'drills: loop {
+		let index = picked[rng.gen_range(0..picked.len())];
+		let drill = &DRILLS[index];
+		let mut instance = drill.deal(&mut rng);
+		let mut message = trf(Msg::DrillPrompt, &[&drill.name]);
+		let verdict = loop {
+			let (right, dealt) = tally[index];
+			let status = drill_status(message.clone(), trf(Msg::DrillTally, &[&right, &dealt]));
+			grid.draw(&instance.game, &view, &status, &stdout);
+			let Ok(key) = keys.recv() else {
+				break 'drills;
+			};
+			// only moving, opening and flagging make sense on a drill
+			match Action::from_key(&key) {
+				Some(Action::Quit) => break 'drills,
+				Some(action @ (Action::Move(_) | Action::Jump(_) | Action::Open | Action::Flag)) => {
+					instance.game.act(action);
+					instance.game.events.clear();
+					message = instance.game.message.take().unwrap_or(message);
+				},
+				_ => (),
+			}
+			if let Some(verdict) = instance.verdict() {
+				break verdict;
+			}
+		};
+		tally[index].1 += 1;
+		let message = match verdict {
+			None => {
+				tally[index].0 += 1;
+				tr(Msg::DrillRight).to_string()
+			},
+			Some(why) => {
+				instance.show_answer();
+				trf(Msg::DrillWrong, &[&tr(why)])
+			},
+		};
+		let (right, dealt) = tally[index];
+		grid.draw(&instance.game, &view, &drill_status(message, trf(Msg::DrillTally, &[&right, &dealt])), &stdout);
+		if matches!(keys.recv(), Ok(Key::Char('q') | Key::Escape) | Err(_)) {
+			break;
+		}
+	}
+	if altscreen {
+		screen::leave(&stdout);
+	}
+	for (drill, (right, dealt)) in DRILLS.iter().zip(tally).filter(|(_, (_, dealt))| *dealt > 0) {
+		let line = trf(Msg::DrillResult, &[&drill.name, &right, &dealt, &(100 * right / dealt)]);
+		stdout.write_line(&line).unwrap();
+	}
+	stdout.flush().unwrap();
+	0
+}
+
+fn drill_status(message: String, tally: String) -> Status {
+	Status {
+		message: Some(message),
+		items: vec![tally],
+		..Default::default()
+	}
+}
@@ -0,0 +1,195 @@
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+use console::{Key, Term};
+
+use crate::action::Action;
+use crate::boardfile;
+use crate::glyphs::Glyphs;
+use crate::lang::{tr, trf, Msg};
+use crate::options::Options;
+use crate::render::{self, GridRenderer, MinimapCache, Renderer, Status, View};
+use crate::theme::Theme;
+use crate::{input, pos_label, screen, MSGame, TileVis};
+
+// the board being made, kept apart from the game it is drawn as
+struct Draft {
+	width: usize,
+	height: usize,
+	mines: Vec<bool>,
+	// safe tiles the game starts with opened
+	opened: Vec<bool>,
+	// empty for a full rectangle
+	void: Vec<bool>,
+}
+
+impl Draft {
+	fn empty(width: usize, height: usize) -> Self {
+		Self {
+			width,
+			height,
+			mines: vec![false; width * height],
+			opened: vec![false; width * height],
+			void: Vec::new(),
+		}
+	}
+
+	fn load(path: &str) -> Result<Self, String> {
+		let game = boardfile::load(path).map_err(|err| err.to_string())?;
+		let tiles = 0..game.board.len();
+		// void tiles are stored opened
+		let opened = |i| matches!(game.board.get(i).visibility, TileVis::Open) && !game.void.get(i).copied().unwrap_or(false);
+		Ok(Self {
+			width: game.width,
+			height: game.height,
+			mines: tiles.clone().map(|i| game.board.is_mine(i)).collect(),
+			opened: tiles.map(opened).collect(),
+			void: game.void.clone(),
+		})
+	}
+
+	// the draft as a game, with the numbers counted again: mines are drawn as flags, and a mine
+	// that is also opened as the mine it would be
+	fn game(&self) -> MSGame {
+		let mut game = MSGame::from_mask(self.width, self.height, &self.mines, self.void.clone());
+		for i in (0..game.board.len()).filter(|&i| self.mines[i] || self.opened[i]) {
+			let mut tile = game.board.get(i);
+			tile.visibility = if self.opened[i] { TileVis::Open } else { TileVis::Flag };
+			game.board.set(i, tile);
+		}
+		game.safe_left = game.safe_hidden();
+		game
+	}
+
+	// why the draft can't be played, if it can't
+	fn problem(&self) -> Option<String> {
+		if let Some(i) = (0..self.mines.len()).find(|&i| self.mines[i] && self.opened[i]) {
+			return Some(trf(Msg::EditorOpenedMine, &[&pos_label(i % self.width, i / self.width)]));
+		}
+		let playable = |i: &usize| !self.void.get(*i).copied().unwrap_or(false);
+		if (0..self.mines.len()).filter(playable).all(|i| self.mines[i] || self.opened[i]) {
+			return Some(tr(Msg::EditorNothingLeft).into());
+		}
+		None
+	}
+}
+
+// edits the board file at `path`, or a new empty board of the size in the options when there is none yet.
+// returns the exit code
+pub fn run(path: &str, options: &Options, glyphs: Glyphs) -> i32 {
+	let mut draft = match Path::new(path).exists() {
+		true => match Draft::load(path) {
+			Ok(draft) => draft,
+			Err(err) => {
+				eprintln!("{}", err);
+				return 2;
+			},
+		},
+		false if options.width == 0 || options.height == 0 => {
+			eprintln!("the board must be at least 1x1");
+			return 2;
+		},
+		false => Draft::empty(options.width, options.height),
+	};
+	let theme = match Theme::by_name(&options.theme) {
+		Ok(theme) => theme,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	let plain = render::plain_output(options.plain);
+	if plain {
+		console::set_colors_enabled(false);
+	}
+	let grid = GridRenderer {
+		theme,
+		glyphs,
+		plain,
+		wide: options.wide,
+		checkerboard: options.checkerboard,
+		grid_lines: false,
+		center: options.center,
+		fog: None,
+		minimap: MinimapCache::default(),
+	};
+	let view = View {
+		minimap: options.minimap,
+		..Default::default()
+	};
+	let stdout = Term::buffered_stdout();
+	let altscreen = options.altscreen && !plain;
+	if altscreen {
+		screen::enter(&stdout);
+	}
+	let keys = if io::stdin().is_terminal() { input::spawn_reader() } else { input::spawn_command_reader() };
+	let mut game = draft.game();
+	let mut message = tr(Msg::EditorHelp).to_string();
+	// changes since the last save, quitting with them takes a second press
+	let mut unsaved = false;
+	let mut quitting = false;
+	loop {
+		let mines = draft.mines.iter().filter(|&&mine| mine).count();
+		let opened = draft.opened.iter().filter(|&&opened| opened).count();
+		let status = editor_status(message.clone(), trf(Msg::EditorCount, &[&mines, &opened]));
+		grid.draw(&game, &view, &status, &stdout);
+		let Ok(key) = keys.recv() else {
+			break;
+		};
+		let i = game.index_of(game.cursor_x, game.cursor_y);
+		let was_quitting = std::mem::take(&mut quitting);
+		match key {
+			Key::Char(' ' | '*') if !game.is_void(game.cursor_x, game.cursor_y) => draft.mines[i] = !draft.mines[i],
+			Key::Char('o') if !game.is_void(game.cursor_x, game.cursor_y) => draft.opened[i] = !draft.opened[i],
+			Key::Char('s') => {
+				message = match draft.problem() {
+					Some(problem) => problem,
+					None => match boardfile::save(&game, path) {
+						Ok(()) => {
+							unsaved = false;
+							trf(Msg::EditorSaved, &[&path])
+						},
+						Err(err) => err.to_string(),
+					},
+				};
+				continue;
+			},
+			Key::Char('q') | Key::Escape if unsaved && !was_quitting => {
+				quitting = true;
+				message = tr(Msg::EditorUnsaved).into();
+				continue;
+			},
+			Key::Char('q') | Key::Escape => break,
+			// the cursor moves the way it does in a game
+			_ => {
+				let moves = |action: &Action| {
+					use Action::*;
+					matches!(action, Move(_) | Jump(_) | RowStart | RowEnd | BoardStart | BoardEnd)
+				};
+				if let Some(action) = Action::from_key(&key).filter(moves) {
+					game.act(action);
+				}
+				message = game.message.take().unwrap_or(message);
+				continue;
+			},
+		}
+		unsaved = true;
+		message = tr(Msg::EditorHelp).into();
+		let cursor = (game.cursor_x, game.cursor_y);
+		game = draft.game();
+		(game.cursor_x, game.cursor_y) = cursor;
+	}
+	if altscreen {
+		screen::leave(&stdout);
+	}
+	stdout.flush().unwrap();
+	0
+}
+
+fn editor_status(message: String, count: String) -> Status {
+	Status {
+		message: Some(message),
+		items: vec![count],
+		..Default::default()
+	}
+}
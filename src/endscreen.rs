@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use console::Term;
+
+use crate::lang::{tr, trf, Msg};
+use crate::{pos_label, TurnResult};
+
+pub fn efficiency(three_bv: usize, moves: usize) -> u64 {
+	(100 * three_bv / moves.max(1)) as u64
+}
+
+const WIN_BANNER: [&str; 4] = [
+	r"__   _____  _   _  __      _____ _  _ ",
+	r"\ \ / / _ \| | | | \ \    / /_ _| \| |",
+	r" \ V / (_) | |_| |  \ \/\/ / | || .` |",
+	r"  |_| \___/ \___/    \_/\_/ |___|_|\_|",
+];
+
+const LOSE_BANNER: [&str; 4] = [
+	r"  ___   _   __  __ ___    _____   _____ ___ ",
+	r" / __| /_\ |  \/  | __|  / _ \ \ / / __| _ \",
+	r"| (_ |/ _ \| |\/| | _|  | (_) \ V /| _||   /",
+	r" \___/_/ \_\_|  |_|___|  \___/ \_/ |___|_|_\",
+];
+
+pub struct Summary {
+	pub result: TurnResult,
+	pub elapsed: Duration,
+	pub three_bv: usize,
+	pub moves: usize,
+	// the mine that ended the game
+	pub fatal: Option<(usize, usize)>,
+	// best time before this game
+	pub best_time: Option<u64>,
+	pub new_best: bool,
+	pub score: Option<String>,
+	pub high_score: Option<u64>,
+	pub new_high_score: bool,
+	pub race: Option<String>,
+	// tiles opened by each player in co-op
+	pub coop: Option<String>,
+	// clicks used and the budget, when there was one
+	pub clicks: Option<(usize, usize)>,
+	// what the solver made of the fatal open and earlier ones
+	pub review: Vec<String>,
+	// place on the leaderboard for this seed
+	pub seed_rank: Option<String>,
+	// the longest waits before a move and where the move was made, longest first
+	pub pauses: Vec<(Duration, (usize, usize))>,
+	// best 3BV/s and efficiency before this game, and whether this game beat them
+	pub best_rate: Option<f64>,
+	pub new_best_rate: bool,
+	pub best_efficiency: Option<u64>,
+	pub new_best_efficiency: bool,
+}
+
+impl Summary {
+	pub fn rate(&self) -> f64 {
+		self.three_bv as f64 / self.elapsed.as_secs_f64().max(0.001)
+	}
+
+	// 3BV per board-changing move as a percentage, a reverse chord is one move
+	pub fn efficiency(&self) -> u64 {
+		efficiency(self.three_bv, self.moves)
+	}
+
+	// draws the end screen below the board, or a single line if there are fewer than `free_rows` left
+	pub fn draw(&self, stdout: &Term, free_rows: usize) {
+		let secs = self.elapsed.as_secs_f64();
+		let secs_text = format!("{:.1}", secs);
+		let banner = match self.result {
+			TurnResult::Win => &WIN_BANNER,
+			TurnResult::Lose | TurnResult::Surrender => &LOSE_BANNER,
+			_ => {
+				stdout.write_line(&trf(Msg::QuitAfter, &[&secs_text])).unwrap();
+				if let Some(race) = &self.race {
+					stdout.write_line(race).unwrap();
+				}
+				stdout.flush().unwrap();
+				return;
+			}
+		};
+
+		let mut lines = Vec::new();
+		if let TurnResult::Win = self.result {
+			let rate = format!("{:.2}", self.rate());
+			lines.push(trf(Msg::WinStats, &[&secs_text, &self.three_bv, &rate, &self.moves]));
+			lines.push(match (self.new_best, self.best_time) {
+				(true, Some(old)) => trf(Msg::NewBestWas, &[&old]),
+				(true, None) => tr(Msg::NewBest).into(),
+				(false, Some(best)) => trf(Msg::BestTime, &[&best, &self.elapsed.as_secs().saturating_sub(best)]),
+				(false, None) => String::new(),
+			});
+			lines.extend(self.seed_rank.clone());
+			lines.push(trf(Msg::Efficiency, &[&self.efficiency()]));
+			let mut bests = Vec::new();
+			if self.new_best_rate {
+				bests.push(tr(Msg::NewBestRate).to_string());
+			} else if let Some(best) = self.best_rate {
+				bests.push(trf(Msg::BestRate, &[&format!("{:.2}", best)]));
+			}
+			if self.new_best_efficiency {
+				bests.push(tr(Msg::NewBestEfficiency).to_string());
+			} else if let Some(best) = self.best_efficiency {
+				bests.push(trf(Msg::BestEfficiency, &[&best]));
+			}
+			lines.push(bests.join("   "));
+			if let Some((used, budget)) = self.clicks {
+				lines.push(trf(Msg::ClicksLeft, &[&used, &budget, &(budget - used)]));
+			}
+		} else {
+			lines.push(trf(Msg::LoseStats, &[&secs_text, &self.moves]));
+			if let TurnResult::Surrender = self.result {
+				lines.push(tr(Msg::Surrendered).into());
+			}
+			match (self.fatal, self.clicks) {
+				(Some((x, y)), _) => lines.push(trf(Msg::HitMine, &[&pos_label(x, y)])),
+				(None, Some((used, budget))) if used >= budget => {
+					lines.push(trf(Msg::OutOfClicks, &[&budget, &budget.saturating_sub(self.three_bv)]));
+				},
+				_ => (),
+			}
+			lines.extend(self.review.iter().cloned());
+		}
+		if let Some(score) = &self.score {
+			lines.push(score.clone());
+			if self.new_high_score {
+				lines.push(tr(Msg::NewHighScore).into());
+			} else if let Some(best) = self.high_score {
+				lines.push(trf(Msg::HighScore, &[&best]));
+			}
+		}
+		if !self.pauses.is_empty() {
+			let pauses: Vec<_> = self
+				.pauses
+				.iter()
+				.enumerate()
+				.map(|(i, (pause, (x, y)))| {
+					trf(Msg::PauseEntry, &[&(i + 1), &format!("{:.1}", pause.as_secs_f64()), &pos_label(*x, *y)])
+				})
+				.collect();
+			lines.push(trf(Msg::LongestPauses, &[&pauses.join("  ")]));
+		}
+		if let Some(race) = &self.race {
+			lines.push(race.clone());
+		}
+		lines.extend(self.coop.clone());
+		lines.retain(|line| !line.is_empty());
+
+		if banner.len() + 1 + lines.len() > free_rows {
+			// not enough room for the full screen
+			let title = match self.result {
+				TurnResult::Win => tr(Msg::YouWin),
+				_ => tr(Msg::GameOver),
+			};
+			stdout.write_line(&format!("{} {}", title, lines.join(", "))).unwrap();
+		} else {
+			for line in banner {
+				stdout.write_line(line).unwrap();
+			}
+			stdout.write_line("").unwrap();
+			for line in lines {
+				stdout.write_line(&line).unwrap();
+			}
+		}
+		stdout.flush().unwrap();
+	}
+}
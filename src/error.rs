@@ -0,0 +1,119 @@
+use std::fmt;
+use std::io;
+
+// everything that can stop a game from starting, with the exit code it ends the program with
+#[derive(Debug)]
+pub enum MinesweeperError {
+	// a tile outside the board, or a void tile on a shaped one
+	OutOfBounds { x: usize, y: usize },
+	// options or board settings that can't make a game
+	InvalidConfig(String),
+	Io { path: String, source: io::Error },
+	// a board, shape or template file that doesn't follow the format
+	ParseBoard { path: String, line: usize, reason: String },
+	// a binary board file that doesn't follow its format
+	ParseBinary { path: String, reason: String },
+}
+
+impl MinesweeperError {
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			Self::InvalidConfig(_) | Self::OutOfBounds { .. } => 2,
+			Self::Io { .. } | Self::ParseBoard { .. } | Self::ParseBinary { .. } => 3,
+		}
+	}
+
+	pub fn io(path: &str, source: io::Error) -> Self {
+		Self::Io { path: path.into(), source }
+	}
+}
+
+impl fmt::Display for MinesweeperError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OutOfBounds { x, y } => write!(f, "({}, {}) is not on the board", x + 1, y + 1),
+			Self::InvalidConfig(reason) => write!(f, "{}", reason),
+			Self::Io { path, source } => write!(f, "could not read '{}': {}", path, source),
+			Self::ParseBoard { path, line, reason } => write!(f, "{}: line {}: {}", path, line, reason),
+			Self::ParseBinary { path, reason } => write!(f, "{}: {}", path, reason),
+		}
+	}
+}
+
+// the option checks report plain strings
+impl From<String> for MinesweeperError {
+	fn from(reason: String) -> Self {
+		Self::InvalidConfig(reason)
+	}
+}
+
+impl From<&str> for MinesweeperError {
+	fn from(reason: &str) -> Self {
+		Self::InvalidConfig(reason.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{boardfile, MSGame};
+
+	#[test]
+	fn out_of_bounds() {
+		let mut game = MSGame::new(4, 3, 2, 1).unwrap();
+		let err = game.open_at(4, 0).err().unwrap();
+		assert!(matches!(err, MinesweeperError::OutOfBounds { x: 4, y: 0 }));
+		assert_eq!(err.to_string(), "(5, 1) is not on the board");
+		assert_eq!(err.exit_code(), 2);
+	}
+
+	#[test]
+	fn flag_out_of_bounds() {
+		let mut game = MSGame::new(4, 3, 2, 1).unwrap();
+		let err = game.flag_at(0, 3).err().unwrap();
+		assert!(matches!(err, MinesweeperError::OutOfBounds { x: 0, y: 3 }));
+		assert_eq!(err.to_string(), "(1, 4) is not on the board");
+		assert_eq!(game.flags, 0);
+		// void tiles on a shaped board aren't on it either
+		let mut game = boardfile::parse("test", "X.*\n").unwrap();
+		assert!(matches!(game.flag_at(0, 0).err().unwrap(), MinesweeperError::OutOfBounds { x: 0, y: 0 }));
+		assert!(game.flag_at(2, 0).is_ok());
+		assert_eq!(game.flags, 1);
+	}
+
+	#[test]
+	fn invalid_config() {
+		let err = MSGame::new(3, 3, 9, 1).err().unwrap();
+		assert!(matches!(err, MinesweeperError::InvalidConfig(_)));
+		assert_eq!(err.to_string(), "too many mines for the 9 tiles on the board");
+		assert_eq!(err.exit_code(), 2);
+	}
+
+	#[test]
+	fn io() {
+		let err = boardfile::load("no/such/board.txt").err().unwrap();
+		assert!(matches!(&err, MinesweeperError::Io { path, .. } if path == "no/such/board.txt"));
+		assert!(err.to_string().starts_with("could not read 'no/such/board.txt': "));
+		assert_eq!(err.exit_code(), 3);
+	}
+
+	#[test]
+	fn parse_board() {
+		let err = boardfile::parse("test", "*..\n.?.\n").err().unwrap();
+		assert!(matches!(err, MinesweeperError::ParseBoard { line: 2, .. }));
+		assert_eq!(err.to_string(), "test: line 2: unexpected character '?'");
+		assert_eq!(err.exit_code(), 3);
+	}
+
+	#[test]
+	fn parse_binary() {
+		let path = std::env::temp_dir().join(format!("minesweeper-error-{}.mbf", std::process::id()));
+		// a 2x2 board with one mine, cut off before its position
+		std::fs::write(&path, [2, 2, 0, 1]).unwrap();
+		let err = boardfile::load(path.to_str().unwrap()).err().unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert!(matches!(err, MinesweeperError::ParseBinary { .. }));
+		assert!(err.to_string().ends_with(": 1 mines need 2 bytes after the header, the file is cut off at 0"));
+		assert_eq!(err.exit_code(), 3);
+	}
+}
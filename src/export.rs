@@ -0,0 +1,131 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::date;
+use crate::history;
+use crate::records::Records;
+
+// the columns never change order, new ones only ever go on the end
+const HEADER: [&str; 11] = ["date", "preset", "width", "height", "mines", "seed", "result", "time", "3bv", "clicks", "assists"];
+
+// writes every game from the history and every best time from the records to a csv file, returns the exit code.
+// best times have the result "best" and leave the columns the records don't know empty
+pub fn run(out: &str) -> i32 {
+	let history = match history::read() {
+		Ok(text) => Some(text),
+		Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+		Err(err) => {
+			eprintln!("could not read the history: {}", err);
+			return 1;
+		},
+	};
+	let records = match Records::read() {
+		Ok(records) => Some(records),
+		Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+		Err(err) => {
+			eprintln!("could not read the records: {}", err);
+			return 1;
+		},
+	};
+	if history.is_none() && records.is_none() {
+		eprintln!("no statistics to export, play a game first");
+		return 1;
+	}
+
+	let mut text = row(HEADER.iter().map(|s| s.to_string()));
+	if let Some(history) = history {
+		text += &history_rows(&history);
+	}
+	for (difficulty, time) in records.iter().flat_map(Records::best_times) {
+		// difficulties look like 16x16x32 with the mode after a dash
+		let (size, preset) = difficulty.split_once('-').unwrap_or((difficulty, ""));
+		let mut size = size.split('x').map(String::from);
+		let mut size = || size.next().unwrap_or_default();
+		text += &row([
+			String::new(),
+			preset.into(),
+			size(),
+			size(),
+			size(),
+			String::new(),
+			"best".into(),
+			time.to_string(),
+			String::new(),
+			String::new(),
+			String::new(),
+		]);
+	}
+
+	if let Err(err) = write(Path::new(out), &text) {
+		eprintln!("could not write '{}': {}", out, err);
+		return 1;
+	}
+	0
+}
+
+// one row per game, the columns are found by name so older history files work too
+fn history_rows(history: &str) -> String {
+	let mut text = String::new();
+	let mut lines = history.lines();
+	let columns: Vec<_> = lines.next().unwrap_or_default().split(',').collect();
+	for line in lines.filter(|line| !line.is_empty()) {
+		let fields = history::fields(line);
+		let get = |name: &str| {
+			let i = columns.iter().position(|&c| c == name);
+			i.and_then(|i| fields.get(i)).cloned().unwrap_or_default()
+		};
+		let date = get("timestamp").parse().map_or(String::new(), date::rfc3339);
+		let assists = match get("assisted").as_str() {
+			"true" => "1",
+			"false" => "0",
+			_ => "",
+		};
+		text += &row([
+			date,
+			get("mode"),
+			get("width"),
+			get("height"),
+			get("mines"),
+			get("seed"),
+			get("result"),
+			get("elapsed"),
+			get("3bv"),
+			// empty for games from before the history had clicks
+			get("clicks"),
+			assists.into(),
+		]);
+	}
+	text
+}
+
+fn write(path: &Path, text: &str) -> io::Result<()> {
+	if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+		fs::create_dir_all(dir)?;
+	}
+	fs::write(path, text)
+}
+
+fn row(fields: impl IntoIterator<Item = String>) -> String {
+	let fields: Vec<_> = fields.into_iter().map(|field| history::quote(&field)).collect();
+	fields.join(",") + "\r\n"
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clicks_come_from_the_history() {
+		let history = "timestamp,width,height,mines,seed,result,elapsed,moves,3bv,assisted,mode,clicks\n\
+			0,9,9,10,7,win,12.5,30,20,false,,18\n";
+		assert_eq!(history_rows(history), "1970-01-01T00:00:00Z,,9,9,10,7,win,12.5,20,18,0\r\n");
+	}
+
+	#[test]
+	fn old_games_have_no_clicks() {
+		let history = "timestamp,width,height,mines,seed,result,elapsed,moves,3bv,assisted,mode\n\
+			0,9,9,10,,loss,3.0,4,20,true,classic\n";
+		assert_eq!(history_rows(history), "1970-01-01T00:00:00Z,classic,9,9,10,,loss,3.0,20,,1\r\n");
+	}
+}
@@ -0,0 +1,1654 @@
+use std::cmp::Ordering;
+use std::ops::Add;
+use std::ops::Rem;
+use std::rc::Rc;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::action::{Action, Direction};
+use crate::board::Board;
+use crate::error::MinesweeperError;
+use crate::lang::{tr, trf, Msg};
+use crate::placement::Placement;
+use crate::review::{Review, Step};
+use crate::solver::{self, BoardView, Cell, Deductions, Solver};
+
+// tiles moved by shift+arrows (or HJKL) unless --jump says otherwise
+pub(crate) const JUMP: usize = 5;
+// the largest count that can be typed in front of an action
+const MAX_COUNT: usize = 9999;
+// the largest neighborhood that still fits the counts in a board cell
+pub(crate) const MAX_RADIUS: usize = 3;
+// triangles touching a triangle by an edge or a corner, for ones pointing up and down
+const UP_TRIANGLE_OFFSETS: [(i32, i32); 12] = [
+	(-1,-1),(0,-1),(1,-1),
+	(-2,0),(-1,0),(1,0),(2,0),
+	(-2,1),(-1,1),(0,1),(1,1),(2,1),
+];
+const DOWN_TRIANGLE_OFFSETS: [(i32, i32); 12] = [
+	(-2,-1),(-1,-1),(0,-1),(1,-1),(2,-1),
+	(-2,0),(-1,0),(1,0),(2,0),
+	(-1,1),(0,1),(1,1),
+];
+
+// how the tiles are arranged, which decides what their neighbors are
+#[derive(Clone, Copy, PartialEq)]
+pub enum Lattice {
+	Square,
+	// alternating up and down pointing triangles, starting with one pointing up in the top left
+	Triangle,
+}
+
+pub enum TurnResult {
+	Continue,
+	Lose,
+	Win,
+	Quit,
+	// gave up, the mines are shown like after a loss
+	Surrender,
+}
+
+#[derive(Clone)]
+pub enum GameEvent {
+	Opened(usize),
+	Flagged,
+}
+
+// winning by flagging every mine, as well as by opening every safe tile or instead of it
+#[derive(Clone, Copy, PartialEq)]
+pub enum FlagWin {
+	Also,
+	Only,
+}
+
+// every offset within `radius` steps in reading order, for radius 1 the usual 8 neighbors
+fn square_offsets(radius: usize) -> Vec<(i32, i32)> {
+	let r = radius as i32;
+	(-r..=r)
+		.flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+		.filter(|&offset| offset != (0, 0))
+		.collect()
+}
+
+// a fixed pseudo random bit for every tile (splitmix64)
+fn lie_bit(key: u64, index: usize) -> bool {
+	let mut z = key.wrapping_add((index as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15));
+	z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+	(z ^ (z >> 31)) & 1 == 1
+}
+
+// 1-based coordinates for showing to the player
+pub(crate) fn pos_label(x: usize, y: usize) -> String {
+	format!("({}, {})", x + 1, y + 1)
+}
+
+// which way the tile at `nx`, `ny` is from `x`, `y`, or where it is when it isn't right next to it
+fn side(x: usize, y: usize, nx: usize, ny: usize) -> String {
+	if nx.abs_diff(x) > 1 || ny.abs_diff(y) > 1 {
+		return pos_label(nx, ny);
+	}
+	let msg = match (nx.cmp(&x), ny.cmp(&y)) {
+		(Ordering::Less, Ordering::Less) => Msg::SideUpLeft,
+		(Ordering::Equal, Ordering::Less) => Msg::SideUp,
+		(Ordering::Greater, Ordering::Less) => Msg::SideUpRight,
+		(Ordering::Less, Ordering::Equal) => Msg::SideLeft,
+		(Ordering::Greater, Ordering::Equal) => Msg::SideRight,
+		(Ordering::Less, Ordering::Greater) => Msg::SideDownLeft,
+		(Ordering::Equal, Ordering::Greater) => Msg::SideDown,
+		_ => Msg::SideDownRight,
+	};
+	tr(msg).into()
+}
+
+#[derive(Clone)]
+pub struct MSGame {
+	pub(crate) width: usize,
+	pub(crate) height: usize,
+	pub(crate) cursor_x: usize,
+	pub(crate) cursor_y: usize,
+	pub(crate) board: Board,
+	pub(crate) mines: usize,
+	pub(crate) flags: usize,
+	// None for boards loaded from a file
+	pub(crate) seed: Option<u64>,
+	pub(crate) events: Vec<GameEvent>,
+	// board-changing actions taken
+	pub(crate) moves: usize,
+	// the mine that was opened, if any
+	pub(crate) exploded: Option<(usize, usize)>,
+	// message for the status bar from the last action
+	pub(crate) message: Option<String>,
+	// candidates and position of the frontier jump being cycled through
+	pub(crate) frontier_cycle: Option<(usize, Vec<(usize, usize)>)>,
+	// tiles opened by the last flood fill and their distance (in steps) from where it started
+	pub(crate) reveal_order: Vec<(usize, usize, usize)>,
+	// the frontier jump was used
+	pub(crate) assisted: bool,
+	// safe tiles not opened yet, the game is won when this reaches zero
+	pub(crate) safe_left: usize,
+	pub(crate) shifting: Option<Shifting>,
+	// opens of hidden tiles, counted against the budget when there is one
+	pub(crate) clicks: usize,
+	pub(crate) click_budget: Option<usize>,
+	// liar variant: every number is shown one off, in a direction picked from this key
+	pub(crate) liar: Option<u64>,
+	pub(crate) lattice: Lattice,
+	// neighbors on the square lattice are the tiles within this many steps, diagonals included
+	pub(crate) radius: usize,
+	pub(crate) offsets: Vec<(i32, i32)>,
+	// tiles outside the shape of the board, empty when it is a full rectangle.
+	// they are stored as opened empty tiles so nothing treats them as something left to do
+	pub(crate) void: Vec<bool>,
+	// extra or replacement win condition, every mine flagged and nothing else
+	pub(crate) flag_win: Option<FlagWin>,
+	// how the mines were spread, kept for restarts
+	pub(crate) placement: Placement,
+	// what the solver can prove from the opened numbers, kept up to date while the indicator is shown
+	pub(crate) forced: Option<Deductions>,
+	// numbers with more flags around them than their value, and those flags, from the last flag check.
+	// shown until the board changes
+	pub(crate) conflicts: Vec<(usize, usize)>,
+	// ask for a second press before opening a tile that is certainly a mine
+	pub(crate) guard: bool,
+	// the tile waiting for that second press
+	pub(crate) guarded: Option<(usize, usize)>,
+	// surrender was the last key, pressing it again gives up
+	pub(crate) surrendering: bool,
+	// the opens judged after a loss, None before that or when the solver can't follow the game
+	pub(crate) review: Option<Review>,
+	// false when the solver can't follow the game
+	pub(crate) reviewable: bool,
+	// the safe tiles in the order they were opened and their numbers, for the review
+	pub(crate) opened: Vec<((usize, usize), u8)>,
+	// the opens of hidden tiles by the player, for the review
+	pub(crate) steps: Vec<Step>,
+	// how far a fast move goes
+	pub(crate) jump: usize,
+	// the count typed in front of the next action
+	pub(crate) count: Option<usize>,
+	// opening an empty tile also opens the tiles around it
+	pub(crate) cascade: bool,
+	// anti-mine variant: some mines are anti-mines, which count as minus one in the numbers
+	pub(crate) anti: bool,
+	// the number of mines is kept from the player, along with everything that would give it away
+	pub(crate) blind: bool,
+	// the second player's cursor in co-op
+	pub(crate) partner: Option<(usize, usize)>,
+	// the move every tile was opened on, counted from 1, 0 for tiles that weren't.
+	// for the heatmap on the end screen
+	pub(crate) opened_at: Vec<u32>,
+	// the mines in each row and in each column, shown next to the board with --line-hints
+	pub(crate) line_totals: Option<(Vec<usize>, Vec<usize>)>,
+	// what the hints, the assists, the review and the no-guess checks reason with
+	pub(crate) solver: Rc<dyn Solver>,
+	// the 3BV progress and the board version it was counted on, the live rates show it every frame
+	pub(crate) progress: std::cell::Cell<Option<(u64, (usize, usize))>>,
+}
+
+#[derive(Default)]
+pub(crate) struct Surroundings {
+	pub(crate) hidden: usize,
+	pub(crate) flagged: usize,
+	// with where they are
+	pub(crate) numbers: Vec<((usize, usize), u8)>,
+}
+
+// moves a mine somewhere else every `every` opens, without changing any opened number
+#[derive(Clone)]
+pub(crate) struct Shifting {
+	pub(crate) every: usize,
+	pub(crate) opens: usize,
+	pub(crate) rng: StdRng,
+}
+
+impl MSGame {
+	pub fn new(width: usize, height: usize, mines: usize, seed: u64) -> Result<Self, MinesweeperError> {
+		Self::shaped(width, height, mines, seed, Vec::new(), Placement::Uniform)
+	}
+
+	// `void` marks the tiles that aren't part of the board, it is empty for a full rectangle
+	pub fn shaped(
+		width: usize,
+		height: usize,
+		mines: usize,
+		seed: u64,
+		void: Vec<bool>,
+		placement: Placement,
+	) -> Result<Self, MinesweeperError> {
+		if width == 0 || height == 0 {
+			return Err("the board must be at least 1x1".into());
+		}
+		if !void.is_empty() && void.len() != width * height {
+			return Err(format!("the shape has {} tiles but the board has {}", void.len(), width * height).into());
+		}
+		let playable = if void.is_empty() {
+			width * height
+		} else {
+			void.iter().filter(|&&v| !v).count()
+		};
+		let mines = placement.mines(width, height, &void).unwrap_or(mines);
+		// at least one safe tile, or the game would be won before it starts
+		if mines >= playable {
+			return Err(format!("too many mines for the {} tiles on the board", playable).into());
+		}
+		placement.check(width, height, mines, &void)?;
+		let mut rng = StdRng::seed_from_u64(seed);
+		if let Some(mask) = placement.mask(width, height, mines, &void, &mut rng) {
+			let mut game = Self::from_mask(width, height, &mask, void);
+			game.seed = Some(seed);
+			game.placement = placement;
+			return Ok(game);
+		}
+		let mut board = Board::new(playable, mines);
+		board.shuffle(&mut rng);
+		if !void.is_empty() {
+			board = board.spread(&void, Tile::void());
+		}
+		let mut game = Self::with_board(width, height, board, void);
+		game.seed = Some(seed);
+		Ok(game)
+	}
+
+	// reasons with another solver instead of `solver::Standard`, for everything from the hints to `analyze::solve`
+	pub fn with_solver(mut self, solver: Rc<dyn Solver>) -> Self {
+		self.solver = solver;
+		self
+	}
+
+	// a new board of the same shape
+	pub fn regenerate(&self, seed: u64) -> Self {
+		let mut game = Self::shaped(self.width, self.height, self.mines, seed, self.void.clone(), self.placement.clone())
+			.expect("the settings already made a board");
+		game.solver = self.solver.clone();
+		game
+	}
+
+	pub(crate) fn from_mask(width: usize, height: usize, mask: &[bool], void: Vec<bool>) -> Self {
+		let mut board = Board::from_mask(mask);
+		for (i, _) in void.iter().enumerate().filter(|(_, &v)| v) {
+			board.set(i, Tile::void());
+		}
+		Self::with_board(width, height, board, void)
+	}
+
+	pub(crate) fn with_board(width: usize, height: usize, board: Board, void: Vec<bool>) -> Self {
+		let mines = (0..board.len())
+			.filter(|&i| board.is_mine(i))
+			.count();
+		let voids = void.iter().filter(|&&v| v).count();
+		let mut new_game = Self {
+			cursor_x: 0,
+			cursor_y: 0,
+			width,
+			height,
+			board,
+			flags: 0,
+			mines,
+			seed: None,
+			events: Vec::new(),
+			moves: 0,
+			exploded: None,
+			message: None,
+			frontier_cycle: None,
+			reveal_order: Vec::new(),
+			assisted: false,
+			safe_left: 0,
+			shifting: None,
+			clicks: 0,
+			click_budget: None,
+			liar: None,
+			lattice: Lattice::Square,
+			radius: 1,
+			offsets: square_offsets(1),
+			void,
+			flag_win: None,
+			placement: Placement::Uniform,
+			forced: None,
+			conflicts: Vec::new(),
+			guard: false,
+			guarded: None,
+			surrendering: false,
+			review: None,
+			reviewable: true,
+			opened: Vec::new(),
+			steps: Vec::new(),
+			jump: JUMP,
+			count: None,
+			cascade: true,
+			anti: false,
+			blind: false,
+			partner: None,
+			opened_at: Vec::new(),
+			line_totals: None,
+			solver: Rc::new(solver::Standard),
+			progress: Default::default(),
+		};
+		new_game.safe_left = new_game.board.len() - mines - voids;
+		new_game.opened_at = vec![0; new_game.board.len()];
+		new_game.count_neighbors();
+		if let Some(i) = new_game.void.iter().position(|&v| !v) {
+			(new_game.cursor_x, new_game.cursor_y) = (i % width, i / width);
+		}
+		new_game
+	}
+
+	// the hints only count the mines as they were placed, the variants that move them can't be combined with them
+	pub fn count_lines(&mut self) {
+		let mut rows = vec![0; self.height];
+		let mut cols = vec![0; self.width];
+		for i in (0..self.board.len()).filter(|&i| self.board.is_mine(i)) {
+			rows[i / self.width] += 1;
+			cols[i % self.width] += 1;
+		}
+		self.line_totals = Some((rows, cols));
+	}
+
+	// one pass over the mines, adding 1 to the count of every neighbor by index
+	pub(crate) fn count_neighbors(&mut self) {
+		let mut counts = vec![0u8; self.board.len()];
+		for i in (0..self.board.len()).filter(|&i| self.board.is_mine(i)) {
+			let (x, y) = (i % self.width, i / self.width);
+			for &(dx, dy) in self.offsets(x, y) {
+				let (nx, ny) = (x.wrapping_add(dx as usize), y.wrapping_add(dy as usize));
+				if nx < self.width && ny < self.height {
+					counts[nx + ny * self.width] += 1;
+				}
+			}
+		}
+		// void tiles stay at 0
+		for (i, _) in self.void.iter().enumerate().filter(|(_, &v)| v) {
+			counts[i] = 0;
+		}
+		self.board.set_counts(&counts);
+	}
+
+	// restarting and the view toggles are up to whoever runs the game, they do nothing here
+	pub fn act(&mut self, action: Action) -> TurnResult {
+		if let Action::Digit(digit) = action {
+			self.add_digit(digit);
+			return TurnResult::Continue;
+		}
+		// a count applies to the next action, and quitting with one only drops it
+		let count = self.count.take();
+		if action == Action::Quit && count.is_some() {
+			return TurnResult::Continue;
+		}
+		let times = count.unwrap_or(1);
+		if action != Action::NextFrontier {
+			self.frontier_cycle = None;
+		}
+		if action != Action::Open {
+			self.guarded = None;
+		}
+		if action != Action::Surrender {
+			self.surrendering = false;
+		}
+		let moves = self.moves;
+		match action {
+			// counted moves stop at the edge instead of wrapping around
+			Action::Move(direction) if count.is_some() => self.jump_cursor(direction, times),
+			Action::Move(direction) => self.move_cursor(direction),
+			Action::RowStart => self.cursor_to(self.row_tiles()),
+			Action::RowEnd   => self.cursor_to(self.row_tiles().rev()),
+			Action::BoardStart => self.cursor_to(0..self.board.len()),
+			Action::BoardEnd   => self.cursor_to((0..self.board.len()).rev()),
+			Action::Jump(direction) => self.jump_cursor(direction, self.jump.saturating_mul(times)),
+			Action::Flag => self.flag_tile(),
+			Action::AntiFlag => self.anti_flag_tile(),
+			Action::Open => self.open_tile(),
+			Action::Finish => self.finish(),
+			Action::NextFrontier => (0..times).for_each(|_| self.jump_to_frontier()),
+			Action::NextNumber => (0..times).for_each(|_| self.cycle_unresolved(true)),
+			Action::PreviousNumber => (0..times).for_each(|_| self.cycle_unresolved(false)),
+			Action::ToggleForced => self.toggle_forced(),
+			Action::CheckFlags => self.check_flags(),
+			Action::SuggestGuess => self.suggest_guess(),
+			Action::Describe => self.describe(),
+			Action::Quit => return TurnResult::Quit,
+			Action::Surrender if self.surrendering => {
+				self.open_mines();
+				return TurnResult::Surrender;
+			},
+			Action::Surrender => {
+				self.surrendering = true;
+				self.message = Some(tr(Msg::ConfirmSurrender).into());
+			},
+			_ => (),
+		}
+		if self.moves != moves {
+			self.conflicts.clear();
+		}
+		self.check_board()
+	}
+
+	// the second player does the action with their own cursor. a count typed by the first player
+	// is left for them
+	pub fn act_as_partner(&mut self, action: Action) -> TurnResult {
+		let Some(partner) = self.partner else {
+			return TurnResult::Continue;
+		};
+		let count = self.count.take();
+		let cursor = (self.cursor_x, self.cursor_y);
+		(self.cursor_x, self.cursor_y) = partner;
+		let turn = self.act(action);
+		self.partner = Some((self.cursor_x, self.cursor_y));
+		(self.cursor_x, self.cursor_y) = cursor;
+		self.count = count;
+		turn
+	}
+
+	// the second player starts on the last tile, away from the first one
+	pub fn add_partner(&mut self) {
+		let last = (0..self.board.len()).rev().find(|&i| self.void.is_empty() || !self.void[i]);
+		self.partner = last.map(|i| (i % self.width, i / self.width));
+	}
+
+	pub(crate) fn check_board(&mut self) -> TurnResult {
+		debug_assert_eq!(self.safe_left, self.safe_hidden(), "safe tile counter out of sync");
+		if self.exploded.is_some() {
+			if self.reviewable && self.review.is_none() {
+				// the board as it was before the player's first open, tiles opened by the board file stay open
+				let mut view = self.view();
+				for &((x, y), _) in &self.opened {
+					view.set(x, y, Cell::Hidden);
+				}
+				self.review = Some(Review::after_loss(&*self.solver, view, &self.opened, &self.steps));
+			}
+			self.open_mines();
+			return TurnResult::Lose;
+		}
+		// with anti-mines the flags have to tell the two kinds apart as well
+		let opened_all = self.safe_left == 0 && self.flag_win != Some(FlagWin::Only) && (!self.anti || self.flagged_exactly());
+		let flagged_all = self.flag_win.is_some() && self.flagged_exactly();
+		if opened_all || flagged_all {
+			TurnResult::Win
+		}
+		else if self.out_of_clicks() {
+			self.open_mines();
+			TurnResult::Lose
+		}
+		else {
+			TurnResult::Continue
+		}
+	}
+
+	// every mine has a flag of its kind and no other tile has one
+	pub(crate) fn flagged_exactly(&self) -> bool {
+		self.flags == self.mines
+			&& (0..self.board.len())
+				.map(|i| self.board.get(i))
+				.all(|tile| match tile.contents {
+					TileContents::Mine => matches!(tile.visibility, TileVis::Flag),
+					TileContents::AntiMine => matches!(tile.visibility, TileVis::AntiFlag),
+					TileContents::Safe(_) => true,
+				})
+	}
+
+	// returns true if a safe tile was opened
+	pub(crate) fn open_single_tile(&mut self, x: usize, y: usize) -> bool {
+		let mut tile = self.get(x, y);
+		if let TileVis::Hidden = tile.visibility {
+			tile.visibility = TileVis::Open;
+			self.set(x, y, tile);
+			let i = self.index_of(x, y);
+			self.opened_at[i] = self.moves as u32 + 1;
+			if let TileContents::Safe(n) = tile.contents {
+				self.safe_left -= 1;
+				self.opened.push(((x, y), n));
+				return true;
+			}
+		}
+		false
+	}
+
+	pub(crate) fn open_tile(&mut self) {
+		let cursor = (self.cursor_x, self.cursor_y);
+		if self.guard && self.guarded != Some(cursor) && self.proven_mine(cursor) {
+			self.guarded = Some(cursor);
+			self.message = Some(tr(Msg::GuardWarning).into());
+			return;
+		}
+		self.guarded = None;
+		if let TileVis::Flag | TileVis::AntiFlag = self.get(self.cursor_x, self.cursor_y).visibility {
+			self.message = Some(tr(Msg::OpenFlagged).into());
+			return;
+		}
+		if let TileVis::Hidden = self.get(self.cursor_x, self.cursor_y).visibility {
+			self.clicks += 1;
+			self.steps.push(Step { at: Some(cursor), after: self.opened.len() });
+		}
+		self.open_flood(&[(self.cursor_x, self.cursor_y)]);
+	}
+
+	// opens one of the empty areas as if the first click had been made there, picked from the seed
+	// so the same board always starts the same way. the opening isn't counted as a move or a click.
+	// returns false if there is no empty tile
+	pub fn open_start(&mut self) -> bool {
+		let empty: Vec<_> = (0..self.board.len())
+			.filter(|&i| matches!(self.board.get(i).contents, TileContents::Safe(0)))
+			.map(|i| (i % self.width, i / self.width))
+			.filter(|&(x, y)| !self.is_void(x, y))
+			.collect();
+		if empty.is_empty() {
+			return false;
+		}
+		let mut rng = StdRng::seed_from_u64(self.seed.unwrap_or_default());
+		(self.cursor_x, self.cursor_y) = empty[rng.gen_range(0..empty.len())];
+		self.open_flood(&[(self.cursor_x, self.cursor_y)]);
+		self.moves = 0;
+		self.events.clear();
+		self.reveal_order.clear();
+		true
+	}
+
+	// opens every hidden tile that isn't flagged at once, when there are as many flags as mines.
+	// a wrong flag leaves a mine among them
+	pub(crate) fn finish(&mut self) {
+		if self.blind {
+			self.message = Some(tr(Msg::BlindNoFinish).into());
+			return;
+		}
+		if self.flags != self.mines {
+			self.message = Some(trf(Msg::FinishNeedsFlags, &[&self.mines, &self.flags]));
+			return;
+		}
+		let hidden: Vec<_> = (0..self.board.len())
+			.filter(|&i| matches!(self.board.get(i).visibility, TileVis::Hidden))
+			.map(|i| (i % self.width, i / self.width))
+			.collect();
+		if hidden.is_empty() {
+			return;
+		}
+		self.clicks += 1;
+		self.steps.push(Step { at: None, after: self.opened.len() });
+		self.open_flood(&hidden);
+	}
+
+	pub(crate) fn out_of_clicks(&self) -> bool {
+		self.click_budget.is_some_and(|budget| self.clicks >= budget)
+	}
+
+	// opens a tile from outside the game, like a click on it, and says how the game stands after it
+	pub fn open_at(&mut self, x: usize, y: usize) -> Result<TurnResult, MinesweeperError> {
+		self.check_pos(x, y)?;
+		self.open_flood(&[(x, y)]);
+		Ok(self.check_board())
+	}
+
+	// flags a tile from outside the game, or takes the flag off, the same as the flag key on it
+	pub fn flag_at(&mut self, x: usize, y: usize) -> Result<TurnResult, MinesweeperError> {
+		self.check_pos(x, y)?;
+		let cursor = (self.cursor_x, self.cursor_y);
+		(self.cursor_x, self.cursor_y) = (x, y);
+		self.flag_tile();
+		(self.cursor_x, self.cursor_y) = cursor;
+		Ok(self.check_board())
+	}
+
+	pub(crate) fn check_pos(&self, x: usize, y: usize) -> Result<(), MinesweeperError> {
+		if !self.valid_pos(x, y) {
+			return Err(MinesweeperError::OutOfBounds { x, y });
+		}
+		Ok(())
+	}
+
+	// flood fill to open all adjacent clear tiles, from every start at once as one move
+	pub(crate) fn open_flood(&mut self, starts: &[(usize, usize)]) {
+		let mut queue: Vec<_> = starts.iter().map(|&(x, y)| (x, y, 0)).collect();
+		let mut i = 0;
+		let mut opened = 0;
+		self.reveal_order.clear();
+		
+		while i < queue.len() {
+			let (x, y, layer) = queue[i];
+			let tile = self.get(x, y);
+			
+			if let TileVis::Hidden = tile.visibility {
+				if self.open_single_tile(x, y) {
+					opened += 1;
+				} else if self.exploded.is_none() {
+					self.exploded = Some((x, y));
+				}
+				self.reveal_order.push((x, y, layer));
+				// if this tile is a 0, add its neighbors to the queue (if they are not already open)
+				if let (TileContents::Safe(0), true) = (tile.contents, self.cascade) {
+					for &(dx, dy) in self.offsets(x, y) {
+						let target_x = x.wrapping_add(dx as usize);
+						let target_y = y.wrapping_add(dy as usize);
+						if !self.valid_pos(target_x, target_y) {
+							continue;
+						}
+						let target = self.get(target_x, target_y);
+						if let TileVis::Open = target.visibility {
+							continue;
+						}
+						queue.push((target_x, target_y, layer + 1));
+					}
+				}
+			}
+			i += 1;
+		}
+		if !self.reveal_order.is_empty() {
+			self.moves += 1;
+		}
+		if let Some(forced) = &self.forced {
+			let changed: Vec<_> = self.reveal_order.iter().map(|&(x, y, _)| (x, y)).collect();
+			self.forced = Some(self.solver.update(&self.view(), forced, &changed));
+		}
+		if opened > 0 {
+			self.events.push(GameEvent::Opened(opened));
+			if self.exploded.is_none() {
+				self.count_shift();
+			}
+		}
+	}
+
+	pub(crate) fn count_shift(&mut self) {
+		let Some(shifting) = &mut self.shifting else {
+			return;
+		};
+		shifting.opens += 1;
+		if shifting.opens % shifting.every != 0 {
+			return;
+		}
+		// both ends of the move must be out of sight of every opened tile, so nothing shown so far becomes wrong
+		let (mines, safe): (Vec<_>, Vec<_>) = self
+			.unobserved_tiles()
+			.into_iter()
+			.partition(|&(x, y)| matches!(self.get(x, y).contents, TileContents::Mine));
+		if mines.is_empty() || safe.is_empty() {
+			return;
+		}
+		let rng = &mut self.shifting.as_mut().unwrap().rng;
+		let from = mines[rng.gen_range(0..mines.len())];
+		let to = safe[rng.gen_range(0..safe.len())];
+		self.move_mine(from, to);
+	}
+
+	// turns a hidden safe tile that no opened number can see into a mine, returns false if there is none.
+	// the last safe tile is never taken, so a game can't be won by a mine appearing
+	pub fn add_mine(&mut self, rng: &mut StdRng) -> bool {
+		let safe: Vec<_> = self
+			.unobserved_tiles()
+			.into_iter()
+			.filter(|&(x, y)| matches!(self.get(x, y).contents, TileContents::Safe(_)))
+			.collect();
+		if safe.is_empty() || self.safe_left <= 1 {
+			return false;
+		}
+		let (x, y) = safe[rng.gen_range(0..safe.len())];
+		let mut tile = self.get(x, y);
+		tile.contents = TileContents::Mine;
+		self.set(x, y, tile);
+		for (nx, ny) in self.neighbors(x, y).collect::<Vec<_>>() {
+			let mut tile = self.get(nx, ny);
+			if let TileContents::Safe(count) = tile.contents {
+				tile.contents = TileContents::Safe(count + 1);
+				self.set(nx, ny, tile);
+			}
+		}
+		self.mines += 1;
+		self.safe_left -= 1;
+		true
+	}
+
+	// turns `count` of the mines into anti-mines, or all of them if there are fewer
+	pub fn add_anti_mines(&mut self, count: usize, rng: &mut StdRng) {
+		let mines: Vec<usize> = (0..self.board.len()).filter(|&i| self.board.is_mine(i)).collect();
+		for &i in mines.choose_multiple(rng, count) {
+			let mut tile = self.board.get(i);
+			tile.contents = TileContents::AntiMine;
+			self.board.set(i, tile);
+		}
+		self.anti = true;
+	}
+
+	// tiles that can change without contradicting anything shown
+	pub(crate) fn unobserved_tiles(&self) -> Vec<(usize, usize)> {
+		(0..self.height)
+			.flat_map(|y| (0..self.width).map(move |x| (x, y)))
+			.filter(|&(x, y)| self.unobserved(x, y))
+			.collect()
+	}
+
+	// a hidden tile with no opened neighbors
+	pub(crate) fn unobserved(&self, x: usize, y: usize) -> bool {
+		matches!(self.get(x, y).visibility, TileVis::Hidden)
+			&& self.neighbors(x, y).all(|(nx, ny)| !matches!(self.get(nx, ny).visibility, TileVis::Open))
+	}
+
+	pub(crate) fn move_mine(&mut self, (from_x, from_y): (usize, usize), (to_x, to_y): (usize, usize)) {
+		let adjust = |game: &mut Self, x: usize, y: usize, up: bool| {
+			for (nx, ny) in game.neighbors(x, y).collect::<Vec<_>>() {
+				let mut tile = game.get(nx, ny);
+				if let TileContents::Safe(count) = tile.contents {
+					tile.contents = TileContents::Safe(if up { count + 1 } else { count - 1 });
+					game.set(nx, ny, tile);
+				}
+			}
+		};
+		let mut tile = self.get(from_x, from_y);
+		tile.contents = TileContents::Safe(0);
+		self.set(from_x, from_y, tile);
+		adjust(self, from_x, from_y, false);
+
+		let mut tile = self.get(to_x, to_y);
+		tile.contents = TileContents::Mine;
+		self.set(to_x, to_y, tile);
+		adjust(self, to_x, to_y, true);
+
+		let count = self
+			.neighbors(from_x, from_y)
+			.filter(|&(nx, ny)| matches!(self.get(nx, ny).contents, TileContents::Mine))
+			.count();
+		let mut tile = self.get(from_x, from_y);
+		tile.contents = TileContents::Safe(count as u8);
+		self.set(from_x, from_y, tile);
+	}
+
+	pub(crate) fn open_mines(&mut self) {
+		for i in 0..self.board.len() {
+			let mut tile = self.board.get(i);
+			if let TileContents::Mine | TileContents::AntiMine = tile.contents {
+				tile.visibility = TileVis::Open;
+				self.board.set(i, tile);
+			}
+		}
+	}
+
+	pub(crate) fn flag_tile(&mut self) {
+		let mut tile = self.get(self.cursor_x, self.cursor_y);
+
+		match tile.visibility {
+			TileVis::Flag | TileVis::AntiFlag => {
+				tile.visibility = TileVis::Hidden;
+				self.flags -= 1;
+				self.moves += 1;
+			},
+			TileVis::Hidden => {
+				tile.visibility = TileVis::Flag;
+				self.flags += 1;
+				self.moves += 1;
+				self.events.push(GameEvent::Flagged);
+			},
+			TileVis::Open => {
+				if !self.flag_neighbors(self.cursor_x, self.cursor_y) {
+					self.message = Some(tr(Msg::FlagOpened).into());
+				}
+				return;
+			},
+		}
+		self.set(self.cursor_x, self.cursor_y, tile);
+	}
+
+	// puts the anti-mine flag on a hidden tile or turns a normal flag into one, a second press takes it off
+	pub(crate) fn anti_flag_tile(&mut self) {
+		if !self.anti {
+			self.message = Some(tr(Msg::NoAntiMines).into());
+			return;
+		}
+		let mut tile = self.get(self.cursor_x, self.cursor_y);
+		match tile.visibility {
+			TileVis::Hidden => {
+				tile.visibility = TileVis::AntiFlag;
+				self.flags += 1;
+				self.events.push(GameEvent::Flagged);
+			},
+			TileVis::Flag => tile.visibility = TileVis::AntiFlag,
+			TileVis::AntiFlag => {
+				tile.visibility = TileVis::Hidden;
+				self.flags -= 1;
+			},
+			TileVis::Open => {
+				self.message = Some(tr(Msg::FlagOpened).into());
+				return;
+			},
+		}
+		self.moves += 1;
+		self.set(self.cursor_x, self.cursor_y, tile);
+	}
+
+	// flags every hidden neighbor of an opened number that has exactly that many unopened neighbors,
+	// returns false if there was nothing to flag
+	pub(crate) fn flag_neighbors(&mut self, x: usize, y: usize) -> bool {
+		// the shown numbers can't be trusted, or don't say how many mines there are
+		if self.liar.is_some() || self.anti {
+			return false;
+		}
+		let TileContents::Safe(n) = self.get(x, y).contents else {
+			return false;
+		};
+		let unopened: Vec<_> = self
+			.neighbors(x, y)
+			.filter(|&(nx, ny)| !matches!(self.get(nx, ny).visibility, TileVis::Open))
+			.collect();
+		if n == 0 || unopened.len() != n as usize {
+			return false;
+		}
+		let mut flagged = false;
+		for (nx, ny) in unopened {
+			let mut tile = self.get(nx, ny);
+			if let TileVis::Hidden = tile.visibility {
+				tile.visibility = TileVis::Flag;
+				self.set(nx, ny, tile);
+				self.flags += 1;
+				self.events.push(GameEvent::Flagged);
+				flagged = true;
+			}
+		}
+		if flagged {
+			self.moves += 1;
+		}
+		flagged
+	}
+
+	// the numbers around a hidden tile prove it is a mine, flags don't count as proof
+	pub(crate) fn proven_mine(&self, (x, y): (usize, usize)) -> bool {
+		// the solver would believe the wrong numbers, or get the neighbors wrong
+		if !self.solvable() {
+			return false;
+		}
+		matches!(self.get(x, y).visibility, TileVis::Hidden) && self.solver.classify(&self.view(), x, y) == Some(true)
+	}
+
+	// puts the safest tile to open in the status bar, or a certainly safe one if there is one.
+	// it counts as help like the frontier jump
+	pub(crate) fn suggest_guess(&mut self) {
+		// the solver would believe the wrong numbers, or get the neighbors wrong
+		if !self.solvable() {
+			self.message = self.line_totals.is_some().then(|| tr(Msg::LineHintsNoSolver).into());
+			return;
+		}
+		self.assisted = true;
+		let view = self.view();
+		let deductions = self.solver.deduce(&view);
+		if let Some(&(x, y)) = deductions.safe.first() {
+			self.message = Some(trf(Msg::NoGuessNeeded, &[&pos_label(x, y)]));
+			return;
+		}
+		// the chances of the tiles away from the numbers depend on how many mines are left
+		if self.blind {
+			self.message = Some(tr(Msg::BlindNoGuess).into());
+			return;
+		}
+		let Some(chances) = self.solver.estimate(&view, self.mines) else {
+			return;
+		};
+		let Some(guess) = chances.best_guess(&view) else {
+			return;
+		};
+		let percent = format!("{:.0}", guess.mine_chance * 100.0);
+		let msg = if chances.exact { Msg::BestGuess } else { Msg::RoughGuess };
+		self.message = Some(trf(msg, &[&pos_label(guess.at.0, guess.at.1), &percent]));
+	}
+
+	// finds the opened numbers that have too many flags around them, without changing anything on the board
+	pub(crate) fn check_flags(&mut self) {
+		// the shown numbers can't be trusted, or don't say how many mines there are
+		if self.liar.is_some() || self.anti {
+			return;
+		}
+		self.conflicts.clear();
+		let mut numbers = 0;
+		for y in 0..self.height {
+			for x in 0..self.width {
+				let tile = self.get(x, y);
+				let (TileVis::Open, TileContents::Safe(n)) = (tile.visibility, tile.contents) else {
+					continue;
+				};
+				if self.is_void(x, y) {
+					continue;
+				}
+				let flags: Vec<_> = self
+					.neighbors(x, y)
+					.filter(|&(nx, ny)| matches!(self.get(nx, ny).visibility, TileVis::Flag))
+					.collect();
+				if flags.len() > n as usize {
+					numbers += 1;
+					self.conflicts.push((x, y));
+					self.conflicts.extend(flags);
+				}
+			}
+		}
+		self.message = Some(match numbers {
+			0 => tr(Msg::FlagsAgree).into(),
+			_ => trf(Msg::TooManyFlags, &[&numbers]),
+		});
+	}
+
+	// puts what can be seen around the cursor in the status bar, in words, for playing without scanning the board.
+	// only what is already on the screen goes into it
+	pub(crate) fn describe(&mut self) {
+		let (x, y) = (self.cursor_x, self.cursor_y);
+		let around = self.surroundings(x, y);
+		let tile = self.shown(x, y);
+		let state = match (tile.visibility, tile.contents) {
+			(TileVis::Open, TileContents::Safe(0)) => {
+				self.message = Some(tr(Msg::DescribeEmpty).into());
+				return;
+			},
+			(TileVis::Open, TileContents::Safe(n)) => {
+				self.message = Some(trf(Msg::DescribeNumber, &[&n, &around.hidden, &around.flagged]));
+				return;
+			},
+			(TileVis::Open, _) => return,
+			(TileVis::Flag | TileVis::AntiFlag, _) => tr(Msg::DescribeFlagged),
+			(TileVis::Hidden, _) => tr(Msg::DescribeHidden),
+		};
+		if around.numbers.is_empty() {
+			self.message = Some(trf(Msg::DescribeAlone, &[&state]));
+			return;
+		}
+		let numbers: Vec<_> = around
+			.numbers
+			.iter()
+			.map(|&((nx, ny), n)| format!("{} ({})", n, side(x, y, nx, ny)))
+			.collect();
+		self.message = Some(trf(Msg::DescribeTouching, &[&state, &numbers.join(", ")]));
+	}
+
+	// the cursor's whole row, or column, as text: `#` hidden, `F` and `A` flags, the numbers, `_` empty,
+	// `*` an opened mine and `X` outside a shaped board. tiles in the fog read as hidden
+	pub fn read_line(&self, row: bool, fog: Option<usize>) -> String {
+		let (x, y) = (self.cursor_x, self.cursor_y);
+		let tiles: Vec<_> = match row {
+			true => (0..self.width).map(|nx| (nx, y)).collect(),
+			false => (0..self.height).map(|ny| (x, ny)).collect(),
+		};
+		let cells: Vec<_> = tiles
+			.iter()
+			.map(|&(nx, ny)| {
+				if self.is_void(nx, ny) {
+					return "X".to_string();
+				}
+				if fog.is_some_and(|radius| self.fogged(radius, nx, ny)) {
+					return "#".to_string();
+				}
+				match (self.get(nx, ny).visibility, self.shown(nx, ny).contents) {
+					(TileVis::Hidden, _) => "#".into(),
+					(TileVis::Flag, _) => "F".into(),
+					(TileVis::AntiFlag, _) => "A".into(),
+					(TileVis::Open, TileContents::Safe(_)) => match self.number(nx, ny) {
+						0 => "_".into(),
+						n => n.to_string(),
+					},
+					(TileVis::Open, _) => "*".into(),
+				}
+			})
+			.collect();
+		match row {
+			true => trf(Msg::ReadRow, &[&(y + 1), &cells.join(" "), &(x + 1)]),
+			false => trf(Msg::ReadColumn, &[&(x + 1), &cells.join(" "), &(y + 1)]),
+		}
+	}
+
+	// shows or hides the number of moves the solver can prove, it counts as help like the frontier jump
+	pub(crate) fn toggle_forced(&mut self) {
+		// the solver would believe the wrong numbers, or get the neighbors wrong
+		if !self.solvable() {
+			self.message = self.line_totals.is_some().then(|| tr(Msg::LineHintsNoSolver).into());
+			return;
+		}
+		self.forced = match self.forced {
+			Some(_) => None,
+			None => {
+				self.assisted = true;
+				Some(self.solver.deduce(&self.view()))
+			},
+		};
+	}
+
+	// hidden tiles the solver can prove safe, and proven mines without a flag
+	pub fn forced_moves(&self) -> Option<usize> {
+		let forced = self.forced.as_ref()?;
+		let unflagged = forced.mines.iter().filter(|&&(x, y)| !matches!(self.get(x, y).visibility, TileVis::Flag));
+		Some(forced.safe.len() + unflagged.count())
+	}
+
+	// move to the nearest frontier tile, repeated presses go to the next nearest
+	pub(crate) fn jump_to_frontier(&mut self) {
+		if self.frontier_cycle.is_none() {
+			let (cursor_x, cursor_y) = (self.cursor_x, self.cursor_y);
+			let mut cells = self.frontier();
+			cells.retain(|&cell| cell != (cursor_x, cursor_y));
+			cells.sort_by_key(|&(x, y)| x.abs_diff(cursor_x) + y.abs_diff(cursor_y));
+			self.frontier_cycle = Some((0, cells));
+		}
+		let (i, cells) = self.frontier_cycle.as_mut().unwrap();
+		if cells.is_empty() {
+			self.message = Some(tr(Msg::NoFrontier).into());
+			return;
+		}
+		(self.cursor_x, self.cursor_y) = cells[*i % cells.len()];
+		*i += 1;
+		self.assisted = true;
+	}
+
+	// moves to the next opened number that still needs work, in reading order and wrapping around
+	pub(crate) fn cycle_unresolved(&mut self, forward: bool) {
+		let cursor = self.index_of(self.cursor_x, self.cursor_y);
+		let numbers: Vec<usize> = (0..self.board.len())
+			.filter(|&i| self.unresolved(i % self.width, i / self.width))
+			.collect();
+		let next = if forward {
+			numbers.iter().find(|&&i| i > cursor).or(numbers.first())
+		} else {
+			numbers.iter().rev().find(|&&i| i < cursor).or(numbers.last())
+		};
+		match next {
+			Some(&i) => (self.cursor_x, self.cursor_y) = (i % self.width, i / self.width),
+			None => self.message = Some(tr(Msg::NoUnresolved).into()),
+		}
+	}
+
+	// an opened number with as many flags around it as its value, anti-mine flags counting as minus one
+	pub(crate) fn satisfied(&self, x: usize, y: usize) -> bool {
+		let TileContents::Safe(n) = self.shown(x, y).contents else {
+			return false;
+		};
+		let flags: i16 = self
+			.neighbors(x, y)
+			.map(|(nx, ny)| match self.get(nx, ny).visibility {
+				TileVis::Flag => 1,
+				TileVis::AntiFlag => -1,
+				_ => 0,
+			})
+			.sum();
+		n > 0 && flags == self.number(x, y)
+	}
+
+	// an opened number that isn't satisfied yet and still has hidden tiles around it
+	pub(crate) fn unresolved(&self, x: usize, y: usize) -> bool {
+		let tile = self.shown(x, y);
+		matches!((tile.visibility, tile.contents), (TileVis::Open, TileContents::Safe(n)) if n > 0)
+			&& !self.satisfied(x, y)
+			&& self.neighbors(x, y).any(|(nx, ny)| matches!(self.get(nx, ny).visibility, TileVis::Hidden))
+	}
+
+	// unflagged hidden tiles next to an opened number
+	pub(crate) fn frontier(&self) -> Vec<(usize, usize)> {
+		let mut cells = Vec::new();
+		for y in 0..self.height {
+			for x in 0..self.width {
+				if self.on_frontier(x, y) {
+					cells.push((x, y));
+				}
+			}
+		}
+		cells
+	}
+
+	// looks only at the tiles around, so it can be asked tile by tile while drawing
+	pub(crate) fn on_frontier(&self, x: usize, y: usize) -> bool {
+		matches!(self.get(x, y).visibility, TileVis::Hidden)
+			&& self.neighbors(x, y).any(|(nx, ny)| {
+				let tile = self.get(nx, ny);
+				matches!((tile.visibility, tile.contents), (TileVis::Open, TileContents::Safe(n)) if n > 0)
+			})
+	}
+
+	// skips over void tiles, wrapping around the edges
+	pub(crate) fn move_cursor(&mut self, direction: Direction) {
+		for _ in 0..self.width.max(self.height) {
+			self.step_cursor(&direction);
+			if !self.is_void(self.cursor_x, self.cursor_y) {
+				break;
+			}
+		}
+	}
+
+	// the indices of the tiles in the cursor's row
+	pub(crate) fn row_tiles(&self) -> std::ops::Range<usize> {
+		let start = self.cursor_y * self.width;
+		start..start + self.width
+	}
+
+	// moves to the first of these tiles that is part of the board
+	pub(crate) fn cursor_to(&mut self, mut indices: impl Iterator<Item = usize>) {
+		if let Some(i) = indices.find(|&i| self.void.is_empty() || !self.void[i]) {
+			(self.cursor_x, self.cursor_y) = (i % self.width, i / self.width);
+		}
+	}
+
+	// typing a count for the next action, a leading 0 does nothing
+	pub(crate) fn add_digit(&mut self, digit: u8) {
+		if digit == 0 && self.count.is_none() {
+			return;
+		}
+		let count = self.count.unwrap_or(0).saturating_mul(10).saturating_add(digit as usize);
+		self.count = Some(count.min(MAX_COUNT));
+	}
+
+	// up to `distance` tiles in one direction, stopping at the edge instead of wrapping.
+	// lands on the farthest of those tiles that is part of the board
+	pub(crate) fn jump_cursor(&mut self, direction: Direction, distance: usize) {
+		let (mut x, mut y) = (self.cursor_x, self.cursor_y);
+		for _ in 0..distance.min(self.width.max(self.height)) {
+			(x, y) = match direction {
+				Direction::Up => (x, y.wrapping_sub(1)),
+				Direction::Down => (x, y + 1),
+				Direction::Left => (x.wrapping_sub(1), y),
+				Direction::Right => (x + 1, y),
+			};
+			if !self.in_bounds(x, y) {
+				break;
+			}
+			if !self.is_void(x, y) {
+				(self.cursor_x, self.cursor_y) = (x, y);
+			}
+		}
+	}
+
+	pub(crate) fn step_cursor(&mut self, direction: &Direction) {
+		match direction {
+			Direction::Up	=> self.cursor_y = self.cursor_y
+				.wrapping_sub(1)
+				.min(self.height - 1),
+			Direction::Down	=> self.cursor_y = self.cursor_y
+				.add(1)
+				.rem(self.height),
+			Direction::Left	=> self.cursor_x = self.cursor_x
+				.wrapping_sub(1)
+				.min(self.width - 1),
+			Direction::Right=> self.cursor_x = self.cursor_x
+				.add(1)
+				.rem(self.width),
+		}
+	}
+
+	// the minimum number of clicks needed to clear the board:
+	// one for every opening plus one for every number not next to an opening
+	pub fn three_bv(&self) -> usize {
+		self.three_bv_progress().1
+	}
+
+	// how much of the 3BV is done, and the whole 3BV: openings with an opened tile and opened numbers outside openings
+	pub fn three_bv_progress(&self) -> (usize, usize) {
+		match self.progress.get() {
+			Some((version, progress)) if version == self.board.version() => progress,
+			_ => {
+				let progress = self.count_three_bv();
+				self.progress.set(Some((self.board.version(), progress)));
+				progress
+			},
+		}
+	}
+
+	pub(crate) fn count_three_bv(&self) -> (usize, usize) {
+		// void tiles look like openings but aren't part of the board
+		let mut reached = vec![false; self.width * self.height];
+		for (i, &v) in self.void.iter().enumerate() {
+			reached[i] = v;
+		}
+		let is_open = |x, y| matches!(self.get(x, y).visibility, TileVis::Open);
+		let mut count = 0;
+		let mut done = 0;
+		for y in 0..self.height {
+			for x in 0..self.width {
+				if reached[self.index_of(x, y)] {
+					continue;
+				}
+				match self.get(x, y).contents {
+					TileContents::Safe(0) => {
+						count += 1;
+						let mut queue = vec![(x, y)];
+						reached[self.index_of(x, y)] = true;
+						let mut opened = false;
+						while let Some((x, y)) = queue.pop() {
+							if let TileContents::Safe(0) = self.get(x, y).contents {
+								opened |= is_open(x, y);
+								for (nx, ny) in self.neighbors(x, y) {
+									let i = self.index_of(nx, ny);
+									if !reached[i] {
+										reached[i] = true;
+										queue.push((nx, ny));
+									}
+								}
+							}
+						}
+						done += opened as usize;
+					},
+					TileContents::Safe(_) => (),
+					TileContents::Mine | TileContents::AntiMine => reached[self.index_of(x, y)] = true,
+				}
+			}
+		}
+		// numbers that no opening reached
+		for (i, _) in reached.iter().enumerate().filter(|(_, &r)| !r) {
+			count += 1;
+			done += is_open(i % self.width, i / self.width) as usize;
+		}
+		(done, count)
+	}
+
+	// the tile a game is considered to start from: the empty tile closest to the center,
+	// or the closest safe tile if there are no empty ones
+	pub(crate) fn start_cell(&self) -> Option<(usize, usize)> {
+		let (cx, cy) = (self.width / 2, self.height / 2);
+		let distance = |&(x, y): &(usize, usize)| x.abs_diff(cx) + y.abs_diff(cy);
+		let cells = (0..self.height)
+			.flat_map(|y| (0..self.width).map(move |x| (x, y)))
+			.filter(|&(x, y)| !self.is_void(x, y));
+		let empty = cells.clone()
+			.filter(|&(x, y)| matches!(self.get(x, y).contents, TileContents::Safe(0)))
+			.min_by_key(distance);
+		empty.or_else(|| cells
+			.filter(|&(x, y)| matches!(self.get(x, y).contents, TileContents::Safe(_)))
+			.min_by_key(distance))
+	}
+
+	// what the player can see of the board
+	pub fn view(&self) -> BoardView {
+		let mut view = BoardView::with_radius(self.width, self.height, self.radius);
+		for y in 0..self.height {
+			for x in 0..self.width {
+				let tile = self.get(x, y);
+				let cell = match (tile.visibility, tile.contents) {
+					_ if self.is_void(x, y) => Cell::Void,
+					(TileVis::Hidden, _) => Cell::Hidden,
+					(TileVis::Flag | TileVis::AntiFlag, _) => Cell::Flagged,
+					(TileVis::Open, TileContents::Safe(n)) => Cell::Open(n),
+					// an opened mine ends the game, nothing to deduce from it
+					(TileVis::Open, TileContents::Mine | TileContents::AntiMine) => Cell::Hidden,
+				};
+				view.set(x, y, cell);
+			}
+		}
+		view
+	}
+
+	pub(crate) fn safe_hidden(&self) -> usize {
+		(0..self.board.len())
+			.filter(|&i| {
+				let tile = self.board.get(i);
+				!matches!(tile.visibility, TileVis::Open) && matches!(tile.contents, TileContents::Safe(_))
+			})
+			.count()
+	}
+
+	pub(crate) fn offsets(&self, x: usize, y: usize) -> &[(i32, i32)] {
+		match self.lattice {
+			Lattice::Square => &self.offsets,
+			Lattice::Triangle if self.points_up(x, y) => &UP_TRIANGLE_OFFSETS,
+			Lattice::Triangle => &DOWN_TRIANGLE_OFFSETS,
+		}
+	}
+
+	pub fn set_radius(&mut self, radius: usize) -> Result<(), MinesweeperError> {
+		if !(1..=MAX_RADIUS).contains(&radius) {
+			return Err(format!("the radius must be between 1 and {}", MAX_RADIUS).into());
+		}
+		self.radius = radius;
+		self.offsets = square_offsets(radius);
+		self.count_neighbors();
+		Ok(())
+	}
+
+	// numbers can have two digits, or a minus sign
+	pub fn wide_numbers(&self) -> bool {
+		if self.anti {
+			return true;
+		}
+		match self.lattice {
+			Lattice::Square => self.offsets.len() > 9,
+			Lattice::Triangle => true,
+		}
+	}
+
+	pub(crate) fn points_up(&self, x: usize, y: usize) -> bool {
+		(x + y).is_multiple_of(2)
+	}
+
+	pub(crate) fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+		self.offsets(x, y)
+			.iter()
+			.map(move |&(dx, dy)| (x.wrapping_add(dx as usize), y.wrapping_add(dy as usize)))
+			.filter(|&(x, y)| self.valid_pos(x, y))
+	}
+
+	// the hidden and flagged tiles around a tile and the opened numbers next to it, as the player sees them
+	pub(crate) fn surroundings(&self, x: usize, y: usize) -> Surroundings {
+		let mut around = Surroundings::default();
+		for (nx, ny) in self.neighbors(x, y) {
+			let tile = self.shown(nx, ny);
+			match (tile.visibility, tile.contents) {
+				(TileVis::Hidden, _) => around.hidden += 1,
+				(TileVis::Flag | TileVis::AntiFlag, _) => around.flagged += 1,
+				(TileVis::Open, TileContents::Safe(n @ 1..)) if !self.is_void(nx, ny) => around.numbers.push(((nx, ny), n)),
+				_ => (),
+			}
+		}
+		around
+	}
+
+	// the tile as the player sees it, with the lie applied to numbers in the liar variant
+	pub(crate) fn shown(&self, x: usize, y: usize) -> Tile {
+		let mut tile = self.get(x, y);
+		if self.is_void(x, y) {
+			return tile;
+		}
+		if let (Some(key), TileContents::Safe(n)) = (self.liar, tile.contents) {
+			let up = match n {
+				0 => true,
+				8 => false,
+				_ => lie_bit(key, self.index_of(x, y)),
+			};
+			tile.contents = TileContents::Safe(if up { n + 1 } else { n - 1 });
+		}
+		tile
+	}
+
+	// the number on a safe tile as the player sees it, which is the mines around it minus the anti-mines
+	// in the anti-mine variant, so it can be 0 or below even with mines next to it
+	pub(crate) fn number(&self, x: usize, y: usize) -> i16 {
+		let TileContents::Safe(n) = self.shown(x, y).contents else {
+			return 0;
+		};
+		if !self.anti {
+			return n as i16;
+		}
+		let anti = self
+			.neighbors(x, y)
+			.filter(|&(nx, ny)| matches!(self.get(nx, ny).contents, TileContents::AntiMine))
+			.count();
+		n as i16 - 2 * anti as i16
+	}
+
+	// the solver and the help built on it know the numbers mean what they say
+	pub(crate) fn solvable(&self) -> bool {
+		self.liar.is_none() && !self.anti && self.lattice == Lattice::Square && self.line_totals.is_none()
+	}
+
+	// outside the radius around the cursor when playing with fog
+	pub fn fogged(&self, radius: usize, x: usize, y: usize) -> bool {
+		x.abs_diff(self.cursor_x).max(y.abs_diff(self.cursor_y)) > radius
+	}
+
+	pub(crate) fn difficulty(&self) -> String {
+		format!("{}x{}x{}", self.width, self.height, self.mines)
+	}
+
+	// callers only pass positions from the board itself, positions from outside go through `open_at`
+	pub(crate) fn get(&self, x: usize, y: usize) -> Tile {
+		debug_assert!(self.in_bounds(x, y), "get outside the board at ({}, {})", x, y);
+		let i = self.index_of(x, y);
+		self.board.get(i)
+	}
+
+	pub(crate) fn set(&mut self, x: usize, y: usize, tile: Tile) {
+		debug_assert!(self.in_bounds(x, y), "set outside the board at ({}, {})", x, y);
+		let i = self.index_of(x, y);
+		self.board.set(i, tile);
+	}
+	
+	// a tile that is part of the board
+	pub(crate) fn valid_pos(&self, x: usize, y: usize) -> bool {
+		self.in_bounds(x, y) && !self.is_void(x, y)
+	}
+
+	pub(crate) fn in_bounds(&self, x: usize, y: usize) -> bool {
+		x < self.width && y < self.height
+	}
+
+	pub(crate) fn is_void(&self, x: usize, y: usize) -> bool {
+		!self.void.is_empty() && self.void[self.index_of(x, y)]
+	}
+
+	pub(crate) fn index_of(&self, x: usize, y: usize) -> usize {
+		x + y * self.width
+	}
+}
+
+#[derive(Copy, Clone)]
+pub struct Tile {
+	pub contents: TileContents,
+	pub visibility: TileVis
+}
+
+#[derive(Copy, Clone)]
+pub enum TileContents {
+	Safe(u8),
+	Mine,
+	// counts as minus one in the numbers around it, in the anti-mine variant
+	AntiMine,
+}
+
+#[derive(Copy, Clone)]
+pub enum TileVis {
+	Hidden,
+	Flag,
+	Open,
+	// flag for an anti-mine
+	AntiFlag,
+}
+
+
+impl Tile {
+	// what tiles outside the shape of the board are stored as
+	pub(crate) fn void() -> Self {
+		Self {
+			contents: TileContents::Safe(0),
+			visibility: TileVis::Open,
+		}
+	}
+
+	pub(crate) fn new(mine: bool) -> Self {
+		let contents = if mine {
+			TileContents::Mine
+		} else {
+			TileContents::Safe(0)
+		};
+		Self {
+			contents,
+			visibility: TileVis::Hidden
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::boardfile;
+	use crate::review::Verdict;
+
+	// random keys, mostly moves, until the game ends, checking the safe tile counter after each
+	#[test]
+	pub(crate) fn safe_counter_agrees_with_the_board() {
+		for seed in 0..200 {
+			let mut game = MSGame::new(8, 7, 9, seed).unwrap();
+			let mut rng = StdRng::seed_from_u64(seed);
+			for _ in 0..500 {
+				let action = match rng.gen_range(0..10) {
+					0..=1 => Action::Open,
+					2 => Action::Flag,
+					3 => Action::Move(Direction::Up),
+					4 => Action::Move(Direction::Left),
+					5..=6 => Action::Move(Direction::Down),
+					_ => Action::Move(Direction::Right),
+				};
+				let turn = game.act(action);
+				assert_eq!(game.safe_left, game.safe_hidden(), "seed {}", seed);
+				assert_eq!(matches!(turn, TurnResult::Win), game.safe_left == 0, "seed {}", seed);
+				if !matches!(turn, TurnResult::Continue) {
+					break;
+				}
+			}
+		}
+	}
+
+	// opens the first hidden safe tile until the game is over
+	pub(crate) fn clear(game: &mut MSGame) -> TurnResult {
+		loop {
+			let safe = (0..game.board.len()).find(|&i| {
+				let tile = game.board.get(i);
+				!game.void.get(i).copied().unwrap_or(false)
+					&& matches!((tile.visibility, tile.contents), (TileVis::Hidden, TileContents::Safe(_)))
+			});
+			let Some(i) = safe else {
+				return game.check_board();
+			};
+			(game.cursor_x, game.cursor_y) = (i % game.width, i / game.width);
+			let turn = game.act(Action::Open);
+			if !matches!(turn, TurnResult::Continue) {
+				return turn;
+			}
+		}
+	}
+
+	#[test]
+	pub(crate) fn donut_board_can_be_cleared() {
+		let (width, height, void) = boardfile::load_shape("shapes/donut.txt").unwrap();
+		for seed in 0..20 {
+			let mut game = MSGame::shaped(width, height, 40, seed, void.clone(), Placement::Uniform).unwrap();
+			for i in (0..game.board.len()).filter(|&i| void[i]) {
+				assert!(!game.board.is_mine(i), "a mine in the hole with seed {}", seed);
+			}
+			assert!(matches!(clear(&mut game), TurnResult::Win), "seed {}", seed);
+		}
+	}
+
+	#[test]
+	pub(crate) fn cursor_stays_off_void_tiles() {
+		let (width, height, void) = boardfile::load_shape("shapes/donut.txt").unwrap();
+		let mut game = MSGame::shaped(width, height, 40, 1, void, Placement::Uniform).unwrap();
+		let mut rng = StdRng::seed_from_u64(1);
+		let directions = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+		for _ in 0..2000 {
+			let direction = directions[rng.gen_range(0..4)];
+			let action = if rng.gen_bool(0.2) { Action::Jump(direction) } else { Action::Move(direction) };
+			game.act(action);
+			assert!(!game.is_void(game.cursor_x, game.cursor_y));
+		}
+	}
+
+	// flags the tiles in order, returning how the game stood after the last one
+	pub(crate) fn flag(game: &mut MSGame, tiles: &[(usize, usize)]) -> TurnResult {
+		let mut turn = TurnResult::Continue;
+		for &(x, y) in tiles {
+			(game.cursor_x, game.cursor_y) = (x, y);
+			turn = game.act(Action::Flag);
+		}
+		turn
+	}
+
+	pub(crate) fn flag_win_game(flag_win: Option<FlagWin>) -> MSGame {
+		let mut game = boardfile::parse("test", "*..\n...\n..*\n").unwrap();
+		game.flag_win = flag_win;
+		game
+	}
+
+	#[test]
+	pub(crate) fn flagging_every_mine_wins() {
+		let mut game = flag_win_game(Some(FlagWin::Also));
+		assert!(matches!(flag(&mut game, &[(0, 0)]), TurnResult::Continue));
+		assert!(matches!(flag(&mut game, &[(2, 2)]), TurnResult::Win));
+	}
+
+	#[test]
+	pub(crate) fn wrong_or_extra_flags_dont_win() {
+		// as many flags as mines, one of them wrong
+		let mut game = flag_win_game(Some(FlagWin::Also));
+		assert!(matches!(flag(&mut game, &[(0, 0), (1, 1)]), TurnResult::Continue));
+		// every mine flagged and one more
+		assert!(matches!(flag(&mut game, &[(2, 2)]), TurnResult::Continue));
+		// taking the wrong one off leaves it exact
+		assert!(matches!(flag(&mut game, &[(1, 1)]), TurnResult::Win));
+	}
+
+	#[test]
+	pub(crate) fn flags_only_win_with_the_rule() {
+		let mut game = flag_win_game(None);
+		assert!(matches!(flag(&mut game, &[(0, 0), (2, 2)]), TurnResult::Continue));
+		assert!(matches!(clear(&mut game), TurnResult::Win));
+	}
+
+	#[test]
+	pub(crate) fn opening_everything_doesnt_win_when_only_flags_do() {
+		let mut game = flag_win_game(Some(FlagWin::Only));
+		assert!(matches!(clear(&mut game), TurnResult::Continue));
+		assert!(matches!(flag(&mut game, &[(0, 0), (2, 2)]), TurnResult::Win));
+	}
+
+	pub(crate) fn open(game: &mut MSGame, x: usize, y: usize) -> TurnResult {
+		(game.cursor_x, game.cursor_y) = (x, y);
+		game.act(Action::Open)
+	}
+
+	#[test]
+	pub(crate) fn opening_a_proven_mine_is_reviewed() {
+		let mut game = boardfile::parse("test", "*...*.\n").unwrap();
+		assert!(matches!(open(&mut game, 2, 0), TurnResult::Continue));
+		assert!(game.review.is_none());
+		assert!(matches!(open(&mut game, 0, 0), TurnResult::Lose));
+		let review = game.review.as_ref().unwrap();
+		assert!(review.last == Some(Verdict::ProvenMine));
+		assert_eq!(review.risky, 1);
+	}
+
+	#[test]
+	pub(crate) fn a_forced_guess_isnt_risky() {
+		let mut game = boardfile::parse("test", "*..\n...\n").unwrap();
+		assert!(matches!(open(&mut game, 2, 1), TurnResult::Continue));
+		assert!(matches!(open(&mut game, 0, 0), TurnResult::Lose));
+		let review = game.review.as_ref().unwrap();
+		assert!(review.last == Some(Verdict::Guess));
+		assert_eq!(review.risky, 0);
+	}
+
+	#[test]
+	pub(crate) fn losing_by_finishing_has_no_last_verdict() {
+		let mut game = boardfile::parse("test", "*..\n...\n").unwrap();
+		open(&mut game, 2, 1);
+		flag(&mut game, &[(0, 1)]);
+		assert!(matches!(game.act(Action::Finish), TurnResult::Lose));
+		let review = game.review.as_ref().unwrap();
+		assert!(review.last.is_none());
+		assert_eq!(review.risky, 0);
+	}
+
+	#[test]
+	pub(crate) fn variants_the_solver_cant_follow_arent_reviewed() {
+		let mut game = boardfile::parse("test", "*...*.\n").unwrap();
+		game.reviewable = false;
+		open(&mut game, 2, 0);
+		assert!(matches!(open(&mut game, 0, 0), TurnResult::Lose));
+		assert!(game.review.is_none());
+	}
+
+	#[test]
+	pub(crate) fn three_bv_progress_follows_the_opens() {
+		for seed in 0..50 {
+			let mut game = MSGame::new(9, 8, 10, seed).unwrap();
+			let mut rng = StdRng::seed_from_u64(seed);
+			while matches!(game.check_board(), TurnResult::Continue) {
+				assert_eq!(game.three_bv_progress(), game.count_three_bv(), "seed {}", seed);
+				open(&mut game, rng.gen_range(0..9), rng.gen_range(0..8));
+			}
+			assert_eq!(game.three_bv_progress(), game.count_three_bv(), "seed {}", seed);
+		}
+	}
+
+	#[test]
+	pub(crate) fn board_and_row_ends_skip_void_tiles() {
+		let mut game = boardfile::parse("test", "X..X\n.*..\nXXXX\nX..X\n").unwrap();
+		let at = |game: &mut MSGame, action| {
+			game.act(action);
+			(game.cursor_x, game.cursor_y)
+		};
+		assert_eq!(at(&mut game, Action::BoardEnd), (2, 3));
+		assert_eq!(at(&mut game, Action::RowStart), (1, 3));
+		assert_eq!(at(&mut game, Action::BoardStart), (1, 0));
+		assert_eq!(at(&mut game, Action::RowEnd), (2, 0));
+		game.cursor_y = 1;
+		assert_eq!(at(&mut game, Action::RowEnd), (3, 1));
+		assert_eq!(at(&mut game, Action::RowStart), (0, 1));
+		// nothing to go to on a row of void tiles
+		(game.cursor_x, game.cursor_y) = (1, 2);
+		assert_eq!(at(&mut game, Action::RowStart), (1, 2));
+		assert_eq!(at(&mut game, Action::RowEnd), (1, 2));
+	}
+}
@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use rand::rngs::StdRng;
+use rand::thread_rng;
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::analyze;
+use crate::boardfile;
+use crate::options::Options;
+use crate::solver::Solver;
+use crate::MSGame;
+
+// writes `count` boards matching the filters to the output directory, returns the exit code.
+// `solver` decides which boards count as no-guess
+pub fn run(options: &Options, count: usize, solver: Rc<dyn Solver>) -> i32 {
+	if let Err(err) = options.placement.check(options.width, options.height, options.mines, &[]) {
+		eprintln!("{}", err);
+		return 2;
+	}
+	let out = Path::new(options.out.as_deref().unwrap_or("."));
+	if let Err(err) = fs::create_dir_all(out) {
+		eprintln!("could not create '{}': {}", out.display(), err);
+		return 2;
+	}
+	// a given --seed makes the whole batch reproducible
+	let mut rng = match options.seed {
+		Some(seed) => StdRng::seed_from_u64(seed),
+		None => StdRng::from_rng(thread_rng()).unwrap(),
+	};
+
+	let mut written = 0;
+	let mut tried = 0;
+	while written < count {
+		let seed = rng.gen();
+		let placement = options.placement.clone();
+		let game = match MSGame::shaped(options.width, options.height, options.mines, seed, Vec::new(), placement) {
+			Ok(game) => game.with_solver(solver.clone()),
+			Err(err) => {
+				eprintln!("{}", err);
+				return err.exit_code();
+			},
+		};
+		tried += 1;
+		if let Some(range) = &options.three_bv {
+			if !range.contains(&game.three_bv()) {
+				continue;
+			}
+		}
+		if options.no_guess {
+			match analyze::solve(&mut game.regenerate(seed)) {
+				Ok(true) => (),
+				Ok(false) => continue,
+				Err(err) => {
+					eprintln!("{}", err);
+					return err.exit_code();
+				},
+			}
+		}
+
+		let path = out.join(format!("board-{}-{}.txt", game.difficulty(), seed));
+		if let Err(err) = fs::write(&path, boardfile::to_text(&game)) {
+			eprintln!("could not write '{}': {}", path.display(), err);
+			return 2;
+		}
+		written += 1;
+		println!("{}/{} {} ({} tried)", written, count, path.display(), tried);
+	}
+	0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::placement::Placement;
+	use crate::solver::Standard;
+
+	fn options(out: &Path) -> Options {
+		Options {
+			width: 9,
+			height: 9,
+			mines: 10,
+			seed: Some(7),
+			out: Some(out.display().to_string()),
+			..Options::default()
+		}
+	}
+
+	fn written(out: &Path) -> Vec<(u64, MSGame)> {
+		let mut boards: Vec<_> = fs::read_dir(out)
+			.unwrap()
+			.map(|entry| {
+				let path = entry.unwrap().path();
+				let seed = path.file_stem().unwrap().to_str().unwrap().rsplit('-').next().unwrap().parse().unwrap();
+				(seed, boardfile::load(path.to_str().unwrap()).unwrap())
+			})
+			.collect();
+		boards.sort_by_key(|(seed, _)| *seed);
+		boards
+	}
+
+	#[test]
+	fn boards_load_back_as_generated() {
+		let out = std::env::temp_dir().join(format!("minesweeper-generate-{}", std::process::id()));
+		assert_eq!(run(&options(&out), 5, Rc::new(Standard)), 0);
+		let boards = written(&out);
+		assert_eq!(boards.len(), 5);
+		for (seed, game) in boards {
+			let generated = MSGame::shaped(9, 9, 10, seed, Vec::new(), Placement::Uniform).unwrap();
+			assert_eq!(game.seed, Some(seed));
+			assert_eq!(boardfile::to_text(&game), boardfile::to_text(&generated));
+		}
+		fs::remove_dir_all(out).unwrap();
+	}
+
+	#[test]
+	fn filters_hold_for_every_board() {
+		let out = std::env::temp_dir().join(format!("minesweeper-generate-filtered-{}", std::process::id()));
+		let options = Options {
+			no_guess: true,
+			three_bv: Some(5..=30),
+			..options(&out)
+		};
+		assert_eq!(run(&options, 3, Rc::new(Standard)), 0);
+		for (_, mut game) in written(&out) {
+			assert!((5..=30).contains(&game.three_bv()));
+			assert!(analyze::solve(&mut game).unwrap());
+		}
+		fs::remove_dir_all(out).unwrap();
+	}
+}
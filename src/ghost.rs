@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::coop::Coop;
+use crate::lang::{trf, Msg};
+use crate::records::data_dir;
+use crate::replay::{self, Replay};
+use crate::{MSGame, TurnResult};
+
+// the best run on a seeded board, played along with a new attempt at it on a board of its own.
+// it is only ever drawn, nothing it does reaches the real game
+pub struct Ghost {
+	replay: Replay,
+	// the board before the first move, to start over from
+	start: MSGame,
+	game: MSGame,
+	coop: Option<Coop>,
+	rush_rng: StdRng,
+	// moves already played
+	played: usize,
+	safe_total: usize,
+	pub shown: bool,
+}
+
+impl Ghost {
+	// the ghost for `code` if the best time on it was recorded, `start` is the board as it is before the first move
+	pub fn load(code: &str, start: &MSGame, coop: Option<Coop>) -> Option<Self> {
+		let path = path(code)?;
+		let replay = Replay::load(path.to_str()?).ok()?;
+		Some(Self {
+			replay,
+			game: start.clone(),
+			start: start.clone(),
+			coop,
+			rush_rng: StdRng::seed_from_u64(start.seed.unwrap_or_default()),
+			played: 0,
+			safe_total: start.safe_left,
+			shown: true,
+		})
+	}
+
+	// back to the first move, for another attempt at the board
+	pub fn restart(&mut self) {
+		self.game = self.start.clone();
+		self.rush_rng = StdRng::seed_from_u64(self.start.seed.unwrap_or_default());
+		self.played = 0;
+	}
+
+	// plays the moves made up to `elapsed` into the run, true if there were any
+	pub fn advance(&mut self, elapsed: Duration) -> bool {
+		let elapsed = elapsed.as_millis() as u64;
+		let due: Vec<_> = self
+			.replay
+			.moves()
+			.skip(self.played)
+			.take_while(|&(ms, _)| ms <= elapsed)
+			.map(|(_, key)| key.cloned())
+			.collect();
+		for key in &due {
+			let result = match key {
+				Some(key) => replay::play_key(&mut self.game, self.coop.as_ref(), key),
+				None => {
+					self.game.add_mine(&mut self.rush_rng);
+					TurnResult::Continue
+				},
+			};
+			self.game.events.clear();
+			self.game.message = None;
+			if !matches!(result, TurnResult::Continue) {
+				// nothing after the end of the run
+				self.played = usize::MAX;
+				return true;
+			}
+		}
+		self.played = self.played.saturating_add(due.len());
+		!due.is_empty()
+	}
+
+	pub fn cursor(&self) -> Option<(usize, usize)> {
+		self.shown.then_some((self.game.cursor_x, self.game.cursor_y))
+	}
+
+	// how far the ghost has come and how far ahead or behind it the player is, for the status bar
+	pub fn status(&self, safe_left: usize, elapsed: Duration) -> Option<String> {
+		if !self.shown {
+			return None;
+		}
+		let cleared = self.safe_total - self.game.safe_left.min(self.safe_total);
+		let percent = 100 * cleared / self.safe_total.max(1);
+		// nothing to compare before the first tile is open
+		let reached = self.replay.reached(safe_left).filter(|_| safe_left < self.safe_total);
+		Some(match reached {
+			Some(ms) if elapsed.as_millis() as u64 <= ms => {
+				let ahead = (ms - elapsed.as_millis() as u64) as f64 / 1000.0;
+				trf(Msg::GhostAhead, &[&percent, &format!("{:.1}", ahead)])
+			},
+			Some(ms) => {
+				let behind = (elapsed.as_millis() as u64 - ms) as f64 / 1000.0;
+				trf(Msg::GhostBehind, &[&percent, &format!("{:.1}", behind)])
+			},
+			None => trf(Msg::GhostStatus, &[&percent]),
+		})
+	}
+}
+
+// keeps the recording of a new best time on a seeded board for the ghost of the next attempts
+pub fn save(code: &str, recording: &mut Replay, result: &TurnResult, elapsed: Duration) -> Result<(), String> {
+	let path = path(code).ok_or("no data directory for the ghost")?;
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir).map_err(|err| format!("could not create '{}': {}", dir.display(), err))?;
+	}
+	recording.save(&path.to_string_lossy(), result, elapsed)
+}
+
+// codes have a colon, which not every file system allows
+fn path(code: &str) -> Option<PathBuf> {
+	Some(data_dir()?.join("ghosts").join(format!("{}.msr", code.replace(':', "_"))))
+}
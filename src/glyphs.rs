@@ -0,0 +1,79 @@
+use std::borrow::Cow;
+
+use console::Term;
+
+// characters outside of plain ASCII, with replacements for terminals that can't show them
+// (like legacy Windows consoles)
+#[derive(Clone, Copy)]
+pub struct Glyphs {
+	pub unicode: bool,
+	pub vertical: char,
+	pub horizontal: char,
+	pub cross: char,
+	pub ellipsis: &'static str,
+	// from fully opened to fully hidden
+	pub shades: [char; 4],
+	// hidden tiles on a triangle board
+	pub up_triangle: char,
+	pub down_triangle: char,
+}
+
+pub const UNICODE: Glyphs = Glyphs {
+	unicode: true,
+	vertical: '│',
+	horizontal: '─',
+	cross: '┼',
+	ellipsis: "…",
+	shades: [' ', '░', '▒', '▓'],
+	up_triangle: '▲',
+	down_triangle: '▼',
+};
+
+pub const ASCII: Glyphs = Glyphs {
+	unicode: false,
+	vertical: '|',
+	horizontal: '-',
+	cross: '+',
+	ellipsis: "...",
+	shades: [' ', '.', ':', '#'],
+	up_triangle: '^',
+	down_triangle: 'v',
+};
+
+impl Glyphs {
+	pub fn detect(stdout: &Term) -> Self {
+		if stdout.features().wants_emoji() {
+			UNICODE
+		} else {
+			ASCII
+		}
+	}
+
+	pub fn by_name(name: &str) -> Result<Self, String> {
+		match name {
+			"unicode" => Ok(UNICODE),
+			"ascii" => Ok(ASCII),
+			_ => Err(format!("unknown glyph set '{}', expected unicode or ascii", name)),
+		}
+	}
+
+	// replaces the symbols used in interface text
+	pub fn text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+		if self.unicode || text.is_ascii() {
+			return Cow::Borrowed(text);
+		}
+		let mut out = String::with_capacity(text.len());
+		for c in text.chars() {
+			match c {
+				'←' => out.push('<'),
+				'↑' => out.push('^'),
+				'↓' => out.push('v'),
+				'→' => out.push('>'),
+				'·' => out.push('|'),
+				'…' => out.push_str("..."),
+				_ => out.push(c),
+			}
+		}
+		Cow::Owned(out)
+	}
+}
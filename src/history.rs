@@ -0,0 +1,156 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+use crate::date;
+use crate::options::Options;
+use crate::records::data_dir;
+use crate::review::Review;
+use crate::{MSGame, TurnResult};
+
+const HEADER: &str = "timestamp,width,height,mines,seed,result,elapsed,moves,3bv,assisted,mode,risky,verdict,safe_at,options,replay,clicks\n";
+// earlier versions of the header, files starting with one get the new one
+const OLD_HEADERS: [&str; 3] = [
+	"timestamp,width,height,mines,seed,result,elapsed,moves,3bv,assisted,mode\n",
+	"timestamp,width,height,mines,seed,result,elapsed,moves,3bv,assisted,mode,risky,verdict,safe_at\n",
+	"timestamp,width,height,mines,seed,result,elapsed,moves,3bv,assisted,mode,risky,verdict,safe_at,options,replay\n",
+];
+
+// appends one line per finished game to history.csv.
+// each line goes out in a single append write so games finishing at the same time don't mix
+pub fn append(game: &MSGame, options: &Options, result: &TurnResult, elapsed: Duration) {
+	let Some(path) = history_path() else {
+		return;
+	};
+	if let Some(dir) = path.parent() {
+		let _ = std::fs::create_dir_all(dir);
+	}
+	migrate(&path);
+	let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) else {
+		return;
+	};
+	let timestamp = date::now();
+	// the absolute path, the history can be browsed from anywhere
+	let replay = options.record.as_ref().and_then(|path| fs::canonicalize(path).ok()).map(|path| path.display().to_string());
+	let (verdict, safe_at) = game.review.as_ref().map(Review::columns).unwrap_or_default();
+	let result = match result {
+		TurnResult::Win => "win",
+		TurnResult::Lose => "loss",
+		TurnResult::Surrender => "surrender",
+		_ => "quit",
+	};
+	let mut line = format!(
+		"{},{},{},{},{},{},{:.1},{},{},{},{},{},{},{},{},{},{}\n",
+		timestamp,
+		game.width,
+		game.height,
+		game.mines,
+		game.seed.map_or(String::new(), |seed| seed.to_string()),
+		result,
+		elapsed.as_secs_f64(),
+		game.moves,
+		game.three_bv(),
+		game.assisted,
+		options.mode().unwrap_or_default(),
+		game.review.as_ref().map_or(String::new(), |review| review.risky.to_string()),
+		verdict,
+		safe_at,
+		// the options that made the board, to play it again from the history. paths can have commas in them
+		quote(&options.game_args().join(" ")),
+		quote(&replay.unwrap_or_default()),
+		game.clicks,
+	);
+	if file.metadata().is_ok_and(|meta| meta.len() == 0) {
+		line.insert_str(0, HEADER);
+	}
+	let _ = file.write_all(line.as_bytes());
+}
+
+// the new columns go on the end, so old lines only need the header swapped. only the first line is read
+// unless it is an old header, and then the new file is written next to the old one and renamed over it,
+// so a crash or another game finishing halfway never leaves the history cut short
+fn migrate(path: &Path) {
+	let Ok(file) = File::open(path) else {
+		return;
+	};
+	let mut reader = BufReader::new(file);
+	let mut header = String::new();
+	if reader.read_line(&mut header).is_err() || !OLD_HEADERS.contains(&header.as_str()) {
+		return;
+	}
+	let temp = path.with_extension(format!("csv.{}.tmp", process::id()));
+	let migrated = File::create(&temp).and_then(|mut out| {
+		out.write_all(HEADER.as_bytes())?;
+		io::copy(&mut reader, &mut out)?;
+		out.sync_all()
+	});
+	if migrated.and_then(|()| fs::rename(&temp, path)).is_err() {
+		let _ = fs::remove_file(&temp);
+	}
+}
+
+// quoted only when needed, with quotes inside doubled
+pub fn quote(field: &str) -> String {
+	if field.contains([',', '"', '\r', '\n']) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.into()
+	}
+}
+
+// the fields of a line, with the quotes taken off the quoted ones
+pub fn fields(line: &str) -> Vec<String> {
+	let mut fields = vec![String::new()];
+	let mut quoted = false;
+	let mut chars = line.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if quoted && chars.peek() == Some(&'"') => {
+				chars.next();
+				fields.last_mut().unwrap().push('"');
+			},
+			'"' => quoted = !quoted,
+			',' if !quoted => fields.push(String::new()),
+			c => fields.last_mut().unwrap().push(c),
+		}
+	}
+	fields
+}
+
+// the whole history file, for exporting
+pub fn read() -> io::Result<String> {
+	let path = history_path().ok_or(io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+	fs::read_to_string(path)
+}
+
+fn history_path() -> Option<PathBuf> {
+	Some(data_dir()?.join("history.csv"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn migrates_old_headers_once() {
+		let path = std::env::temp_dir().join(format!("minesweeper-history-{}.csv", process::id()));
+		let line = "1700000000,9,9,10,,win,12.0,30,20,false,\n";
+		fs::write(&path, format!("{}{}", OLD_HEADERS[0], line)).unwrap();
+		migrate(&path);
+		assert_eq!(fs::read_to_string(&path).unwrap(), format!("{}{}", HEADER, line));
+		migrate(&path);
+		assert_eq!(fs::read_to_string(&path).unwrap(), format!("{}{}", HEADER, line));
+		fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn quoted_fields_keep_their_commas() {
+		let replay = "/home/a,b/say \"hi\".msr";
+		let line = format!("1700000000,win,{},18", quote(replay));
+		assert_eq!(line, "1700000000,win,\"/home/a,b/say \"\"hi\"\".msr\",18");
+		assert_eq!(fields(&line), ["1700000000", "win", replay, "18"]);
+		assert_eq!(fields("1,,3"), ["1", "", "3"]);
+	}
+}
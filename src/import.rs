@@ -0,0 +1,394 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use console::Key;
+
+use crate::boardfile;
+use crate::input::command_key;
+use crate::options::{apply_variants, Options};
+use crate::replay::{play_key, Replay};
+use crate::{MSGame, TileContents, TileVis, TurnResult};
+
+// the raw video format (RAWVF) that Viennasweeper and the Minesweeper Arbiter tools export their
+// .avf and .rmv videos to, as text:
+//
+//   RawVF_Version: Rev5
+//   Width: 8
+//   Height: 8
+//   Mines: 10
+//   Marks: Off
+//   Board:
+//   *.......
+//   ...
+//   Events:
+//   0.00 start
+//   0.00 lc 3 5 (40 72)
+//   0.05 lr 3 5 (40 72)
+//
+// header fields, the board with `*` for mines, then the mouse events: the time in seconds, the
+// event, and for clicks the column and row counted from 1 and the mouse position in pixels.
+// lc, rc and mc press the left, right and middle button and lr, rr and mr release them.
+// the binary .avf and .rmv files themselves aren't read
+pub fn run(path: &str) -> i32 {
+	match import(path) {
+		Ok(lines) => {
+			for line in lines {
+				println!("{}", line);
+			}
+			0
+		},
+		Err(err) => {
+			eprintln!("{}", err);
+			2
+		},
+	}
+}
+
+// writes the board and the replay next to the file, returns what to tell the player
+fn import(path: &str) -> Result<Vec<String>, String> {
+	if let Some(ext @ ("avf" | "rmv")) = Path::new(path).extension().and_then(|ext| ext.to_str()) {
+		return Err(format!(
+			"'{}': binary .{} videos can't be read, export them as a RAW video (.rawvf) with Viennasweeper first",
+			path, ext
+		));
+	}
+	let text = fs::read_to_string(path).map_err(|err| format!("could not read '{}': {}", path, err))?;
+	let video = parse(path, &text)?;
+
+	let stem = path.strip_suffix(".rawvf").unwrap_or(path);
+	let (board_path, replay_path) = (format!("{}-board.txt", stem), format!("{}.msr", stem));
+	let mut game = boardfile::parse(path, &video.board).map_err(|err| err.to_string())?;
+	fs::write(&board_path, &video.board).map_err(|err| format!("could not write '{}': {}", board_path, err))?;
+	let options = Options {
+		width: game.width,
+		height: game.height,
+		mines: game.mines,
+		board: Some(board_path.clone()),
+		..Options::default()
+	};
+	apply_variants(&mut game, &options).map_err(|err| err.to_string())?;
+
+	let mut player = Player {
+		replay: Replay::new(&options, &game),
+		game,
+		result: TurnResult::Continue,
+		last_ms: 0,
+		skipped: BTreeMap::new(),
+	};
+	player.play(&video.events);
+	let elapsed = Duration::from_millis(player.last_ms);
+	let mut skipped = video.skipped;
+	for (what, count) in player.skipped {
+		*skipped.entry(what).or_default() += count;
+	}
+	player.replay.save(&replay_path, &player.result, elapsed)?;
+
+	let mut lines = vec![format!("wrote the board to '{}' and the replay to '{}', watch it with --watch {}", board_path, replay_path, replay_path)];
+	if video.marks {
+		lines.push("question marks were on, right clicks are only kept as flags".into());
+	}
+	for (what, count) in skipped {
+		lines.push(format!("left out {} {}", count, what));
+	}
+	Ok(lines)
+}
+
+struct Video {
+	// in the board file format
+	board: String,
+	events: Vec<Event>,
+	marks: bool,
+	// what couldn't be read, and how many times
+	skipped: BTreeMap<String, usize>,
+}
+
+struct Event {
+	secs: f64,
+	kind: Click,
+	// from 0
+	x: usize,
+	y: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Click {
+	LeftDown,
+	LeftUp,
+	RightDown,
+	RightUp,
+	MiddleDown,
+	MiddleUp,
+}
+
+fn parse(path: &str, text: &str) -> Result<Video, String> {
+	let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line.trim_end()));
+	let mut header = BTreeMap::new();
+	for (_, line) in lines.by_ref() {
+		if line == "Board:" {
+			break;
+		}
+		if let Some((key, value)) = line.split_once(':') {
+			header.insert(key.trim().to_string(), value.trim().to_string());
+		}
+	}
+	if !header.contains_key("RawVF_Version") {
+		return Err(format!("'{}' is not a RAW video, it has no RawVF_Version", path));
+	}
+	let number = |key: &str| -> Result<usize, String> {
+		let value = header.get(key).ok_or_else(|| format!("{}: no {} in the header", path, key))?;
+		value.parse().map_err(|_| format!("{}: invalid {} '{}'", path, key, value))
+	};
+	let (width, height, mines) = (number("Width")?, number("Height")?, number("Mines")?);
+
+	let mut board = String::new();
+	for _ in 0..height {
+		let Some((n, line)) = lines.next() else {
+			return Err(format!("{}: the board ends before its {} rows", path, height));
+		};
+		if line.chars().count() != width {
+			return Err(format!("{}:{}: expected a row of {} tiles", path, n, width));
+		}
+		board.extend(line.chars().map(|c| if c == '*' { '*' } else { '.' }));
+		board.push('\n');
+	}
+	if board.matches('*').count() != mines {
+		return Err(format!("{}: the board doesn't have the {} mines the header says", path, mines));
+	}
+	if !lines.by_ref().any(|(_, line)| line == "Events:") {
+		return Err(format!("{}: no events", path));
+	}
+
+	let mut events = Vec::new();
+	let mut skipped = BTreeMap::new();
+	for (n, line) in lines.filter(|(_, line)| !line.is_empty()) {
+		let fields: Vec<_> = line.split_whitespace().collect();
+		let secs: f64 = fields[0].parse().map_err(|_| format!("{}:{}: invalid time '{}'", path, n, fields[0]))?;
+		let kind = match fields.get(1).copied() {
+			Some("lc") => Click::LeftDown,
+			Some("lr") => Click::LeftUp,
+			Some("rc") => Click::RightDown,
+			Some("rr") => Click::RightUp,
+			Some("mc") => Click::MiddleDown,
+			Some("mr") => Click::MiddleUp,
+			// the mouse moving, and the start and end of the game, which the replay finds out for itself
+			Some("mv" | "start" | "won" | "blast") => continue,
+			Some(other) => {
+				*skipped.entry(format!("'{}' events", other)).or_default() += 1;
+				continue;
+			},
+			None => return Err(format!("{}:{}: expected an event after the time", path, n)),
+		};
+		let pos = |i: usize| fields.get(i).and_then(|value| value.parse::<usize>().ok());
+		match (pos(2), pos(3)) {
+			(Some(x @ 1..), Some(y @ 1..)) if x <= width && y <= height => events.push(Event {
+				secs: secs.max(0.0),
+				kind,
+				x: x - 1,
+				y: y - 1,
+			}),
+			_ => *skipped.entry("button events outside the board".into()).or_default() += 1,
+		}
+	}
+	let marks = header.get("Marks").is_some_and(|marks| marks.eq_ignore_ascii_case("on"));
+	Ok(Video { board, events, marks, skipped })
+}
+
+// turns the clicks into the keys this game is played with, playing them as it goes to know what each click did
+struct Player {
+	replay: Replay,
+	game: MSGame,
+	result: TurnResult,
+	// when the last key was pressed, the time the game took
+	last_ms: u64,
+	skipped: BTreeMap<String, usize>,
+}
+
+impl Player {
+	// a left click opens a hidden tile when the button comes up, a right click flags or unflags one when it goes
+	// down. releasing one button while the other is down, or the middle button, is a chord, which this game
+	// doesn't have, so it becomes opening every tile the chord would open
+	fn play(&mut self, events: &[Event]) {
+		let (mut left, mut right, mut chorded) = (false, false, false);
+		for event in events {
+			if !matches!(self.result, TurnResult::Continue) {
+				*self.skipped.entry("button events after the game ended".into()).or_default() += 1;
+				continue;
+			}
+			let ms = (event.secs * 1000.0).round() as u64;
+			let at = (event.x, event.y);
+			match event.kind {
+				Click::LeftDown => left = true,
+				Click::RightDown => {
+					right = true;
+					if !left {
+						self.flag(ms, at);
+					}
+				},
+				Click::MiddleDown => (),
+				Click::LeftUp | Click::RightUp if left && right => {
+					self.chord(ms, at);
+					chorded = true;
+				},
+				Click::LeftUp if !chorded => self.open(ms, at),
+				Click::MiddleUp => self.chord(ms, at),
+				_ => (),
+			}
+			match event.kind {
+				Click::LeftUp => left = false,
+				Click::RightUp => right = false,
+				_ => (),
+			}
+			if !left && !right {
+				chorded = false;
+			}
+		}
+	}
+
+	fn open(&mut self, ms: u64, at: (usize, usize)) {
+		if matches!(self.game.get(at.0, at.1).visibility, TileVis::Hidden) {
+			self.press_at(ms, at, "open");
+		}
+	}
+
+	fn flag(&mut self, ms: u64, at: (usize, usize)) {
+		if !matches!(self.game.get(at.0, at.1).visibility, TileVis::Open) {
+			self.press_at(ms, at, "flag");
+		}
+	}
+
+	// on an opened number with as many flags around it, opens the other hidden tiles around it
+	fn chord(&mut self, ms: u64, (x, y): (usize, usize)) {
+		let tile = self.game.get(x, y);
+		let (TileVis::Open, TileContents::Safe(n @ 1..)) = (tile.visibility, tile.contents) else {
+			return;
+		};
+		let around: Vec<_> = self.game.neighbors(x, y).collect();
+		let flags = around.iter().filter(|&&(nx, ny)| matches!(self.game.get(nx, ny).visibility, TileVis::Flag)).count();
+		if flags != n as usize {
+			return;
+		}
+		for at in around {
+			if matches!(self.result, TurnResult::Continue) {
+				self.open(ms, at);
+			}
+		}
+	}
+
+	// moves the cursor there with the arrows, then presses the key
+	fn press_at(&mut self, ms: u64, (x, y): (usize, usize), command: &str) {
+		let (cursor_x, cursor_y) = (self.game.cursor_x, self.game.cursor_y);
+		let horizontal = if x < cursor_x { "left" } else { "right" };
+		let vertical = if y < cursor_y { "up" } else { "down" };
+		let steps = [(horizontal, x.abs_diff(cursor_x)), (vertical, y.abs_diff(cursor_y)), (command, 1)];
+		for (command, times) in steps {
+			for _ in 0..times {
+				self.press(ms, command_key(command).expect("a known command"));
+			}
+		}
+	}
+
+	fn press(&mut self, ms: u64, key: Key) {
+		self.result = play_key(&mut self.game, None, &key);
+		self.game.events.clear();
+		self.game.message = None;
+		self.replay.key(Duration::from_millis(ms), key, &self.game);
+		self.last_ms = ms;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::env;
+
+	use super::*;
+
+	const WIN: &str = include_str!("../imports/custom-win.rawvf");
+	const LOSS: &str = include_str!("../imports/custom-loss.rawvf");
+
+	// plays the video on its board
+	fn played(video: &Video) -> Player {
+		let game = boardfile::parse("test", &video.board).unwrap();
+		let options = Options {
+			width: game.width,
+			height: game.height,
+			mines: game.mines,
+			..Options::default()
+		};
+		let mut player = Player {
+			replay: Replay::new(&options, &game),
+			game,
+			result: TurnResult::Continue,
+			last_ms: 0,
+			skipped: BTreeMap::new(),
+		};
+		player.play(&video.events);
+		player
+	}
+
+	#[test]
+	fn reads_a_won_video() {
+		let video = parse("custom-win.rawvf", WIN).unwrap();
+		assert_eq!(video.board, "........\n........\n.....*..\n*.......\n......*.\n..*.....\n");
+		// the moves and the start and end of the game are left out, the button events are kept
+		assert_eq!(video.events.len(), 18);
+		let first = &video.events[0];
+		assert!(first.kind == Click::LeftDown && (first.x, first.y) == (0, 0) && first.secs == 0.35);
+		assert!(!video.marks);
+		assert!(video.skipped.is_empty());
+
+		let player = played(&video);
+		assert!(matches!(player.result, TurnResult::Win));
+		assert_eq!(player.last_ms, 3480);
+		assert!(player.skipped.is_empty());
+	}
+
+	#[test]
+	fn reads_a_lost_video() {
+		let video = parse("custom-loss.rawvf", LOSS).unwrap();
+		assert_eq!(video.board, "....*\n.....\n*....\n....*\n");
+		assert_eq!(video.events.len(), 8);
+		assert!(video.marks);
+		let skipped: Vec<_> = video.skipped.iter().map(|(what, &count)| (what.as_str(), count)).collect();
+		assert_eq!(skipped, [("'sc' events", 1), ("button events outside the board", 2)]);
+
+		// the clicks after the blast aren't played
+		let player = played(&video);
+		assert!(matches!(player.result, TurnResult::Lose));
+		assert_eq!(player.last_ms, 2170);
+		assert_eq!(player.skipped.get("button events after the game ended"), Some(&2));
+	}
+
+	#[test]
+	fn rejects_broken_videos() {
+		let err = |text: &str| parse("test.rawvf", text).err().unwrap();
+		assert_eq!(err("Width: 8\n"), "'test.rawvf' is not a RAW video, it has no RawVF_Version");
+		assert_eq!(err(&WIN.replace("Mines: 4", "Mines: 5")), "test.rawvf: the board doesn't have the 5 mines the header says");
+		assert_eq!(err(&WIN.replace("Height: 6", "Height: 7")), "test.rawvf:18: expected a row of 8 tiles");
+		assert_eq!(err(&WIN.replace("Width: 8", "Width: x")), "test.rawvf: invalid Width 'x'");
+		assert_eq!(err(&WIN.replace("0.35 lc", "soon lc")), "test.rawvf:20: invalid time 'soon'");
+	}
+
+	#[test]
+	fn imports_a_video_as_a_replay_that_passes() {
+		let dir = env::temp_dir().join(format!("minesweeper-import-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let path = dir.join("custom-win.rawvf");
+		fs::write(&path, WIN).unwrap();
+		let path = path.to_str().unwrap();
+
+		let lines = import(path).unwrap();
+		assert_eq!(lines.len(), 1);
+		let stem = path.strip_suffix(".rawvf").unwrap();
+		let board = fs::read_to_string(format!("{}-board.txt", stem)).unwrap();
+		assert_eq!(board, parse(path, WIN).unwrap().board);
+		assert_eq!(crate::replay::verify(&format!("{}.msr", stem)), 0);
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn binary_videos_are_turned_away() {
+		assert!(import("game.avf").unwrap_err().contains("export them as a RAW video (.rawvf)"));
+	}
+}
@@ -0,0 +1,144 @@
+use std::io;
+use std::io::BufRead;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use console::Key;
+use console::Term;
+
+// reads keys on a separate thread so the main loop can wait for input with a timeout
+pub fn spawn_reader() -> Receiver<Key> {
+	let (sender, receiver) = mpsc::channel();
+	thread::spawn(move || {
+		let term = Term::stdout();
+		while let Ok(key) = term.read_key() {
+			let key = match key {
+				Key::UnknownEscSeq(seq) if seq == ['[', '1', ';'] => modified_arrow(&term),
+				key => key,
+			};
+			if sender.send(key).is_err() {
+				break;
+			}
+		}
+	});
+	receiver
+}
+
+// xterm sends arrows with modifiers as ESC [ 1 ; <modifier> <A-D>, which console only reads up to the `;`.
+// shift (modifier 2) is turned into the fallback keys HJKL, anything else is dropped.
+// terminals that send shifted arrows like plain ones can only use HJKL
+fn modified_arrow(term: &Term) -> Key {
+	let (Ok(Key::Char(modifier)), Ok(Key::Char(arrow))) = (term.read_key(), term.read_key()) else {
+		return Key::Unknown;
+	};
+	match (modifier, arrow) {
+		('2', 'A') => Key::Char('K'),
+		('2', 'B') => Key::Char('J'),
+		('2', 'C') => Key::Char('L'),
+		('2', 'D') => Key::Char('H'),
+		_ => Key::Unknown,
+	}
+}
+
+// reads commands from stdin when it isn't a terminal, with any number of commands per line:
+// up, down, left, right, home, end, tab, backtab, open, flag, next, zoom, minimap, dim, highlight, forced, check, rates, frontier, transpose, guess, restart, quit, or a single character for that key.
+// the end of the input quits
+pub fn spawn_command_reader() -> Receiver<Key> {
+	let (sender, receiver) = mpsc::channel();
+	thread::spawn(move || {
+		for line in io::stdin().lock().lines() {
+			let Ok(line) = line else {
+				break;
+			};
+			for command in line.split_whitespace() {
+				let Some(key) = command_key(command) else {
+					eprintln!("unknown command '{}'", command);
+					continue;
+				};
+				if sender.send(key).is_err() {
+					return;
+				}
+			}
+		}
+		let _ = sender.send(Key::Char('q'));
+	});
+	receiver
+}
+
+pub fn command_key(command: &str) -> Option<Key> {
+	let key = match command {
+		"up" => Key::ArrowUp,
+		"down" => Key::ArrowDown,
+		"left" => Key::ArrowLeft,
+		"right" => Key::ArrowRight,
+		"open" => Key::Char(' '),
+		"finish" => Key::Enter,
+		"flag" => Key::Char('f'),
+		"antiflag" => Key::Char('F'),
+		"next" => Key::Char('n'),
+		"zoom" => Key::Char('z'),
+		"minimap" => Key::Char('m'),
+		"dim" => Key::Char('d'),
+		"highlight" => Key::Char('h'),
+		"forced" => Key::Char('c'),
+		"check" => Key::Char('a'),
+		"rates" => Key::Char('e'),
+		"frontier" => Key::Char('b'),
+		"transpose" => Key::Char('t'),
+		"ghost" => Key::Char('v'),
+		"guess" => Key::Char('p'),
+		"describe" => Key::Char('i'),
+		"row" => Key::Char('R'),
+		"column" => Key::Char('C'),
+		"restart" => Key::Char('r'),
+		"quit" => Key::Char('q'),
+		"surrender" => Key::Char('!'),
+		"home" => Key::Home,
+		"end" => Key::End,
+		"tab" => Key::Tab,
+		"backtab" => Key::BackTab,
+		_ => {
+			let mut chars = command.chars();
+			match (chars.next(), chars.next()) {
+				(Some(c), None) => Key::Char(c),
+				_ => return None,
+			}
+		}
+	};
+	Some(key)
+}
+
+// on a transposed board the arrows move the way it is drawn: up on the screen is left on the board
+pub fn transposed(key: Key) -> Key {
+	match key {
+		Key::ArrowUp => Key::ArrowLeft,
+		Key::ArrowLeft => Key::ArrowUp,
+		Key::ArrowDown => Key::ArrowRight,
+		Key::ArrowRight => Key::ArrowDown,
+		Key::Char('K') => Key::Char('H'),
+		Key::Char('H') => Key::Char('K'),
+		Key::Char('J') => Key::Char('L'),
+		Key::Char('L') => Key::Char('J'),
+		key => key,
+	}
+}
+
+// the command for a key, the way replays store them
+pub fn key_command(key: &Key) -> Option<String> {
+	let command = match key {
+		Key::ArrowUp => "up",
+		Key::ArrowDown => "down",
+		Key::ArrowLeft => "left",
+		Key::ArrowRight => "right",
+		Key::Home => "home",
+		Key::End => "end",
+		Key::Tab => "tab",
+		Key::BackTab => "backtab",
+		Key::Enter => "finish",
+		Key::Escape => "quit",
+		Key::Char(' ') => "open",
+		Key::Char(c) if !c.is_whitespace() => return Some(c.to_string()),
+		_ => return None,
+	};
+	Some(command.into())
+}
@@ -0,0 +1,185 @@
+use std::fmt::Write as _;
+use std::iter::Peekable;
+use std::str::Chars;
+
+// just enough json to read back the small files this game writes itself
+#[derive(PartialEq)]
+pub enum Value {
+	Null,
+	Bool(bool),
+	Number(f64),
+	String(String),
+	Array(Vec<Value>),
+	Object(Vec<(String, Value)>),
+}
+
+impl Value {
+	pub fn get(&self, key: &str) -> Option<&Value> {
+		match self {
+			Self::Object(fields) => fields.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+			_ => None,
+		}
+	}
+
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Self::String(text) => Some(text),
+			_ => None,
+		}
+	}
+
+	pub fn as_usize(&self) -> Option<usize> {
+		match *self {
+			Self::Number(n) if n >= 0.0 && n.fract() == 0.0 => Some(n as usize),
+			_ => None,
+		}
+	}
+
+	pub fn as_array(&self) -> Option<&[Value]> {
+		match self {
+			Self::Array(items) => Some(items),
+			_ => None,
+		}
+	}
+
+	// a position written as [x, y]
+	pub fn as_pos(&self) -> Option<(usize, usize)> {
+		match self.as_array()? {
+			[x, y] => Some((x.as_usize()?, y.as_usize()?)),
+			_ => None,
+		}
+	}
+}
+
+// a string for inside the quotes of a json string
+pub fn escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+// the whole text has to be one value, a file cut off in the middle is an error
+pub fn parse(text: &str) -> Result<Value, String> {
+	let mut chars = text.chars().peekable();
+	let value = parse_value(&mut chars)?;
+	skip_space(&mut chars);
+	match chars.next() {
+		None => Ok(value),
+		Some(c) => Err(format!("unexpected '{}' after the value", c)),
+	}
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Value, String> {
+	skip_space(chars);
+	match chars.peek().copied() {
+		Some('{') => {
+			chars.next();
+			let mut fields = Vec::new();
+			skip_space(chars);
+			if chars.next_if_eq(&'}').is_some() {
+				return Ok(Value::Object(fields));
+			}
+			loop {
+				skip_space(chars);
+				expect(chars, '"')?;
+				let name = parse_string(chars)?;
+				skip_space(chars);
+				expect(chars, ':')?;
+				fields.push((name, parse_value(chars)?));
+				skip_space(chars);
+				match chars.next() {
+					Some(',') => (),
+					Some('}') => return Ok(Value::Object(fields)),
+					_ => return Err("expected ',' or '}' in an object".into()),
+				}
+			}
+		},
+		Some('[') => {
+			chars.next();
+			let mut items = Vec::new();
+			skip_space(chars);
+			if chars.next_if_eq(&']').is_some() {
+				return Ok(Value::Array(items));
+			}
+			loop {
+				items.push(parse_value(chars)?);
+				skip_space(chars);
+				match chars.next() {
+					Some(',') => (),
+					Some(']') => return Ok(Value::Array(items)),
+					_ => return Err("expected ',' or ']' in an array".into()),
+				}
+			}
+		},
+		Some('"') => {
+			chars.next();
+			parse_string(chars).map(Value::String)
+		},
+		Some('t') => parse_word(chars, "true", Value::Bool(true)),
+		Some('f') => parse_word(chars, "false", Value::Bool(false)),
+		Some('n') => parse_word(chars, "null", Value::Null),
+		Some(c) if c == '-' || c.is_ascii_digit() => {
+			let mut number = String::new();
+			while let Some(c) = chars.next_if(|&c| c.is_ascii_digit() || "+-.eE".contains(c)) {
+				number.push(c);
+			}
+			number.parse().map(Value::Number).map_err(|_| format!("invalid number '{}'", number))
+		},
+		Some(c) => Err(format!("unexpected '{}'", c)),
+		None => Err("unexpected end of the text".into()),
+	}
+}
+
+// the rest of a string after the opening quote
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+	let mut text = String::new();
+	loop {
+		match chars.next() {
+			Some('"') => return Ok(text),
+			Some('\\') => match chars.next() {
+				Some('n') => text.push('\n'),
+				Some('r') => text.push('\r'),
+				Some('t') => text.push('\t'),
+				Some('b') => text.push('\u{8}'),
+				Some('f') => text.push('\u{c}'),
+				Some('u') => {
+					let hex: String = chars.take(4).collect();
+					let code = u32::from_str_radix(&hex, 16).map_err(|_| format!("invalid escape '\\u{}'", hex))?;
+					text.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+				},
+				Some(c) => text.push(c),
+				None => return Err("unexpected end of the text".into()),
+			},
+			Some(c) => text.push(c),
+			None => return Err("unexpected end of the text".into()),
+		}
+	}
+}
+
+fn parse_word(chars: &mut Peekable<Chars>, word: &str, value: Value) -> Result<Value, String> {
+	for expected in word.chars() {
+		expect(chars, expected)?;
+	}
+	Ok(value)
+}
+
+fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), String> {
+	match chars.next() {
+		Some(c) if c == expected => Ok(()),
+		Some(c) => Err(format!("expected '{}', found '{}'", expected, c)),
+		None => Err("unexpected end of the text".into()),
+	}
+}
+
+fn skip_space(chars: &mut Peekable<Chars>) {
+	while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
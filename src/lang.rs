@@ -0,0 +1,630 @@
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+// every player-facing string, `{}` marks are filled in order by `trf`
+#[derive(Clone, Copy)]
+pub enum Msg {
+	KeyHints,
+	MineCounter,
+	BlindCounter,
+	BlindNoFinish,
+	BlindNoGuess,
+	SafeLeft,
+	NewGame,
+	NoFrontier,
+	NoUnresolved,
+	FinishNeedsFlags,
+	OpenFlagged,
+	FlagOpened,
+	UnknownKey,
+	NoCascade,
+	Idle,
+	GradientStatus,
+	PendingCount,
+	MacroRecording,
+	MacroStatus,
+	MacroSaved,
+	MacroNotSaved,
+	MacroTooLong,
+	MacroEmpty,
+	YouWin,
+	GameOver,
+	ConfirmSurrender,
+	Surrendered,
+	NoSurrenderInRace,
+	QuitAfter,
+	WinStats,
+	LoseStats,
+	NewBestWas,
+	NewBest,
+	BestTime,
+	HitMine,
+	NewHighScore,
+	HighScore,
+	ScoreStatus,
+	ScoreBreakdown,
+	OpponentProgress,
+	OpponentCleared,
+	OpponentDead,
+	OpponentGone,
+	OpponentWon,
+	OpponentDied,
+	OpponentLeft,
+	RaceWon,
+	RaceLost,
+	PressAnyKey,
+	PressRetry,
+	HeatmapHint,
+	Attempt,
+	Clicks,
+	ClicksLeft,
+	OutOfClicks,
+	LiarMode,
+	CoopOpened,
+	CoopStatus,
+	AntiMode,
+	NoAntiMines,
+	WinByFlags,
+	WinByFlagsOnly,
+	MineAdded,
+	ForcedMoves,
+	FlagsAgree,
+	TooManyFlags,
+	GuardWarning,
+	ProvenMine,
+	MissedSafe,
+	ForcedGuess,
+	RiskyOpens,
+	Efficiency,
+	BestRate,
+	NewBestRate,
+	BestEfficiency,
+	NewBestEfficiency,
+	LiveRates,
+	LongestPauses,
+	PauseShown,
+	PauseEntry,
+	NoGuessNeeded,
+	BestGuess,
+	RoughGuess,
+	SeedRank,
+	SeedUnranked,
+	LeaderboardTitle,
+	NoTimes,
+	LeaderboardHint,
+	NoHistory,
+	HistoryTitle,
+	AllPresets,
+	ColumnDate,
+	ColumnPreset,
+	ColumnResult,
+	ColumnTime,
+	HasReplay,
+	ResultWin,
+	ResultLoss,
+	ResultQuit,
+	ResultSurrender,
+	HistoryPage,
+	HistoryKeys,
+	NoSeed,
+	NoOptions,
+	PlayOrWatch,
+	PlayAgain,
+	WaitingForGame,
+	SpectateHint,
+	GhostStatus,
+	GhostAhead,
+	GhostBehind,
+	RecoverySaved,
+	RecoveryFound,
+	RecoveryInvalid,
+	PressContinue,
+	PressExit,
+	CampaignStatus,
+	CampaignResumed,
+	CampaignCleared,
+	CampaignLifeLost,
+	CampaignComplete,
+	CampaignOver,
+	CampaignSaved,
+	CampaignInvalid,
+	TimeLeft,
+	MarathonStatus,
+	MarathonPenalty,
+	MarathonOver,
+	MarathonHits,
+	MarathonNewBest,
+	MarathonNewBestWas,
+	MarathonBest,
+	DrillPrompt,
+	DrillTally,
+	DrillRight,
+	DrillWrong,
+	DrillMine,
+	DrillGuess,
+	DrillSafe,
+	DrillResult,
+	SubmitDone,
+	SubmitFailed,
+	SubmitPending,
+	FetchFailed,
+	DailyTopTitle,
+	DailyTopEmpty,
+	LineHintsNoSolver,
+	DescribeEmpty,
+	DescribeNumber,
+	DescribeHidden,
+	DescribeFlagged,
+	DescribeAlone,
+	DescribeTouching,
+	ReadRow,
+	ReadColumn,
+	SideUp,
+	SideDown,
+	SideLeft,
+	SideRight,
+	SideUpLeft,
+	SideUpRight,
+	SideDownLeft,
+	SideDownRight,
+	EditorHelp,
+	EditorCount,
+	EditorSaved,
+	EditorUnsaved,
+	EditorOpenedMine,
+	EditorNothingLeft,
+	TournamentStatus,
+	TournamentResumed,
+	TournamentCleared,
+	TournamentFailed,
+	TournamentStanding,
+	TournamentOver,
+	TournamentReport,
+	TournamentSaved,
+	TournamentInvalid,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Lang {
+	English,
+	Swedish,
+}
+
+const LANGS: [(&str, Lang); 2] = [("en", Lang::English), ("sv", Lang::Swedish)];
+
+static CURRENT: OnceLock<Lang> = OnceLock::new();
+
+// picks the language from `code`, or the locale environment variables when None
+pub fn init(code: Option<&str>) -> Result<(), String> {
+	let lang = match code {
+		Some(code) => match from_code(code) {
+			Some(lang) => lang,
+			None => {
+				let names: Vec<_> = LANGS.iter().map(|(name, _)| *name).collect();
+				return Err(format!("unknown language '{}', available languages: {}", code, names.join(", ")));
+			}
+		},
+		None => ["LC_ALL", "LC_MESSAGES", "LANG"]
+			.iter()
+			.filter_map(|var| env::var(var).ok())
+			.find(|value| !value.is_empty())
+			.and_then(|value| from_code(&value))
+			.unwrap_or(Lang::English),
+	};
+	let _ = CURRENT.set(lang);
+	Ok(())
+}
+
+// accepts plain codes like "sv" as well as locales like "sv_SE.UTF-8"
+fn from_code(code: &str) -> Option<Lang> {
+	let code = code.split(['_', '.', '-']).next()?;
+	LANGS.iter().find(|(name, _)| *name == code).map(|&(_, lang)| lang)
+}
+
+pub fn tr(msg: Msg) -> &'static str {
+	let lang = *CURRENT.get().unwrap_or(&Lang::English);
+	let translated = match lang {
+		Lang::English => None,
+		Lang::Swedish => swedish(msg),
+	};
+	translated.unwrap_or_else(|| english(msg))
+}
+
+pub fn trf(msg: Msg, args: &[&dyn Display]) -> String {
+	let mut parts = tr(msg).split("{}");
+	let mut out = parts.next().unwrap_or_default().to_string();
+	for (i, part) in parts.enumerate() {
+		if let Some(arg) = args.get(i) {
+			out.push_str(&arg.to_string());
+		}
+		out.push_str(part);
+	}
+	out
+}
+
+// 1st, 2nd, 3rd in the current language
+pub fn ordinal(n: usize) -> String {
+	let lang = *CURRENT.get().unwrap_or(&Lang::English);
+	let suffix = match lang {
+		Lang::English => match (n % 10, n % 100) {
+			(_, 11..=13) => "th",
+			(1, _) => "st",
+			(2, _) => "nd",
+			(3, _) => "rd",
+			_ => "th",
+		},
+		Lang::Swedish => match (n % 10, n % 100) {
+			(_, 11 | 12) => ":e",
+			(1 | 2, _) => ":a",
+			_ => ":e",
+		},
+	};
+	format!("{}{}", n, suffix)
+}
+
+fn english(msg: Msg) -> &'static str {
+	match msg {
+		Msg::KeyHints => "←↑↓→ move · space open · f flag · n next frontier · z zoom · m map · r restart · q quit",
+		Msg::MineCounter => "Mines: {}/{}",
+		Msg::BlindCounter => "Mines: ?  Flags: {}",
+		Msg::BlindNoFinish => "finishing needs the number of mines, which --blind-count hides",
+		Msg::BlindNoGuess => "the chances need the number of mines, which --blind-count hides",
+		Msg::SafeLeft => "Safe left: {}",
+		Msg::NewGame => "new game",
+		Msg::NoFrontier => "no frontier cells",
+		Msg::NoUnresolved => "no unresolved numbers",
+		Msg::FinishNeedsFlags => "finishing needs a flag on all {} mines, there are {}",
+		Msg::OpenFlagged => "the tile is flagged, unflag it first",
+		Msg::FlagOpened => "can't flag an opened tile",
+		Msg::UnknownKey => "nothing on that key",
+		Msg::NoCascade => "no flood fill",
+		Msg::Idle => "idle, the timer is paused. Press any key",
+		Msg::GradientStatus => "mines {}→{}%",
+		Msg::PendingCount => "count {}",
+		Msg::MacroRecording => "recording macro {}, Q to stop",
+		Msg::MacroStatus => "rec @{}",
+		Msg::MacroSaved => "macro {} saved with {} moves",
+		Msg::MacroNotSaved => "macro {} recorded but could not be saved",
+		Msg::MacroTooLong => "macro {} stopped at {} moves",
+		Msg::MacroEmpty => "macro {} is empty",
+		Msg::YouWin => "YOU WIN!",
+		Msg::GameOver => "GAME OVER!",
+		Msg::ConfirmSurrender => "press ! again to give up and see the mines",
+		Msg::Surrendered => "Surrendered",
+		Msg::NoSurrenderInRace => "there is no giving up in a race",
+		Msg::QuitAfter => "Quit after {}s",
+		Msg::WinStats => "Time: {}s   3BV: {}   3BV/s: {}   Moves: {}",
+		Msg::LoseStats => "Time: {}s   Moves: {}",
+		Msg::NewBestWas => "New best time! (previous best {}s)",
+		Msg::NewBest => "New best time!",
+		Msg::BestTime => "Best time: {}s (+{}s)",
+		Msg::HitMine => "Hit a mine at {}",
+		Msg::NewHighScore => "New high score!",
+		Msg::HighScore => "High score: {}",
+		Msg::ScoreStatus => "Score: {} (streak x{})",
+		Msg::ScoreBreakdown => "Score: {} (tiles {} + fill {} + streak {} + time {})",
+		Msg::OpponentProgress => "Opponent: {}%",
+		Msg::OpponentCleared => "Opponent: cleared",
+		Msg::OpponentDead => "Opponent: dead",
+		Msg::OpponentGone => "Opponent: gone",
+		Msg::OpponentWon => "your opponent cleared the board first",
+		Msg::OpponentDied => "your opponent hit a mine, you win the race",
+		Msg::OpponentLeft => "your opponent disconnected, playing alone",
+		Msg::RaceWon => "You win the race!",
+		Msg::RaceLost => "Your opponent wins the race",
+		Msg::PressAnyKey => "press O for the order tiles were opened in, 1-5 for the longest pauses, any other key to exit",
+		Msg::PressRetry => "press R to play the same board again, O for the order tiles were opened in, 1-5 for the longest pauses, any other key to exit",
+		Msg::HeatmapHint => "O: first opened in blue, last in red",
+		Msg::Attempt => "attempt {}",
+		Msg::Clicks => "Clicks: {}/{}",
+		Msg::ClicksLeft => "Cleared in {} of {} clicks ({} to spare)",
+		Msg::OutOfClicks => "Out of clicks, the budget was {} (3BV + {})",
+		Msg::LiarMode => "LIAR: numbers are off by one",
+		Msg::CoopOpened => "Player one opened {} tiles, player two {}",
+		Msg::CoopStatus => "P1 {} P2 {}",
+		Msg::AntiMode => "ANTI: F flags anti-mines",
+		Msg::NoAntiMines => "no anti-mines in this game",
+		Msg::WinByFlags => "Win: clear or flag all",
+		Msg::WinByFlagsOnly => "Win: flag all mines",
+		Msg::MineAdded => "a new mine appeared somewhere",
+		Msg::ForcedMoves => "Forced moves: {}",
+		Msg::FlagsAgree => "no number has too many flags",
+		Msg::TooManyFlags => "numbers with too many flags: {}",
+		Msg::GuardWarning => "the numbers say that is a mine, press space again to open it anyway",
+		Msg::ProvenMine => "Mistake: the numbers proved that was a mine",
+		Msg::MissedSafe => "Mistake: {} was certainly safe",
+		Msg::ForcedGuess => "Unlucky: nothing was certainly safe, it had to be a guess",
+		Msg::RiskyOpens => "Earlier guesses while something was certainly safe: {}",
+		Msg::Efficiency => "Efficiency: {}%",
+		Msg::BestRate => "Best 3BV/s: {}",
+		Msg::NewBestRate => "New best 3BV/s!",
+		Msg::BestEfficiency => "Best efficiency: {}%",
+		Msg::NewBestEfficiency => "New best efficiency!",
+		Msg::LiveRates => "3BV/s: {}  Eff: {}%  APM: {}",
+		Msg::LongestPauses => "Longest pauses: {}",
+		Msg::PauseShown => "pause {}: {}s before the move here",
+		Msg::PauseEntry => "{}) {}s at {}",
+		Msg::NoGuessNeeded => "no need to guess, {} is certainly safe",
+		Msg::BestGuess => "best guess {}: {}% mine",
+		Msg::RoughGuess => "best guess {}: about {}% mine (estimated)",
+		Msg::SeedRank => "Your rank on {}: {} ({}s, best {}s)",
+		Msg::SeedUnranked => "Not in the top {} on {} (best {}s)",
+		Msg::LeaderboardTitle => "Best times on {}",
+		Msg::NoTimes => "No times on {} yet",
+		Msg::LeaderboardHint => "codes look like 16x16x32:1234 and are shown after winning a game started with --seed",
+		Msg::NoHistory => "There are no games in the history yet",
+		Msg::HistoryTitle => "History: {} games, {}",
+		Msg::AllPresets => "all presets",
+		Msg::ColumnDate => "date",
+		Msg::ColumnPreset => "preset",
+		Msg::ColumnResult => "result",
+		Msg::ColumnTime => "time",
+		Msg::HasReplay => "replay",
+		Msg::ResultWin => "win",
+		Msg::ResultLoss => "loss",
+		Msg::ResultQuit => "quit",
+		Msg::ResultSurrender => "gave up",
+		Msg::HistoryPage => "page {}/{}",
+		Msg::HistoryKeys => "↑↓ select · ←→ page · f filter by preset · enter play again · q quit",
+		Msg::NoSeed => "that board had no seed and can't be played again",
+		Msg::NoOptions => "that game is from before the history kept its options",
+		Msg::PlayOrWatch => "p play this board · w watch the replay · any other key to go back",
+		Msg::PlayAgain => "p play this board · any other key to go back",
+		Msg::WaitingForGame => "waiting for game…",
+		Msg::SpectateHint => "spectating · q quit",
+		Msg::GhostStatus => "ghost {}%",
+		Msg::GhostAhead => "ghost {}% · {}s ahead",
+		Msg::GhostBehind => "ghost {}% · {}s behind",
+		Msg::RecoverySaved => "the game was saved to {} and can be resumed on the next start",
+		Msg::RecoveryFound => "A game was saved to {} when minesweeper crashed. Resume it? (y/n)",
+		Msg::RecoveryInvalid => "is not a saved game",
+		Msg::PressContinue => "press any key to go on, q to stop here",
+		Msg::PressExit => "press any key to exit",
+		Msg::CampaignStatus => "level {}/{} · lives {}",
+		Msg::CampaignResumed => "campaign resumed at level {} with {} lives",
+		Msg::CampaignCleared => "Level {} cleared in {}s, next up is {}",
+		Msg::CampaignLifeLost => "A life lost, {} left. Level {} again on a new board",
+		Msg::CampaignComplete => "Campaign complete! All {} levels cleared in {}s",
+		Msg::CampaignOver => "Campaign over: {} of {} levels cleared in {}s",
+		Msg::CampaignSaved => "Campaign saved at level {} with {} lives after {}s, --campaign goes on from there",
+		Msg::CampaignInvalid => "is not a saved campaign",
+		Msg::TimeLeft => "{} left",
+		Msg::MarathonStatus => "boards {}",
+		Msg::MarathonPenalty => "a new board, and {}s less on the clock",
+		Msg::MarathonOver => "Marathon over: {} boards cleared and {}% of the last one, a score of {}",
+		Msg::MarathonHits => "boards lost: {}, {}s of penalties",
+		Msg::MarathonNewBest => "New personal best!",
+		Msg::MarathonNewBestWas => "New personal best! (was {})",
+		Msg::MarathonBest => "Personal best: {}",
+		Msg::DrillPrompt => "{}: flag the mines and open the safe tiles that the numbers prove",
+		Msg::DrillTally => "right {}/{}",
+		Msg::DrillRight => "right! any key for the next one, q to stop",
+		Msg::DrillWrong => "not quite, {}. the answer is shown, any key for the next one, q to stop",
+		Msg::DrillMine => "that was a mine",
+		Msg::DrillGuess => "the numbers don't decide that tile",
+		Msg::DrillSafe => "that tile is safe",
+		Msg::DrillResult => "{}: {} of {} right ({}%)",
+		Msg::SubmitDone => "Time sent to {}",
+		Msg::SubmitFailed => "Could not send the time: {}",
+		Msg::SubmitPending => "The leaderboard hasn't answered yet, the time may not have arrived",
+		Msg::FetchFailed => "could not fetch the best times: {}",
+		Msg::DailyTopTitle => "Best times on the daily board of {} ({})",
+		Msg::DailyTopEmpty => "no times yet",
+		Msg::LineHintsNoSolver => "the solver doesn't know about the row and column hints, it's off with --line-hints",
+		Msg::DescribeEmpty => "empty",
+		Msg::DescribeNumber => "{}: {} hidden, {} flagged neighbors",
+		Msg::DescribeHidden => "hidden",
+		Msg::DescribeFlagged => "flagged",
+		Msg::DescribeAlone => "{}: no numbers next to it",
+		Msg::DescribeTouching => "{}: touching numbers {}",
+		Msg::ReadRow => "row {}: {}, cursor at {}",
+		Msg::ReadColumn => "column {}: {}, cursor at {}",
+		Msg::SideUp => "up",
+		Msg::SideDown => "down",
+		Msg::SideLeft => "left",
+		Msg::SideRight => "right",
+		Msg::SideUpLeft => "up-left",
+		Msg::SideUpRight => "up-right",
+		Msg::SideDownLeft => "down-left",
+		Msg::SideDownRight => "down-right",
+		Msg::EditorHelp => "space mine | o opened | s save | q quit",
+		Msg::EditorCount => "{} mines, {} opened",
+		Msg::EditorSaved => "saved to {}",
+		Msg::EditorUnsaved => "the changes aren't saved, press q again to quit anyway",
+		Msg::EditorOpenedMine => "the mine at {} is opened, a board can't start with a mine opened",
+		Msg::EditorNothingLeft => "every safe tile is opened, there would be nothing left to play",
+		Msg::TournamentStatus => "board {}/{} · score {}s",
+		Msg::TournamentResumed => "tournament resumed at board {} of {}",
+		Msg::TournamentCleared => "Board {} cleared in {}s",
+		Msg::TournamentFailed => "Board {} not cleared, {}s added to the score",
+		Msg::TournamentStanding => "Score after {} boards: {}s, {} cleared",
+		Msg::TournamentOver => "Tournament over: {} of {} boards cleared, score {}s",
+		Msg::TournamentReport => "the report was written to {}",
+		Msg::TournamentSaved => "Tournament saved at board {} of {}, --tournament {} goes on from there",
+		Msg::TournamentInvalid => "is not a saved tournament",
+	}
+}
+
+fn swedish(msg: Msg) -> Option<&'static str> {
+	Some(match msg {
+		Msg::KeyHints => "←↑↓→ flytta · mellanslag öppna · f flagga · n nästa gränsruta · z zooma · m karta · r börja om · q avsluta",
+		Msg::MineCounter => "Minor: {}/{}",
+		Msg::BlindCounter => "Minor: ?  Flaggor: {}",
+		Msg::BlindNoFinish => "att avsluta kräver antalet minor, som --blind-count döljer",
+		Msg::BlindNoGuess => "chanserna kräver antalet minor, som --blind-count döljer",
+		Msg::SafeLeft => "Säkra kvar: {}",
+		Msg::NewGame => "nytt spel",
+		Msg::NoFrontier => "inga gränsrutor",
+		Msg::NoUnresolved => "inga olösta siffror",
+		Msg::FinishNeedsFlags => "att avsluta kräver flaggor på alla {} minor, det finns {}",
+		Msg::OpenFlagged => "rutan är flaggad, ta bort flaggan först",
+		Msg::FlagOpened => "kan inte flagga en öppnad ruta",
+		Msg::UnknownKey => "inget på den tangenten",
+		Msg::NoCascade => "ingen flodfyllning",
+		Msg::Idle => "inaktiv, klockan är pausad. Tryck på valfri tangent",
+		Msg::GradientStatus => "minor {}→{}%",
+		Msg::PendingCount => "antal {}",
+		Msg::MacroRecording => "spelar in makro {}, Q för att sluta",
+		Msg::MacroStatus => "spelar in @{}",
+		Msg::MacroSaved => "makro {} sparat med {} drag",
+		Msg::MacroNotSaved => "makro {} inspelat men kunde inte sparas",
+		Msg::MacroTooLong => "makro {} stoppades vid {} drag",
+		Msg::MacroEmpty => "makro {} är tomt",
+		Msg::YouWin => "DU VANN!",
+		Msg::GameOver => "DU FÖRLORADE!",
+		Msg::ConfirmSurrender => "tryck ! igen för att ge upp och se minorna",
+		Msg::Surrendered => "Gav upp",
+		Msg::NoSurrenderInRace => "man kan inte ge upp i ett lopp",
+		Msg::QuitAfter => "Avslutade efter {}s",
+		Msg::WinStats => "Tid: {}s   3BV: {}   3BV/s: {}   Drag: {}",
+		Msg::LoseStats => "Tid: {}s   Drag: {}",
+		Msg::NewBestWas => "Ny bästa tid! (förra bästa {}s)",
+		Msg::NewBest => "Ny bästa tid!",
+		Msg::BestTime => "Bästa tid: {}s (+{}s)",
+		Msg::HitMine => "Gick på en mina vid {}",
+		Msg::NewHighScore => "Nytt poängrekord!",
+		Msg::HighScore => "Poängrekord: {}",
+		Msg::ScoreStatus => "Poäng: {} (svit x{})",
+		Msg::ScoreBreakdown => "Poäng: {} (rutor {} + fyllning {} + svit {} + tid {})",
+		Msg::OpponentProgress => "Motståndare: {}%",
+		Msg::OpponentCleared => "Motståndare: klar",
+		Msg::OpponentDead => "Motståndare: död",
+		Msg::OpponentGone => "Motståndare: borta",
+		Msg::OpponentWon => "din motståndare röjde brädet först",
+		Msg::OpponentDied => "din motståndare gick på en mina, du vinner racet",
+		Msg::OpponentLeft => "din motståndare kopplade från, du spelar ensam",
+		Msg::RaceWon => "Du vinner racet!",
+		Msg::RaceLost => "Din motståndare vinner racet",
+		Msg::PressAnyKey => "tryck O för ordningen rutorna öppnades i, 1-5 för de längsta pauserna, valfri annan tangent för att avsluta",
+		Msg::PressRetry => "tryck R för att spela samma bräde igen, O för ordningen rutorna öppnades i, 1-5 för de längsta pauserna, valfri annan tangent för att avsluta",
+		Msg::HeatmapHint => "O: först öppnade i blått, sist i rött",
+		Msg::Attempt => "försök {}",
+		Msg::Clicks => "Klick: {}/{}",
+		Msg::ClicksLeft => "Röjt på {} av {} klick ({} över)",
+		Msg::OutOfClicks => "Slut på klick, budgeten var {} (3BV + {})",
+		Msg::LiarMode => "LÖGNARE: siffrorna är fel med ett",
+		Msg::CoopOpened => "Spelare ett öppnade {} rutor, spelare två {}",
+		Msg::CoopStatus => "S1 {} S2 {}",
+		Msg::AntiMode => "ANTI: F flaggar antiminor",
+		Msg::NoAntiMines => "inga antiminor i det här spelet",
+		Msg::WinByFlags => "Vinst: röj eller flagga allt",
+		Msg::WinByFlagsOnly => "Vinst: flagga alla minor",
+		Msg::MineAdded => "en ny mina dök upp någonstans",
+		Msg::ForcedMoves => "Säkra drag: {}",
+		Msg::FlagsAgree => "inga siffror har för många flaggor",
+		Msg::TooManyFlags => "siffror med för många flaggor: {}",
+		Msg::GuardWarning => "siffrorna säger att det är en mina, tryck mellanslag igen för att öppna ändå",
+		Msg::ProvenMine => "Misstag: siffrorna visade att det var en mina",
+		Msg::MissedSafe => "Misstag: {} var garanterat säker",
+		Msg::ForcedGuess => "Otur: inget var garanterat säkert, det måste gissas",
+		Msg::RiskyOpens => "Tidigare gissningar när något var garanterat säkert: {}",
+		Msg::Efficiency => "Effektivitet: {}%",
+		Msg::BestRate => "Bästa 3BV/s: {}",
+		Msg::NewBestRate => "Ny bästa 3BV/s!",
+		Msg::BestEfficiency => "Bästa effektivitet: {}%",
+		Msg::NewBestEfficiency => "Ny bästa effektivitet!",
+		Msg::LiveRates => "3BV/s: {}  Eff: {}%  APM: {}",
+		Msg::LongestPauses => "Längsta pauser: {}",
+		Msg::PauseShown => "paus {}: {}s före draget här",
+		Msg::PauseEntry => "{}) {}s vid {}",
+		Msg::NoGuessNeeded => "ingen gissning behövs, {} är garanterat säker",
+		Msg::BestGuess => "bästa gissning {}: {}% mina",
+		Msg::RoughGuess => "bästa gissning {}: ungefär {}% mina (uppskattat)",
+		Msg::SeedRank => "Din placering på {}: {} ({}s, bästa {}s)",
+		Msg::SeedUnranked => "Inte bland de {} bästa på {} (bästa {}s)",
+		Msg::LeaderboardTitle => "Bästa tider på {}",
+		Msg::NoTimes => "Inga tider på {} än",
+		Msg::LeaderboardHint => "koder ser ut som 16x16x32:1234 och visas efter en vinst i ett spel startat med --seed",
+		Msg::NoHistory => "Det finns inga spel i historiken än",
+		Msg::HistoryTitle => "Historik: {} spel, {}",
+		Msg::AllPresets => "alla varianter",
+		Msg::ColumnDate => "datum",
+		Msg::ColumnPreset => "variant",
+		Msg::ColumnResult => "resultat",
+		Msg::ColumnTime => "tid",
+		Msg::HasReplay => "repris",
+		Msg::ResultWin => "vinst",
+		Msg::ResultLoss => "förlust",
+		Msg::ResultQuit => "avslutat",
+		Msg::ResultSurrender => "gav upp",
+		Msg::HistoryPage => "sida {}/{}",
+		Msg::HistoryKeys => "↑↓ välj · ←→ bläddra · f filtrera på variant · enter spela igen · q avsluta",
+		Msg::NoSeed => "den brädan hade inget frö och kan inte spelas igen",
+		Msg::NoOptions => "det spelet är från innan historiken sparade inställningarna",
+		Msg::PlayOrWatch => "p spela brädan · w se reprisen · annan tangent för att gå tillbaka",
+		Msg::PlayAgain => "p spela brädan · annan tangent för att gå tillbaka",
+		Msg::WaitingForGame => "väntar på ett spel…",
+		Msg::SpectateHint => "tittar på · q avsluta",
+		Msg::GhostStatus => "spöke {}%",
+		Msg::GhostAhead => "spöke {}% · {}s före",
+		Msg::GhostBehind => "spöke {}% · {}s efter",
+		Msg::RecoverySaved => "spelet sparades i {} och kan fortsättas nästa gång",
+		Msg::RecoveryFound => "Ett spel sparades i {} när minesweeper kraschade. Fortsätta det? (j/n)",
+		Msg::RecoveryInvalid => "är inte ett sparat spel",
+		Msg::PressContinue => "tryck valfri tangent för att fortsätta, q för att sluta här",
+		Msg::PressExit => "tryck valfri tangent för att avsluta",
+		Msg::CampaignStatus => "nivå {}/{} · liv {}",
+		Msg::CampaignResumed => "kampanjen fortsätter på nivå {} med {} liv",
+		Msg::CampaignCleared => "Nivå {} klarad på {}s, nästa är {}",
+		Msg::CampaignLifeLost => "Ett liv förlorat, {} kvar. Nivå {} igen på ett nytt bräde",
+		Msg::CampaignComplete => "Kampanjen klarad! Alla {} nivåer på {}s",
+		Msg::CampaignOver => "Kampanjen är slut: {} av {} nivåer klarade på {}s",
+		Msg::CampaignSaved => "Kampanjen sparad på nivå {} med {} liv efter {}s, --campaign fortsätter därifrån",
+		Msg::CampaignInvalid => "är inte en sparad kampanj",
+		Msg::TimeLeft => "{} kvar",
+		Msg::MarathonStatus => "bräden {}",
+		Msg::MarathonPenalty => "ett nytt bräde, och {}s mindre på klockan",
+		Msg::MarathonOver => "Maraton slut: {} bräden klarade och {}% av det sista, {} poäng",
+		Msg::MarathonHits => "förlorade bräden: {}, {}s i straff",
+		Msg::MarathonNewBest => "Nytt personligt rekord!",
+		Msg::MarathonNewBestWas => "Nytt personligt rekord! (var {})",
+		Msg::MarathonBest => "Personligt rekord: {}",
+		Msg::DrillPrompt => "{}: flagga minorna och öppna de säkra rutorna som siffrorna bevisar",
+		Msg::DrillTally => "rätt {}/{}",
+		Msg::DrillRight => "rätt! valfri tangent för nästa, q för att sluta",
+		Msg::DrillWrong => "inte riktigt, {}. svaret visas, valfri tangent för nästa, q för att sluta",
+		Msg::DrillMine => "det var en mina",
+		Msg::DrillGuess => "siffrorna avgör inte den rutan",
+		Msg::DrillSafe => "den rutan är säker",
+		Msg::DrillResult => "{}: {} av {} rätt ({}%)",
+		Msg::SubmitDone => "Tiden skickad till {}",
+		Msg::SubmitFailed => "Kunde inte skicka tiden: {}",
+		Msg::SubmitPending => "Topplistan har inte svarat än, tiden kanske inte kom fram",
+		Msg::FetchFailed => "kunde inte hämta de bästa tiderna: {}",
+		Msg::DailyTopTitle => "Bästa tider på dagens bräde {} ({})",
+		Msg::DailyTopEmpty => "inga tider än",
+		Msg::LineHintsNoSolver => "lösaren känner inte till rad- och kolumntipsen, den är av med --line-hints",
+		Msg::DescribeEmpty => "tom",
+		Msg::DescribeNumber => "{}: {} dolda, {} flaggade grannar",
+		Msg::DescribeHidden => "dold",
+		Msg::DescribeFlagged => "flaggad",
+		Msg::DescribeAlone => "{}: inga siffror bredvid",
+		Msg::DescribeTouching => "{}: rör siffrorna {}",
+		Msg::ReadRow => "rad {}: {}, markören på {}",
+		Msg::ReadColumn => "kolumn {}: {}, markören på {}",
+		Msg::SideUp => "upp",
+		Msg::SideDown => "ner",
+		Msg::SideLeft => "vänster",
+		Msg::SideRight => "höger",
+		Msg::SideUpLeft => "upp till vänster",
+		Msg::SideUpRight => "upp till höger",
+		Msg::SideDownLeft => "ner till vänster",
+		Msg::SideDownRight => "ner till höger",
+		Msg::EditorHelp => "mellanslag mina | o öppnad | s spara | q avsluta",
+		Msg::EditorCount => "{} minor, {} öppnade",
+		Msg::EditorSaved => "sparat i {}",
+		Msg::EditorUnsaved => "ändringarna är inte sparade, tryck q igen för att avsluta ändå",
+		Msg::EditorOpenedMine => "minan på {} är öppnad, ett bräde kan inte börja med en öppen mina",
+		Msg::EditorNothingLeft => "alla säkra rutor är öppnade, det skulle inte finnas något kvar att spela",
+		Msg::TournamentStatus => "bräde {}/{} · poäng {}s",
+		Msg::TournamentResumed => "turneringen fortsätter på bräde {} av {}",
+		Msg::TournamentCleared => "Bräde {} klarat på {}s",
+		Msg::TournamentFailed => "Bräde {} inte klarat, {}s läggs till poängen",
+		Msg::TournamentStanding => "Poäng efter {} bräden: {}s, {} klarade",
+		Msg::TournamentOver => "Turneringen slut: {} av {} bräden klarade, poäng {}s",
+		Msg::TournamentReport => "rapporten skrevs till {}",
+		Msg::TournamentSaved => "Turneringen sparad på bräde {} av {}, --tournament {} fortsätter därifrån",
+		Msg::TournamentInvalid => "är inte en sparad turnering",
+	})
+}
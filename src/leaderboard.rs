@@ -0,0 +1,149 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use crate::date;
+use crate::lang::{ordinal, tr, trf, Msg};
+use crate::records::data_dir;
+
+// times kept for every board
+const KEEP: usize = 10;
+
+// the best times on boards picked by seed, one line per time: <code> <seconds> <date>.
+// the code is the difficulty and the seed, like 16x16x32:1234
+pub struct Leaderboard {
+	lines: Vec<Entry>,
+}
+
+struct Entry {
+	code: String,
+	secs: f64,
+	date: String,
+}
+
+pub fn code(difficulty: &str, seed: u64) -> String {
+	format!("{}:{}", difficulty, seed)
+}
+
+impl Leaderboard {
+	pub fn load() -> Self {
+		let text = path().and_then(|path| fs::read_to_string(path).ok()).unwrap_or_default();
+		let lines = text
+			.lines()
+			.filter_map(|line| {
+				let mut fields = line.split_whitespace();
+				Some(Entry {
+					code: fields.next()?.into(),
+					secs: fields.next()?.parse().ok()?,
+					date: fields.next().unwrap_or_default().into(),
+				})
+			})
+			.collect();
+		Self { lines }
+	}
+
+	// adds a time and returns its rank from 1, None if it didn't make the cut
+	pub fn submit(&mut self, code: &str, secs: f64) -> Option<usize> {
+		let rank = self.times(code).filter(|entry| entry.secs <= secs).count();
+		if rank >= KEEP {
+			return None;
+		}
+		self.lines.push(Entry {
+			code: code.into(),
+			secs,
+			date: date::date(date::now()),
+		});
+		// keep every board in time order with the slowest past the limit dropped
+		self.lines.sort_by(|a, b| a.code.cmp(&b.code).then(a.secs.total_cmp(&b.secs)));
+		let mut kept = 0;
+		let mut last_code = String::new();
+		self.lines.retain(|entry| {
+			if entry.code != last_code {
+				last_code = entry.code.clone();
+				kept = 0;
+			}
+			kept += 1;
+			kept <= KEEP
+		});
+		Some(rank + 1)
+	}
+
+	pub fn best(&self, code: &str) -> Option<f64> {
+		self.times(code).map(|entry| entry.secs).min_by(f64::total_cmp)
+	}
+
+	// written to a temporary file first and moved into place, so other games never read half a file
+	pub fn save(&self) {
+		let Some(path) = path() else {
+			return;
+		};
+		if let Some(dir) = path.parent() {
+			let _ = fs::create_dir_all(dir);
+		}
+		let text: String = self
+			.lines
+			.iter()
+			.map(|entry| format!("{} {:.1} {}\n", entry.code, entry.secs, entry.date))
+			.collect();
+		let temp = path.with_extension(format!("{}.tmp", process::id()));
+		if fs::write(&temp, text).is_ok() && fs::rename(&temp, &path).is_err() {
+			let _ = fs::remove_file(&temp);
+		}
+	}
+
+	// the times for a code as lines to print
+	pub fn table(&self, code: &str) -> Vec<String> {
+		let mut times: Vec<_> = self.times(code).collect();
+		if times.is_empty() {
+			return vec![trf(Msg::NoTimes, &[&code])];
+		}
+		times.sort_by(|a, b| a.secs.total_cmp(&b.secs));
+		let mut lines = vec![trf(Msg::LeaderboardTitle, &[&code])];
+		for (i, entry) in times.iter().enumerate() {
+			lines.push(format!("{:>5}  {:>7.1}s  {}", ordinal(i + 1), entry.secs, entry.date));
+		}
+		lines
+	}
+
+	fn times<'a>(&'a self, code: &'a str) -> impl Iterator<Item = &'a Entry> + 'a {
+		self.lines.iter().filter(move |entry| entry.code == code)
+	}
+}
+
+// the end screen line after a win on a seeded board
+pub fn rank_line(rank: Option<usize>, code: &str, secs: f64, best: Option<f64>) -> String {
+	let best = best.map_or(secs, |best| best.min(secs));
+	match rank {
+		Some(rank) => trf(Msg::SeedRank, &[&code, &ordinal(rank), &format!("{:.1}", secs), &format!("{:.1}", best)]),
+		None => trf(Msg::SeedUnranked, &[&KEEP, &code, &format!("{:.1}", best)]),
+	}
+}
+
+pub fn print(code: &str) -> i32 {
+	let board = Leaderboard::load();
+	for line in board.table(code) {
+		println!("{}", line);
+	}
+	if board.best(code).is_none() {
+		eprintln!("{}", tr(Msg::LeaderboardHint));
+		return 1;
+	}
+	0
+}
+
+fn path() -> Option<PathBuf> {
+	Some(data_dir()?.join("leaderboard"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rank_lines_name_the_board() {
+		assert_eq!(rank_line(Some(2), "16x16x40:123", 61.25, Some(55.0)), "Your rank on 16x16x40:123: 2nd (61.2s, best 55.0s)");
+		// a new best counts as the best
+		assert_eq!(rank_line(Some(1), "16x16x40:123", 50.0, Some(55.0)), "Your rank on 16x16x40:123: 1st (50.0s, best 50.0s)");
+		assert_eq!(rank_line(None, "16x16x40:123", 99.0, Some(55.0)), "Not in the top 10 on 16x16x40:123 (best 55.0s)");
+	}
+}
@@ -0,0 +1,126 @@
+// the game, its board files and the solver, these build without the terminal and for wasm32-unknown-unknown
+pub mod action;
+pub mod analyze;
+mod board;
+pub mod boardfile;
+pub mod error;
+mod game;
+pub mod lang;
+pub mod placement;
+pub mod probability;
+pub mod review;
+pub mod solver;
+
+// the terminal game
+#[cfg(feature = "tui")]
+pub mod app;
+#[cfg(feature = "tui")]
+pub mod bench;
+#[cfg(feature = "tui")]
+pub mod browser;
+#[cfg(feature = "tui")]
+pub mod campaign;
+#[cfg(feature = "tui")]
+pub mod cast;
+#[cfg(feature = "tui")]
+mod coop;
+#[cfg(feature = "tui")]
+mod date;
+#[cfg(feature = "tui")]
+pub mod diagnose;
+#[cfg(feature = "tui")]
+pub mod drill;
+#[cfg(feature = "tui")]
+pub mod editor;
+#[cfg(feature = "tui")]
+mod endscreen;
+#[cfg(feature = "tui")]
+pub mod export;
+#[cfg(feature = "tui")]
+pub mod generate;
+#[cfg(feature = "tui")]
+mod ghost;
+#[cfg(feature = "tui")]
+pub mod glyphs;
+#[cfg(feature = "tui")]
+mod history;
+#[cfg(feature = "tui")]
+pub mod import;
+#[cfg(feature = "tui")]
+mod input;
+#[cfg(feature = "tui")]
+mod json;
+#[cfg(feature = "tui")]
+pub mod leaderboard;
+#[cfg(feature = "tui")]
+mod macros;
+#[cfg(feature = "tui")]
+pub mod marathon;
+#[cfg(feature = "tui")]
+mod notices;
+#[cfg(feature = "tui")]
+pub mod options;
+#[cfg(feature = "tui")]
+mod race;
+#[cfg(feature = "tui")]
+mod records;
+#[cfg(feature = "tui")]
+pub mod recovery;
+#[cfg(feature = "tui")]
+mod render;
+#[cfg(feature = "tui")]
+pub mod replay;
+#[cfg(feature = "tui")]
+mod score;
+#[cfg(feature = "tui")]
+mod screen;
+#[cfg(feature = "tui")]
+pub mod spectate;
+#[cfg(feature = "tui")]
+pub mod submit;
+#[cfg(feature = "tui")]
+pub mod svg;
+#[cfg(feature = "tui")]
+pub mod theme;
+#[cfg(feature = "tui")]
+pub mod tournament;
+
+pub use game::{FlagWin, GameEvent, Lattice, MSGame, Tile, TileContents, TileVis, TurnResult};
+// the terminal game as a whole, for running it from another program the way main.rs does
+#[cfg(feature = "tui")]
+pub use app::{App, GameOutcome};
+#[cfg(feature = "tui")]
+pub use endscreen::Summary;
+use game::pos_label;
+
+// only the library, as it builds with `--no-default-features`
+#[cfg(test)]
+mod tests {
+	use crate::probability::probabilities;
+	use crate::solver::{self, Solver};
+	use crate::{MSGame, TurnResult};
+
+	// opens what the solver proves safe, then the tile least likely to be a mine, until the board is cleared or lost
+	fn play(game: &mut MSGame, mines: usize) -> bool {
+		let mut at = (game.width / 2, game.height / 2);
+		loop {
+			match game.open_at(at.0, at.1) {
+				Ok(TurnResult::Continue) => (),
+				Ok(TurnResult::Win) => return true,
+				_ => return false,
+			}
+			let view = game.view();
+			at = match solver::Standard.deduce(&view).safe.first() {
+				Some(&safe) => safe,
+				None => probabilities(&view, mines).best_guess(&view).expect("a hidden tile").at,
+			};
+		}
+	}
+
+	#[test]
+	fn plays_without_the_terminal_game() {
+		let results: Vec<_> = (0..50).map(|seed| play(&mut MSGame::new(9, 9, 10, seed).unwrap(), 10)).collect();
+		assert!(results.contains(&true) && results.contains(&false));
+		assert!(results.iter().filter(|&&won| won).count() > 25);
+	}
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use console::Key;
+
+use crate::action::Action;
+use crate::input::{command_key, key_command};
+use crate::lang::{trf, Msg};
+use crate::options::config_dir;
+
+// longest macro that can be recorded, and most times one can be played in a row
+const MAX_ACTIONS: usize = 500;
+pub const MAX_REPEATS: usize = 100;
+
+// Q and a letter records the following actions into that register until Q again,
+// @ and a letter plays them back, `@@` the last one played again. A count typed in front
+// (`3@a`) is the game's, whoever plays the macro repeats it that many times.
+// registers are kept in the config dir between games
+pub struct Macros {
+	registers: HashMap<char, Vec<Action>>,
+	recording: Option<(char, Vec<Action>)>,
+	waiting: Option<Waiting>,
+	last_played: Option<char>,
+}
+
+// the key after Q or @ picks the register
+#[derive(Clone, Copy)]
+enum Waiting {
+	Record,
+	Play,
+}
+
+// what a key meant to the macros
+pub enum MacroKey {
+	// not a macro key, it goes to the game
+	Other,
+	// used up by the macros, with a message to show
+	Handled(Option<String>),
+	Play(Vec<Action>),
+}
+
+impl Macros {
+	pub fn load() -> Self {
+		let mut registers = HashMap::new();
+		let text = macros_path().and_then(|path| fs::read_to_string(path).ok()).unwrap_or_default();
+		for line in text.lines() {
+			let mut words = line.split_whitespace();
+			let Some(register) = words.next().and_then(register_name) else {
+				continue;
+			};
+			let actions = words.filter_map(|word| Action::from_key(&command_key(word)?)).collect();
+			registers.insert(register, actions);
+		}
+		Self {
+			registers,
+			recording: None,
+			waiting: None,
+			last_played: None,
+		}
+	}
+
+	pub fn handle(&mut self, key: &Key) -> MacroKey {
+		if let Some(waiting) = self.waiting.take() {
+			let register = match (waiting, key) {
+				(Waiting::Play, Key::Char('@')) => self.last_played,
+				(_, Key::Char(c)) => register_name(&c.to_string()),
+				_ => None,
+			};
+			let Some(register) = register else {
+				return MacroKey::Handled(None);
+			};
+			return match waiting {
+				Waiting::Record => {
+					self.recording = Some((register, Vec::new()));
+					MacroKey::Handled(Some(trf(Msg::MacroRecording, &[&register])))
+				},
+				Waiting::Play => self.play(register),
+			};
+		}
+		match key {
+			Key::Char('Q') if self.recording.is_some() => MacroKey::Handled(self.stop()),
+			Key::Char('Q') => {
+				self.waiting = Some(Waiting::Record);
+				MacroKey::Handled(None)
+			},
+			Key::Char('@') => {
+				self.waiting = Some(Waiting::Play);
+				MacroKey::Handled(None)
+			},
+			_ => MacroKey::Other,
+		}
+	}
+
+	// adds an action to the macro being recorded. restarting and quitting are left out
+	pub fn record(&mut self, action: Action) -> Option<String> {
+		let (register, actions) = self.recording.as_mut()?;
+		if matches!(action, Action::Restart | Action::Quit) {
+			return None;
+		}
+		if actions.len() == MAX_ACTIONS {
+			let register = *register;
+			self.recording = None;
+			return Some(trf(Msg::MacroTooLong, &[&register, &MAX_ACTIONS]));
+		}
+		actions.push(action);
+		None
+	}
+
+	// the register being recorded into, for the status bar
+	pub fn recording(&self) -> Option<char> {
+		self.recording.as_ref().map(|(register, _)| *register)
+	}
+
+	fn stop(&mut self) -> Option<String> {
+		let (register, actions) = self.recording.take()?;
+		let length = actions.len();
+		self.registers.insert(register, actions);
+		if self.save().is_err() {
+			return Some(trf(Msg::MacroNotSaved, &[&register]));
+		}
+		Some(trf(Msg::MacroSaved, &[&register, &length]))
+	}
+
+	fn play(&mut self, register: char) -> MacroKey {
+		// playing the macro being recorded would record it into itself
+		if self.recording() == Some(register) {
+			return MacroKey::Handled(None);
+		}
+		let Some(actions) = self.registers.get(&register).filter(|actions| !actions.is_empty()) else {
+			return MacroKey::Handled(Some(trf(Msg::MacroEmpty, &[&register])));
+		};
+		self.last_played = Some(register);
+		MacroKey::Play(actions.clone())
+	}
+
+	fn save(&self) -> std::io::Result<()> {
+		let Some(path) = macros_path() else {
+			return Ok(());
+		};
+		let mut registers: Vec<_> = self.registers.iter().collect();
+		registers.sort_by_key(|(register, _)| **register);
+		let mut text = String::new();
+		for (register, actions) in registers {
+			let commands: Vec<String> = actions.iter().filter_map(|action| key_command(&action.key())).collect();
+			text.push_str(&format!("{} {}\n", register, commands.join(" ")));
+		}
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		fs::write(path, text)
+	}
+}
+
+// registers are named by a lowercase letter
+fn register_name(word: &str) -> Option<char> {
+	let mut chars = word.chars();
+	match (chars.next(), chars.next()) {
+		(Some(c), None) if c.is_ascii_lowercase() => Some(c),
+		_ => None,
+	}
+}
+
+fn macros_path() -> Option<PathBuf> {
+	Some(config_dir()?.join("macros"))
+}
@@ -1,355 +1,136 @@
-use std::ops::Add;
-use std::ops::Rem;
-use std::time::SystemTime;
+use std::rc::Rc;
 
 use console::Term;
-use console::Key;
-use console::style;
 
-use rand::prelude::SliceRandom;
-use rand::thread_rng;
-
-const NEIGHBOR_OFFSETS: [(i32, i32); 8] = [(-1,-1),(0,-1),(1,-1),(-1,0),(1,0),(-1,1),(0,1),(1,1)];
-
-enum TurnResult {
-	Continue,
-	Lose,
-	Win,
-	Quit,
-}
-
-enum Direction {
-	Up,
-	Down,
-	Left,
-	Right
-}
+use minesweeper::browser::{self, Choice};
+use minesweeper::error::MinesweeperError;
+use minesweeper::glyphs::Glyphs;
+use minesweeper::options::{self, apply_neighborhood, new_game, Options};
+use minesweeper::replay::{self, Replay};
+use minesweeper::solver::Standard;
+use minesweeper::{
+	analyze, bench, boardfile, campaign, cast, diagnose, drill, editor, export, generate, import, lang, leaderboard,
+	marathon, recovery, spectate, submit, svg, theme, tournament, App,
+};
 
 fn main() {
-	let stdout = Term::buffered_stdout();
-	let mut game = MSGame::new(16, 16, 32);
-	game.draw(&stdout);
-	let start_time = SystemTime::now();
-
-	loop {
-		let action = game.process_key(stdout.read_key().expect("failed to read key"));
-		game.draw(&stdout);
-		match action {
-			TurnResult::Quit => break,
-			TurnResult::Lose => {
-				println!("GAME OVER!");
-				break;
-			},
-			TurnResult::Win => {
-				println!("YOU WIN!");
-				break;
-			},
-			TurnResult::Continue => (),
-		}
+	let mut options = Options::load().unwrap_or_else(|err| exit_with(err));
+	if options.list_themes {
+		theme::list_themes();
+		return;
 	}
-	let time_taken = SystemTime::now()
-		.duration_since(start_time)
-		.unwrap()
-		.as_secs();
-	println!("Time taken: {}s", time_taken);
-}
-
-struct MSGame {
-	width: usize,
-	height: usize,
-	cursor_x: usize,
-	cursor_y: usize,
-	board: Vec<Tile>,
-	mines: usize,
-	flags: usize,
-}
-
-impl MSGame {
-	fn new(width: usize, height: usize, mines: usize) -> Self {
-		let size = width * height;
-		let mut board = Vec::<Tile>::with_capacity(size);
-		
-		let empty_tiles = size.saturating_sub(mines);
-
-		board.resize_with(empty_tiles, || {Tile::new(false)});
-		for _ in 0..mines {
-			board.push(Tile::new(true));
-		}
-		board.shuffle(&mut thread_rng());
-
-		let mut new_game = Self {
-			cursor_x: 0,
-			cursor_y: 0,
-			width,
-			height,
-			board,
-			flags: 0,
-			mines,
-		};
-		new_game.count_neighbors();
-		new_game
+	if options.list_profiles {
+		options::list_profiles();
+		return;
 	}
-
-	fn count_neighbors(&mut self) {
-		// count neighbors for all tiles
-		for center_y in 0..self.height {
-			for center_x in 0..self.width {
-				let tile = self.get(center_x, center_y);
-				if let TileContents::Mine = tile.contents {
-					// this tile is a mine so we add 1 to the counts of all neighboring empty tiles
-					for (dx, dy) in NEIGHBOR_OFFSETS {
-						let x = center_x.wrapping_add(dx as usize);
-						let y = center_y.wrapping_add(dy as usize);
-						
-						if !self.valid_pos(x, y) {
-							continue;
-						}
-						
-						let tile = self.get_mut(x, y);
-						if let TileContents::Safe(count) = tile.contents {
-							tile.contents = TileContents::Safe(count + 1);
-						}
-
-					}
-				}
-			}
-		}
+	lang::init(options.lang.as_deref()).unwrap_or_else(|err| exit_with(err));
+	let glyphs = match &options.glyphs {
+		Some(name) => Glyphs::by_name(name).unwrap_or_else(|err| exit_with(err)),
+		None => Glyphs::detect(&Term::stdout()),
+	};
+	if let Some(code) = &options.leaderboard {
+		std::process::exit(leaderboard::print(code));
 	}
-
-	fn process_key(&mut self, key: Key) -> TurnResult{
-		match key {
-			Key::ArrowUp    => self.move_cursor(Direction::Up),
-			Key::ArrowLeft  => self.move_cursor(Direction::Left),
-			Key::ArrowDown  => self.move_cursor(Direction::Down),
-			Key::ArrowRight => self.move_cursor(Direction::Right),
-			Key::Char('f') => self.flag_tile(),
-			Key::Char(' ') => self.open_tile(),
-			Key::Escape	| Key::Char('q') => return TurnResult::Quit,
-			_ => (),
-		}
-		self.check_board()
+	if let Some(url) = &options.fetch_daily_top {
+		std::process::exit(submit::print_top(url, &options));
 	}
-
-	fn check_board(&mut self) -> TurnResult {
-		let mut explored = true;
-		for y in 0..self.height {
-			for x in 0..self.width {
-				let tile = self.get(x, y);
-				match tile.visibility {
-					TileVis::Open => {
-						if let TileContents::Mine = tile.contents {
-							self.open_mines();
-							return TurnResult::Lose;
-						}
-					},
-					TileVis::Hidden => {
-						if let TileContents::Safe(_) = tile.contents {
-							explored = false;
-						}
-					},
-					_ => (),
-				}
-			}
-		}
-		if explored {
-			TurnResult::Win
-		}
-		else {
-			TurnResult::Continue
-		}
+	if let Some(path) = &options.import_replay {
+		std::process::exit(import::run(path));
 	}
-
-	fn open_single_tile(&mut self, x: usize, y: usize) {
-		let i = self.index_of(x, y);
-		let tile = &mut self.board[i];
-		if let TileVis::Hidden = tile.visibility {
-			tile.visibility = TileVis::Open;
-		}
+	if let Some(path) = &options.verify {
+		std::process::exit(replay::verify(path));
 	}
-
-	// flood fill to open all adjacent clear tiles
-	fn open_tile(&mut self) {
-		let mut queue = vec![(self.cursor_x, self.cursor_y)];
-		let mut i = 0;
-		
-		while i < queue.len() {
-			let (x, y) = queue[i];
-			let tile = self.get(x, y);
-			
-			if let TileVis::Hidden = tile.visibility {
-				self.open_single_tile(x, y);
-				// if this tile is a 0, add its neighbors to the queue (if they are not already open)
-				if let TileContents::Safe(0) = tile.contents {
-					for (dx, dy) in NEIGHBOR_OFFSETS {
-						let target_x = x.wrapping_add(dx as usize);
-						let target_y = y.wrapping_add(dy as usize);
-						if !self.valid_pos(target_x, target_y) {
-							continue;
-						}
-						let target = self.get(target_x, target_y);
-						if let TileVis::Open = target.visibility {
-							continue;
-						}
-						queue.push((target_x, target_y));
-					}
-				}
-			}
-			i += 1;
-		}
-		
+	if let Some(path) = &options.edit {
+		std::process::exit(editor::run(path, &options, glyphs));
 	}
-
-	fn open_mines(&mut self) {
-		for tile in &mut self.board {
-			if let TileContents::Mine = tile.contents {
-				tile.visibility = TileVis::Open;
-			}
-		}
+	if let Some(name) = &options.drill {
+		std::process::exit(drill::run(name, &options, glyphs));
 	}
-
-	fn flag_tile(&mut self) {
-		let i = self.index_of(self.cursor_x, self.cursor_y);
-		let tile = &mut self.board[i];
-
-		match tile.visibility {
-			TileVis::Flag => {
-				tile.visibility = TileVis::Hidden;
-				self.flags -= 1;
-			},
-			TileVis::Hidden => {
-				tile.visibility = TileVis::Flag;
-				self.flags += 1;
-			},
-			TileVis::Open => (),
-		}
+	if let Some(path) = &options.spectate {
+		std::process::exit(spectate::run(path, &options, glyphs));
 	}
-
-	fn move_cursor(&mut self, direction: Direction) {
-		match direction {
-			Direction::Up	=> self.cursor_y = self.cursor_y
-				.wrapping_sub(1)
-				.min(self.height - 1),
-			Direction::Down	=> self.cursor_y = self.cursor_y
-				.add(1)
-				.rem(self.height),
-			Direction::Left	=> self.cursor_x = self.cursor_x
-				.wrapping_sub(1)
-				.min(self.width - 1),
-			Direction::Right=> self.cursor_x = self.cursor_x
-				.add(1)
-				.rem(self.width),
-		}
-	}
-
-	fn draw(&self, stdout: &Term) {
-		stdout.clear_screen().unwrap();
-		stdout.flush().unwrap();
-
-		for row in 0..self.height {
-			cell_gap(self.cursor_x, self.cursor_y, usize::MAX, row);
-			
-			for col in 0..self.width {
-				let tile = self.get(col, row);
-				tile.draw();
-				cell_gap(self.cursor_x, self.cursor_y, col, row);
-			}
-			println!();
-		}
-		println!();
-		println!("Mines: {}, Flags: {}, Remaining: {}", self.mines, self.flags, self.mines - self.flags);
-		println!("Use arrow keys to move, space to open tiles, F to place flags");
-
-		fn cell_gap(cursor_x: usize, cursor_y: usize, col: usize, row: usize) {
-			if cursor_y != row {
-				print!(" ");
-				return;
-			}
-			match cursor_x.wrapping_sub(col) {
-				1 => print!("("),
-				0 => print!(")"),
-				_ => print!(" "),
-			}
-		}
+	if let Some(out) = &options.export_stats {
+		std::process::exit(export::run(out));
 	}
-
-	fn get(&self, x: usize, y: usize) -> Tile {
-		if !self.valid_pos(x, y) {
-			panic!("invalid get pos");
-		}
-		let i = self.index_of(x, y);
-		self.board[i]
+	if options.browse {
+		match browser::run() {
+			Ok(Some(Choice::Play(args, seed))) => {
+				options.set_game(&args).unwrap_or_else(|err| exit_with(err));
+				options.seed = Some(seed);
+			},
+			Ok(Some(Choice::Watch(path))) => options.watch = Some(path),
+			Ok(None) => return,
+			Err(err) => exit_with(err),
+		}
+	}
+	// a replay being watched plays the same game with the keys from the file, and leaves no records
+	let watching = options.watch.clone().map(|path| {
+		let replay = Replay::load(&path).unwrap_or_else(|err| exit_with(err));
+		replay.configure(&mut options).unwrap_or_else(|err| exit_with(err));
+		replay
+	});
+	if watching.is_some() {
+		options.history = false;
+		options.record = None;
+	}
+	if let (Some(replay), Some(path)) = (&watching, &options.export_cast) {
+		std::process::exit(cast::export(replay, &options, glyphs, path));
+	}
+	if let (Some(replay), Some(path)) = (&watching, &options.export_svg) {
+		std::process::exit(svg::export(replay, &options, path));
+	}
+	if options.diagnose_term {
+		diagnose::run(&glyphs);
+		return;
+	}
+	if options.bench {
+		std::process::exit(bench::run(glyphs));
+	}
+	if let Some(count) = options.generate {
+		std::process::exit(generate::run(&options, count, Rc::new(Standard)));
+	}
+	if let Some(path) = &options.export_board {
+		let game = new_game(&options).unwrap_or_else(|err| exit_with(err));
+		boardfile::save(&game, path).unwrap_or_else(|err| exit_with(err));
+		return;
+	}
+	if options.analyze {
+		let mut game = new_game(&options).unwrap_or_else(|err| exit_with(err));
+		apply_neighborhood(&mut game, &options).unwrap_or_else(|err| exit_with(err));
+		std::process::exit(analyze::run(game));
+	}
+	if options.campaign {
+		std::process::exit(campaign::run(options, glyphs));
+	}
+	if options.marathon.is_some() {
+		std::process::exit(marathon::run(options, glyphs));
+	}
+	if let Some(path) = options.tournament.clone() {
+		std::process::exit(tournament::run(&path, options, glyphs));
+	}
+	// a game saved by a crash can be picked up again, but not in a race or a replay
+	let race = options.host.is_some() || options.connect.is_some();
+	let recovered = if watching.is_none() && !race {
+		recovery::install();
+		recovery::offer(&mut options)
+	} else {
+		None
+	};
+	let mut app = App::new(options, glyphs, watching).unwrap_or_else(|err| exit_with(err));
+	if let Some(recovery) = &recovered {
+		app.resume(recovery).unwrap_or_else(|err| exit_with(err));
 	}
-
-	fn get_mut(&mut self, x: usize, y: usize) -> &mut Tile {
-		if !self.valid_pos(x, y) {
-			panic!("invalid get pos");
+	loop {
+		let outcome = app.run();
+		let summary = app.report(outcome);
+		if !app.finish(&summary) {
+			break;
 		}
-		let i = self.index_of(x, y);
-		&mut self.board[i]
-	}
-	
-	fn valid_pos(&self, x: usize, y: usize) -> bool {
-		x < self.width && y < self.height
-	}
-
-	fn index_of(&self, x: usize, y: usize) -> usize {
-		x + y * self.width
 	}
 }
 
-#[derive(Copy, Clone)]
-struct Tile {
-	contents: TileContents,
-	visibility: TileVis
-}
-
-#[derive(Copy, Clone)]
-enum TileContents {
-	Safe(u8),
-	Mine,
-}
-
-#[derive(Copy, Clone)]
-enum TileVis {
-	Hidden,
-	Flag,
-	Open,
-}
-
-
-impl Tile {
-	fn new(mine: bool) -> Self {
-		let contents = if mine {
-			TileContents::Mine
-		} else {
-			TileContents::Safe(0)
-		};
-		Self {
-			contents,
-			visibility: TileVis::Hidden
-		}
-	}
-
-	fn draw(&self) {
-		let out = match self.visibility {
-			TileVis::Hidden => style("#".into()).dim(),
-			TileVis::Open => {
-				match self.contents {
-					TileContents::Mine => style("*".into()).black().on_red(),
-					TileContents::Safe(0) => style(" ".into()),
-					TileContents::Safe(num) => {
-						let n = style(num.to_string());
-						match num {
-							1 => n.green().dim(),
-							2 => n.cyan().bright(),
-							3 => n.yellow().bright(),
-							_ => n.magenta(),
-						}
-					} 
-				}
-			},
-			TileVis::Flag => style("F".into()).red().bright(),
-		};
-		print!("{}", out);
-	}
+fn exit_with(err: impl Into<MinesweeperError>) -> ! {
+	let err = err.into();
+	eprintln!("{}", err);
+	std::process::exit(err.exit_code());
 }
@@ -0,0 +1,78 @@
+use std::time::{Duration, SystemTime};
+
+use crate::app::App;
+use crate::glyphs::Glyphs;
+use crate::lang::{tr, trf, Msg};
+use crate::options::Options;
+use crate::records::Records;
+use crate::{recovery, TurnResult};
+
+// taken off the time left for every mine hit
+const PENALTY: Duration = Duration::from_secs(15);
+
+// clears boards of one size against the clock until the time runs out or the player quits,
+// returns the exit code
+pub fn run(options: Options, glyphs: Glyphs) -> i32 {
+	let Some(budget) = options.marathon else {
+		return 2;
+	};
+	let (width, height, mines) = (options.width, options.height, options.mines);
+	// a marathon is its own category, with the time it was given
+	let difficulty = format!("{}x{}x{}-marathon{}", width, height, mines, budget.as_secs());
+	let mut app = match App::new(options, glyphs, None) {
+		Ok(app) => app,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	app.set_deadline(Some(SystemTime::now() + budget));
+	let mut cleared = 0;
+	let mut hits = 0;
+	let elapsed = loop {
+		app.set_label(Some(trf(Msg::MarathonStatus, &[&cleared])));
+		let outcome = app.run();
+		match outcome.result {
+			TurnResult::Win => cleared += 1,
+			TurnResult::Lose | TurnResult::Surrender => {
+				hits += 1;
+				app.set_deadline(app.deadline().map(|deadline| deadline - PENALTY));
+				app.notify(trf(Msg::MarathonPenalty, &[&PENALTY.as_secs()]));
+			},
+			// the time ran out, or the player stopped early
+			_ => break outcome.elapsed,
+		}
+		// the next board starts right away, there is no end screen between them
+		if let Err(err) = app.set_board(width, height, mines) {
+			app.close(&[]);
+			eprintln!("{}", err);
+			return 2;
+		}
+	};
+	recovery::clear();
+
+	// the board the time ran out on counts for as much of it as was cleared
+	let progress = app.progress();
+	let score = cleared as f64 + progress;
+	let mut lines = vec![
+		trf(Msg::MarathonOver, &[&cleared, &format!("{:.0}", progress * 100.0), &format!("{:.2}", score)]),
+	];
+	if hits > 0 {
+		lines.push(trf(Msg::MarathonHits, &[&hits, &(hits * PENALTY.as_secs())]));
+	}
+	// kept in hundredths of a board, as a high score
+	let mut records = Records::load();
+	let best = records.high_score(&difficulty);
+	if records.submit_score(&difficulty, (score * 100.0).round() as u64) {
+		records.save();
+		lines.push(match best {
+			Some(best) => trf(Msg::MarathonNewBestWas, &[&format!("{:.2}", best as f64 / 100.0)]),
+			None => tr(Msg::MarathonNewBest).into(),
+		});
+	} else if let Some(best) = best {
+		lines.push(trf(Msg::MarathonBest, &[&format!("{:.2}", best as f64 / 100.0)]));
+	}
+	app.interlude(elapsed, &lines, Msg::PressExit);
+	app.close(&lines);
+	0
+}
@@ -0,0 +1,50 @@
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+// how long a message stays up if no key is pressed
+const MESSAGE_TIME: Duration = Duration::from_secs(4);
+
+// short messages for the status bar, shown one at a time in the order they came.
+// each one stays until the next key or until it times out, then the next one comes up
+#[derive(Default)]
+pub struct Notices {
+	queue: VecDeque<String>,
+	// the message on screen and when it appeared
+	shown: Option<(String, SystemTime)>,
+}
+
+impl Notices {
+	pub fn push(&mut self, text: impl Into<String>) {
+		self.queue.push_back(text.into());
+	}
+
+	// for messages that may not be there
+	pub fn extend(&mut self, text: Option<String>) {
+		self.queue.extend(text);
+	}
+
+	// a key was pressed, so the message on screen has been seen
+	pub fn dismiss(&mut self) {
+		self.shown = None;
+	}
+
+	// takes down the message on screen once it has been up long enough, true if it did
+	pub fn expire(&mut self) -> bool {
+		let expired = self
+			.shown
+			.as_ref()
+			.is_some_and(|(_, since)| since.elapsed().unwrap_or_default() >= MESSAGE_TIME);
+		if expired {
+			self.shown = None;
+		}
+		expired
+	}
+
+	// the message to show, bringing up the next one when nothing is on screen
+	pub fn current(&mut self) -> Option<String> {
+		if self.shown.is_none() {
+			self.shown = self.queue.pop_front().map(|text| (text, SystemTime::now()));
+		}
+		self.shown.as_ref().map(|(text, _)| text.clone())
+	}
+}
@@ -0,0 +1,824 @@
+use std::env;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::thread_rng;
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::boardfile;
+use crate::error::MinesweeperError;
+use crate::game::{Lattice, Shifting, JUMP, MAX_RADIUS};
+use crate::placement::Placement;
+use crate::submit;
+use crate::{FlagWin, MSGame};
+
+const RUSH_TIME: Duration = Duration::from_secs(20);
+const MARATHON_TIME: Duration = Duration::from_secs(5 * 60);
+// opens between mine shifts for a bare --shift-mines
+const SHIFT_EVERY: usize = 10;
+
+pub struct Options {
+	pub arcade: bool,
+	pub compact: bool,
+	pub minimap: bool,
+	pub dim_satisfied: bool,
+	pub highlight: bool,
+	pub frontier: bool,
+	// draw the board's rows as columns, None to do it when the terminal is taller than the board is wide
+	pub transpose: Option<bool>,
+	pub wide: bool,
+	pub checkerboard: bool,
+	pub grid_lines: bool,
+	pub theme: String,
+	pub list_themes: bool,
+	// a section of the config file to use on top of the rest of it
+	pub profile: Option<String>,
+	pub list_profiles: bool,
+	pub bell: bool,
+	pub flash: bool,
+	pub animation: bool,
+	pub width: usize,
+	pub height: usize,
+	pub mines: usize,
+	pub seed: Option<u64>,
+	pub board: Option<String>,
+	// shape file for boards that aren't rectangles
+	pub shape: Option<String>,
+	pub analyze: bool,
+	// time board setup and drawing on a big board, then exit
+	pub bench: bool,
+	// number of boards to generate
+	pub generate: Option<usize>,
+	pub out: Option<String>,
+	pub no_guess: bool,
+	pub three_bv: Option<RangeInclusive<usize>>,
+	// language code, taken from the locale when not given
+	pub lang: Option<String>,
+	pub plain: bool,
+	// unicode or ascii, picked from the terminal when not given
+	pub glyphs: Option<String>,
+	pub diagnose_term: bool,
+	pub history: bool,
+	// port to wait for a race opponent on
+	pub host: Option<u16>,
+	// the address the race host listens on, only this computer unless another one is given for LAN play
+	pub host_address: String,
+	// address of a race host
+	pub connect: Option<String>,
+	// a second player on the same keyboard, and their keys
+	pub coop: bool,
+	pub coop_keys: String,
+	pub altscreen: bool,
+	pub center: bool,
+	// only show tiles this close to the cursor
+	pub fog: Option<usize>,
+	// how long the fog lifts to show a flood fill
+	pub fog_flash: Duration,
+	// pause the timer after this long without a key, None to never pause
+	pub idle: Option<Duration>,
+	// move a mine after this many opens
+	pub shift_mines: Option<usize>,
+	// the number of mines in every row and column is shown next to the board
+	pub line_hints: bool,
+	// clicks allowed on top of the 3BV
+	pub click_margin: Option<usize>,
+	pub liar: bool,
+	// mines that are anti-mines
+	pub anti: Option<usize>,
+	pub triangles: bool,
+	// how far away tiles count as neighbors
+	pub radius: usize,
+	// tiles moved by shift+arrows
+	pub jump: usize,
+	pub win_by_flags: Option<FlagWin>,
+	// add a mine this often
+	pub rush: Option<Duration>,
+	pub placement: Placement,
+	// file pinning tiles as mines or safe, replaces the placement
+	pub template: Option<String>,
+	// show how many moves the solver can prove
+	pub forced_moves: bool,
+	pub guard: bool,
+	// opening an empty tile opens only that tile
+	pub no_cascade: bool,
+	// the game starts with one empty area already opened
+	pub open_start: bool,
+	// the status bar doesn't say how many mines there are
+	pub blind_count: bool,
+	// live 3BV/s and efficiency
+	pub rates: bool,
+	// print the best times on the board with this code and exit
+	pub leaderboard: Option<String>,
+	// turn a video from another minesweeper into a board file and a replay, and exit
+	pub import_replay: Option<String>,
+	// write the board the other options pick to this file, as a board file or an MBF board, and exit
+	pub export_board: Option<String>,
+	// play today's seeded board, the same one for everyone
+	pub daily: bool,
+	// send a daily win to this url, with `name` to show it under
+	pub submit: Option<String>,
+	pub name: Option<String>,
+	// print the best times on today's daily board from this url and exit
+	pub fetch_daily_top: Option<String>,
+	// write the history and records to this csv file and exit
+	pub export_stats: Option<String>,
+	// save the moves of the game to this file
+	pub record: Option<String>,
+	// replay this file without drawing and check its result, then exit
+	pub verify: Option<String>,
+	// play a replay back
+	pub watch: Option<String>,
+	// draw the watched replay into this asciinema file instead of the terminal
+	pub export_cast: Option<String>,
+	// draw the board the game ended on into this svg file, and the pixels per tile
+	pub export_svg: Option<String>,
+	pub svg_cell: usize,
+	// keep the state of the game in this file for a spectator, or be the spectator of one
+	pub publish: Option<String>,
+	pub spectate: Option<String>,
+	// pick a game from the history to play again or watch
+	pub browse: bool,
+	// levels of growing boards with three lives, picking up where the last session stopped
+	pub campaign: bool,
+	// clear as many boards as possible in this long
+	pub marathon: Option<Duration>,
+	// practice a pattern on small boards made for it, or all of them
+	pub drill: Option<String>,
+	// make or change a board file instead of playing
+	pub edit: Option<String>,
+	// play the boards listed in this file in order for one score, and write how it went to `tournament_report`
+	pub tournament: Option<String>,
+	pub tournament_report: Option<String>,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Self {
+			arcade: false,
+			compact: false,
+			minimap: false,
+			dim_satisfied: false,
+			highlight: false,
+			frontier: false,
+			transpose: None,
+			wide: false,
+			checkerboard: false,
+			grid_lines: false,
+			theme: "classic".into(),
+			list_themes: false,
+			profile: None,
+			list_profiles: false,
+			bell: true,
+			flash: true,
+			animation: true,
+			width: 16,
+			height: 16,
+			mines: 32,
+			seed: None,
+			board: None,
+			shape: None,
+			analyze: false,
+			bench: false,
+			generate: None,
+			out: None,
+			no_guess: false,
+			three_bv: None,
+			lang: None,
+			plain: false,
+			glyphs: None,
+			diagnose_term: false,
+			history: true,
+			host: None,
+			host_address: "127.0.0.1".into(),
+			connect: None,
+			coop: false,
+			coop_keys: crate::coop::DEFAULT_KEYS.into(),
+			altscreen: true,
+			center: true,
+			fog: None,
+			fog_flash: Duration::from_millis(400),
+			idle: Some(Duration::from_secs(60)),
+			shift_mines: None,
+			line_hints: false,
+			click_margin: None,
+			liar: false,
+			anti: None,
+			triangles: false,
+			radius: 1,
+			jump: JUMP,
+			win_by_flags: None,
+			rush: None,
+			placement: Placement::Uniform,
+			template: None,
+			forced_moves: false,
+			guard: false,
+			no_cascade: false,
+			open_start: false,
+			blind_count: false,
+			rates: false,
+			leaderboard: None,
+			import_replay: None,
+			export_board: None,
+			daily: false,
+			submit: None,
+			name: None,
+			fetch_daily_top: None,
+			export_stats: None,
+			record: None,
+			verify: None,
+			watch: None,
+			export_cast: None,
+			export_svg: None,
+			svg_cell: 24,
+			publish: None,
+			spectate: None,
+			browse: false,
+			campaign: false,
+			marathon: None,
+			drill: None,
+			edit: None,
+			tournament: None,
+			tournament_report: None,
+		}
+	}
+}
+
+impl Options {
+	// options from the config file, then the picked profile, overridden by the command line
+	pub fn load() -> Result<Self, String> {
+		let args: Vec<String> = env::args().skip(1).collect();
+		let (base, profiles) = read_config();
+		let mut options = Self::default();
+		options.parse(base.into_iter()).map_err(|err| format!("config: {}", err))?;
+		// the command line can pick a different profile than the config file does
+		let mut given = Self::default();
+		given.parse(args.iter().cloned())?;
+		if let Some(name) = given.profile.or(options.profile.clone()) {
+			let Some((_, profile)) = profiles.iter().find(|(profile, _)| *profile == name) else {
+				let names: Vec<_> = profiles.iter().map(|(name, _)| name.as_str()).collect();
+				return Err(match names.is_empty() {
+					true => format!("unknown profile '{}', the config file has none", name),
+					false => format!("unknown profile '{}', available profiles: {}", name, names.join(", ")),
+				});
+			};
+			options.parse(profile.iter().cloned()).map_err(|err| format!("profile {}: {}", name, err))?;
+		}
+		options.parse(args.into_iter())?;
+		let race = options.host.is_some() || options.connect.is_some();
+		if race && options.record.is_some() {
+			return Err("races can't be recorded".into());
+		}
+		if race && options.coop {
+			return Err("races are for one player each, not --coop".into());
+		}
+		if race && (options.watch.is_some() || options.browse) {
+			return Err("replays can't be watched in a race".into());
+		}
+		// only the size, mines and seed go over to the other player
+		if race && (options.mode().is_some() || options.board.is_some() || options.shape.is_some()) {
+			return Err("races are played on plain boards, --board, --shape, --template, the placements and the variants can't be used in one".into());
+		}
+		if options.daily {
+			if options.seed.is_some() || options.board.is_some() || options.shape.is_some() || options.template.is_some() {
+				return Err("--daily is today's seeded board, it can't be combined with --seed, --board, --shape or --template".into());
+			}
+			if options.watch.is_some() || options.browse {
+				return Err("--daily can't be combined with a replay".into());
+			}
+			options.seed = Some(submit::daily_seed());
+		}
+		// nothing is sent anywhere without --submit
+		if options.submit.is_some() && !options.daily {
+			return Err("--submit only sends times on the daily board, it needs --daily".into());
+		}
+		if options.submit.is_some() && options.name.is_none() {
+			return Err("--submit needs a name to show the time under, given with --name".into());
+		}
+		let own_board = options.seed.is_some() || options.board.is_some() || options.shape.is_some() || options.template.is_some();
+		if options.campaign && (race || own_board || options.watch.is_some() || options.browse) {
+			return Err("--campaign picks its own boards, it can't be combined with --seed, --board, --shape, --template, a race or a replay".into());
+		}
+		if options.marathon.is_some() && (race || own_board || options.campaign || options.watch.is_some() || options.browse) {
+			return Err("--marathon plays new boards one after another, it can't be combined with --seed, --board, --shape, --template, --campaign, a race or a replay".into());
+		}
+		// a seed only picks which drills come up and how they are turned
+		let drill_board = options.board.is_some() || options.shape.is_some() || options.template.is_some();
+		if options.drill.is_some() && (race || drill_board || options.campaign || options.marathon.is_some() || options.watch.is_some() || options.browse) {
+			return Err("--drill deals its own small boards, it can't be combined with --board, --shape, --template, --campaign, --marathon, a race or a replay".into());
+		}
+		if options.edit.is_some() && (race || own_board || options.campaign || options.marathon.is_some() || options.drill.is_some() || options.watch.is_some() || options.browse) {
+			return Err("--edit works on its own board file, it can't be combined with --seed, --board, --shape, --template, --campaign, --marathon, --drill, a race or a replay".into());
+		}
+		let other_mode = options.campaign || options.marathon.is_some() || options.drill.is_some() || options.edit.is_some();
+		if options.tournament.is_some() && (race || own_board || other_mode || options.watch.is_some() || options.browse) {
+			return Err("--tournament plays the boards in its file, it can't be combined with --seed, --board, --shape, --template, --campaign, --marathon, --drill, --edit, a race or a replay".into());
+		}
+		if options.tournament_report.is_some() && options.tournament.is_none() {
+			return Err("--tournament-report needs a tournament to report on, given with --tournament".into());
+		}
+		if options.export_cast.is_some() && options.watch.is_none() {
+			return Err("--export-cast needs the replay to draw, given with --watch".into());
+		}
+		options.check()?;
+		Ok(options)
+	}
+
+	// replaces the options `game_args` covers with those in `args` and keeps the rest,
+	// for playing a game again with the look picked for this one
+	pub fn set_game(&mut self, args: &[String]) -> Result<(), String> {
+		let default = Self::default();
+		self.width = default.width;
+		self.height = default.height;
+		self.mines = default.mines;
+		self.board = default.board;
+		self.shape = default.shape;
+		self.template = default.template;
+		self.placement = default.placement;
+		self.fog = default.fog;
+		self.shift_mines = default.shift_mines;
+		self.click_margin = default.click_margin;
+		self.rush = default.rush;
+		self.radius = default.radius;
+		self.jump = default.jump;
+		self.win_by_flags = default.win_by_flags;
+		self.liar = default.liar;
+		self.anti = default.anti;
+		self.triangles = default.triangles;
+		self.guard = default.guard;
+		self.no_cascade = default.no_cascade;
+		self.open_start = default.open_start;
+		self.blind_count = default.blind_count;
+		self.line_hints = default.line_hints;
+		self.forced_moves = default.forced_moves;
+		self.arcade = default.arcade;
+		self.coop = default.coop;
+		self.coop_keys = default.coop_keys;
+		self.parse(args.iter().cloned())?;
+		self.check()
+	}
+
+	fn check(&self) -> Result<(), String> {
+		let options = self;
+		if options.width == 0 || options.height == 0 {
+			return Err("the board must be at least 1x1".into());
+		}
+		if !(1..=MAX_RADIUS).contains(&options.radius) {
+			return Err(format!("the radius must be between 1 and {}", MAX_RADIUS));
+		}
+		if options.svg_cell == 0 {
+			return Err("--svg-cell must be at least 1".into());
+		}
+		if options.jump == 0 {
+			return Err("--jump must be at least 1".into());
+		}
+		if options.triangles && options.radius != 1 {
+			return Err("--radius only works on square boards".into());
+		}
+		if options.triangles && (options.analyze || options.no_guess) {
+			return Err("the solver doesn't know about triangle boards".into());
+		}
+		if options.anti.is_some() && (options.analyze || options.no_guess) {
+			return Err("the solver doesn't know about anti-mines".into());
+		}
+		// these change the numbers or move mines around, which the anti-mines wouldn't keep up with
+		let moves_mines = options.shift_mines.is_some() || options.rush.is_some();
+		if options.line_hints && (options.analyze || options.no_guess) {
+			return Err("the solver doesn't know about the row and column hints".into());
+		}
+		if options.line_hints && moves_mines {
+			return Err("--line-hints counts the mines once, it can't be combined with --shift-mines or --rush".into());
+		}
+		if options.anti.is_some() && (options.liar || options.triangles || options.radius != 1 || moves_mines) {
+			return Err("--anti can't be combined with --liar, --triangles, --radius, --shift-mines or --rush".into());
+		}
+		// a win by flags would go off as soon as the hidden count was reached
+		if options.blind_count && options.win_by_flags.is_some() {
+			return Err("--blind-count can't be combined with --win-by-flags, which would give the number of mines away".into());
+		}
+		crate::coop::check_keys(&options.coop_keys)?;
+		if options.rush == Some(Duration::ZERO) {
+			return Err("--rush-every needs at least 1 second between mines".into());
+		}
+		if options.marathon == Some(Duration::ZERO) {
+			return Err("--marathon-time needs at least 1 second".into());
+		}
+		if options.shift_mines == Some(0) {
+			return Err("--shift-mines needs at least 1 open between moves".into());
+		}
+		// the editor places its own mines
+		let sized_by_file = options.shape.is_some() || options.template.is_some() || options.edit.is_some();
+		if options.mines >= options.width * options.height && !sized_by_file {
+			return Err(format!("too many mines for a {}x{} board", options.width, options.height));
+		}
+		Ok(())
+	}
+
+	// the options that change the rules or the board, without the seed.
+	// everything else only changes how the game looks
+	pub fn game_args(&self) -> Vec<String> {
+		let mut args: Vec<String> = Vec::new();
+		let mut push = |arg: &str, value: Option<String>| {
+			args.push(arg.into());
+			args.extend(value);
+		};
+		push("--width", Some(self.width.to_string()));
+		push("--height", Some(self.height.to_string()));
+		push("--mines", Some(self.mines.to_string()));
+		for (arg, path) in [("--board", &self.board), ("--shape", &self.shape), ("--template", &self.template)] {
+			if let Some(path) = path {
+				push(arg, Some(path.clone()));
+			}
+		}
+		match self.placement {
+			Placement::Uniform | Placement::Template(_) => (),
+			Placement::Spaced(spacing) => push("--min-spacing", Some(spacing.to_string())),
+			Placement::Balanced(cols, rows) => push("--placement", Some(format!("balanced:{}x{}", cols, rows))),
+			Placement::Gradient { from, to, vertical } => {
+				let axis = if vertical { "vertical" } else { "horizontal" };
+				push("--gradient", Some(format!("{}:{}:{}", from, to, axis)));
+			},
+		}
+		if let Some(radius) = self.fog {
+			push("--fog", Some(radius.to_string()));
+		}
+		if let Some(count) = self.anti {
+			push("--anti", Some(count.to_string()));
+		}
+		if let Some(every) = self.shift_mines {
+			push("--shift-mines", Some(every.to_string()));
+		}
+		if let Some(margin) = self.click_margin {
+			push("--clicks", Some(margin.to_string()));
+		}
+		if let Some(every) = self.rush {
+			push("--rush-every", Some(every.as_secs().to_string()));
+		}
+		if self.radius != 1 {
+			push("--radius", Some(self.radius.to_string()));
+		}
+		// replays store the keys, so they need the same jump to end up on the same tiles
+		if self.jump != JUMP {
+			push("--jump", Some(self.jump.to_string()));
+		}
+		if self.coop && self.coop_keys != crate::coop::DEFAULT_KEYS {
+			push("--coop-keys", Some(self.coop_keys.clone()));
+		}
+		match self.win_by_flags {
+			Some(FlagWin::Also) => push("--win-by-flags", None),
+			Some(FlagWin::Only) => push("--win-by-flags-only", None),
+			None => (),
+		}
+		for (arg, on) in [
+			("--liar", self.liar),
+			("--triangles", self.triangles),
+			("--guard", self.guard),
+			("--no-cascade", self.no_cascade),
+			("--open-start", self.open_start),
+			("--blind-count", self.blind_count),
+			("--line-hints", self.line_hints),
+			("--forced-moves", self.forced_moves),
+			("--arcade", self.arcade),
+			("--coop", self.coop),
+		] {
+			if on {
+				push(arg, None);
+			}
+		}
+		args
+	}
+
+	fn parse(&mut self, args: impl Iterator<Item = String>) -> Result<(), String> {
+		let mut args = args.peekable();
+		while let Some(arg) = args.next() {
+			let mut value = || args.next().ok_or(format!("missing value for '{}'", arg));
+			match arg.as_str() {
+				"--arcade" => self.arcade = true,
+				"--compact" => self.compact = true,
+				"--minimap" => self.minimap = true,
+				"--dim-satisfied" => self.dim_satisfied = true,
+				"--highlight" => self.highlight = true,
+				"--frontier" => self.frontier = true,
+				"--transpose" => self.transpose = Some(true),
+				"--no-transpose" => self.transpose = Some(false),
+				"--wide" => self.wide = true,
+				"--checkerboard" => self.checkerboard = true,
+				"--grid-lines" => self.grid_lines = true,
+				"--theme" => self.theme = value()?,
+				"--list-themes" => self.list_themes = true,
+				"--profile" => self.profile = Some(value()?),
+				"--list-profiles" => self.list_profiles = true,
+				"--no-bell" => self.bell = false,
+				"--no-flash" => self.flash = false,
+				"--no-animation" => self.animation = false,
+				"--width" => self.width = number(&arg, value()?)?,
+				"--height" => self.height = number(&arg, value()?)?,
+				"--mines" => self.mines = number(&arg, value()?)?,
+				"--seed" => self.seed = Some(number(&arg, value()?)?),
+				"--board" => self.board = Some(value()?),
+				"--shape" => self.shape = Some(value()?),
+				"--analyze" => self.analyze = true,
+				"--bench-internal" => self.bench = true,
+				"--generate" => self.generate = Some(number(&arg, value()?)?),
+				"--out" => self.out = Some(value()?),
+				"--no-guess" => self.no_guess = true,
+				"--lang" => self.lang = Some(value()?),
+				"--plain" => self.plain = true,
+				"--glyphs" => self.glyphs = Some(value()?),
+				"--diagnose-term" => self.diagnose_term = true,
+				"--no-history" => self.history = false,
+				"--host" => self.host = Some(number(&arg, value()?)?),
+				"--host-address" => self.host_address = value()?,
+				"--connect" => self.connect = Some(value()?),
+				"--coop" => self.coop = true,
+				"--coop-keys" => self.coop_keys = value()?,
+				"--no-altscreen" => self.altscreen = false,
+				"--no-center" => self.center = false,
+				"--fog" => self.fog = Some(number(&arg, value()?)?),
+				"--idle" => {
+					let secs: u64 = number(&arg, value()?)?;
+					self.idle = (secs > 0).then(|| Duration::from_secs(secs));
+				},
+				"--fog-flash" => self.fog_flash = Duration::from_millis(number(&arg, value()?)?),
+				"--jump" => self.jump = number(&arg, value()?)?,
+				// the count is optional, the next argument is only taken when it isn't another option
+				"--shift-mines" => self.shift_mines = Some(match args.next_if(|next| !next.starts_with("--")) {
+					Some(every) => number(&arg, every)?,
+					None => SHIFT_EVERY,
+				}),
+				"--clicks" => self.click_margin = Some(number(&arg, value()?)?),
+				"--liar" => self.liar = true,
+				"--anti" => self.anti = Some(number(&arg, value()?)?),
+				"--triangles" => self.triangles = true,
+				"--radius" => self.radius = number(&arg, value()?)?,
+				"--win-by-flags" => self.win_by_flags = Some(FlagWin::Also),
+				"--win-by-flags-only" => self.win_by_flags = Some(FlagWin::Only),
+				"--min-spacing" => self.placement = match number(&arg, value()?)? {
+					0 => return Err("--min-spacing must be at least 1".into()),
+					1 => Placement::Uniform,
+					spacing => Placement::Spaced(spacing),
+				},
+				"--forced-moves" => self.forced_moves = true,
+				"--guard" => self.guard = true,
+				"--no-cascade" => self.no_cascade = true,
+				"--open-start" => self.open_start = true,
+				"--blind-count" => self.blind_count = true,
+				"--line-hints" => self.line_hints = true,
+				"--rates" => self.rates = true,
+				"--leaderboard" => self.leaderboard = Some(value()?),
+				"--import-replay" => self.import_replay = Some(value()?),
+				"--export-board" => self.export_board = Some(value()?),
+				"--daily" => self.daily = true,
+				"--submit" => self.submit = Some(value()?),
+				"--name" => self.name = Some(value()?),
+				"--fetch-daily-top" => self.fetch_daily_top = Some(value()?),
+				"--export-stats" => self.export_stats = Some(value()?),
+				"--record" => self.record = Some(value()?),
+				"--verify" => self.verify = Some(value()?),
+				"--watch" => self.watch = Some(value()?),
+				"--export-cast" => self.export_cast = Some(value()?),
+				"--export-svg" => self.export_svg = Some(value()?),
+				"--svg-cell" => self.svg_cell = number(&arg, value()?)?,
+				"--publish" => self.publish = Some(value()?),
+				"--spectate" => self.spectate = Some(value()?),
+				"--history" => self.browse = true,
+				"--campaign" => self.campaign = true,
+				"--drill" => self.drill = Some(value()?),
+				"--edit" => self.edit = Some(value()?),
+				"--tournament" => self.tournament = Some(value()?),
+				"--tournament-report" => self.tournament_report = Some(value()?),
+				"--marathon" => self.marathon = Some(MARATHON_TIME),
+				"--marathon-time" => self.marathon = Some(Duration::from_secs(number(&arg, value()?)?)),
+				"--template" => self.template = Some(value()?),
+				"--placement" => self.placement = Placement::parse(&value()?)?,
+				"--gradient" => self.placement = Placement::parse_gradient(&value()?)?,
+				"--rush" => self.rush = Some(RUSH_TIME),
+				"--rush-every" => self.rush = Some(Duration::from_secs(number(&arg, value()?)?)),
+				"--3bv" => {
+					let value = value()?;
+					let (min, max) = value
+						.split_once('-')
+						.ok_or(format!("expected a range like 30-60 for '{}'", arg))?;
+					self.three_bv = Some(number(&arg, min.into())?..=number(&arg, max.into())?);
+				},
+				_ => return Err(format!("unknown option '{}'", arg)),
+			}
+		}
+		Ok(())
+	}
+
+	// name of the game variant, for keeping its records apart
+	pub fn mode(&self) -> Option<String> {
+		let mut parts = Vec::new();
+		if let Some(radius) = self.fog {
+			parts.push(format!("fog{}", radius));
+		}
+		if let Some(every) = self.shift_mines {
+			parts.push(format!("shift{}", every));
+		}
+		if let Some(margin) = self.click_margin {
+			parts.push(format!("clicks{}", margin));
+		}
+		if self.guard {
+			parts.push("guard".into());
+		}
+		if self.no_cascade {
+			parts.push("nocascade".into());
+		}
+		if self.open_start {
+			parts.push("open".into());
+		}
+		if self.blind_count {
+			parts.push("blind".into());
+		}
+		if self.line_hints {
+			parts.push("lines".into());
+		}
+		if let Some(every) = self.rush {
+			parts.push(format!("rush{}", every.as_secs()));
+		}
+		match &self.template {
+			Some(path) => parts.push(format!("template-{}", path_stem(path))),
+			None => parts.extend(self.placement.name()),
+		}
+		if self.liar {
+			parts.push("liar".into());
+		}
+		if let Some(count) = self.anti {
+			parts.push(format!("anti{}", count));
+		}
+		if self.triangles {
+			parts.push("triangles".into());
+		}
+		if self.radius != 1 {
+			parts.push(format!("radius{}", self.radius));
+		}
+		if self.coop {
+			parts.push("coop".into());
+		}
+		if self.campaign {
+			parts.push("campaign".into());
+		}
+		match self.win_by_flags {
+			Some(FlagWin::Also) => parts.push("flags".into()),
+			Some(FlagWin::Only) => parts.push("flagsonly".into()),
+			None => (),
+		}
+		(!parts.is_empty()).then(|| parts.join("-"))
+	}
+}
+
+pub fn new_game(options: &Options) -> Result<MSGame, MinesweeperError> {
+	if let Some(path) = &options.board {
+		return boardfile::load(path);
+	}
+	let (width, height, void) = match &options.shape {
+		Some(path) => {
+			let (width, height, void) = boardfile::load_shape(path)?;
+			let playable = void.iter().filter(|&&v| !v).count();
+			if options.mines >= playable {
+				return Err(format!("too many mines for the {} tiles in '{}'", playable, path).into());
+			}
+			(width, height, void)
+		},
+		None => (options.width, options.height, Vec::new()),
+	};
+	let (width, height, void, placement) = match &options.template {
+		Some(path) => {
+			let template = boardfile::load_template(path)?;
+			(template.width, template.height, template.void, Placement::Template(template.pins))
+		},
+		None => (width, height, void, options.placement.clone()),
+	};
+	let seed = options.seed.unwrap_or_else(|| thread_rng().gen());
+	MSGame::shaped(width, height, options.mines, seed, void, placement)
+}
+
+// changes to which tiles count as neighbors, these decide the numbers so they come before anything else
+pub fn apply_neighborhood(game: &mut MSGame, options: &Options) -> Result<(), MinesweeperError> {
+	if options.triangles {
+		game.lattice = Lattice::Triangle;
+		game.count_neighbors();
+	}
+	if options.radius != 1 {
+		game.set_radius(options.radius)?;
+	}
+	Ok(())
+}
+
+// sets up the rule changes picked in the options
+pub fn apply_variants(game: &mut MSGame, options: &Options) -> Result<(), MinesweeperError> {
+	apply_neighborhood(game, options)?;
+	game.flag_win = options.win_by_flags;
+	if options.liar || options.triangles || options.anti.is_some() || options.line_hints {
+		// the solver would believe the wrong numbers, get the neighbors wrong or miss what the hints say
+		game.reviewable = false;
+	}
+	if options.line_hints {
+		game.count_lines();
+	}
+	game.guard = options.guard;
+	game.jump = options.jump;
+	game.cascade = !options.no_cascade;
+	game.blind = options.blind_count;
+	if let Some(count) = options.anti {
+		game.add_anti_mines(count, &mut StdRng::seed_from_u64(game.seed.unwrap_or_else(|| thread_rng().gen())));
+	}
+	if options.coop {
+		game.add_partner();
+	}
+	if options.forced_moves {
+		game.toggle_forced();
+	}
+	if options.liar {
+		game.liar = Some(game.seed.unwrap_or_else(|| thread_rng().gen()));
+	}
+	if let Some(margin) = options.click_margin {
+		game.click_budget = Some(game.three_bv() + margin);
+	}
+	if let Some(every) = options.shift_mines {
+		game.shifting = Some(Shifting {
+			every,
+			opens: 0,
+			rng: StdRng::seed_from_u64(game.seed.unwrap_or_else(|| thread_rng().gen())),
+		});
+	}
+	// last, so the area is opened with the numbers and rules it will be played with
+	if options.open_start && !game.open_start() {
+		return Err("--open-start needs an empty tile, but this board has none".into());
+	}
+	Ok(())
+}
+
+fn path_stem(path: &str) -> String {
+	Path::new(path).file_stem().map_or(path.into(), |stem| stem.to_string_lossy().into_owned())
+}
+
+fn number<T: std::str::FromStr>(arg: &str, value: String) -> Result<T, String> {
+	value.parse().map_err(|_| format!("invalid number '{}' for '{}'", value, arg))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+	let base = match env::var_os("XDG_CONFIG_HOME") {
+		Some(dir) => PathBuf::from(dir),
+		None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+	};
+	Some(base.join("minesweeper-rs"))
+}
+
+// the config file holds command line options, any number per line
+fn config_path() -> Option<PathBuf> {
+	Some(config_dir()?.join("config"))
+}
+
+// the options at the top of the config file, and the ones under each `[name]` line after them
+fn read_config() -> (Vec<String>, Vec<(String, Vec<String>)>) {
+	let text = config_path().and_then(|path| fs::read_to_string(path).ok()).unwrap_or_default();
+	let mut base = Vec::new();
+	let mut profiles: Vec<(String, Vec<String>)> = Vec::new();
+	for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+		if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+			profiles.push((name.trim().into(), Vec::new()));
+			continue;
+		}
+		let args = line.split_whitespace().map(String::from);
+		match profiles.last_mut() {
+			Some((_, profile)) => profile.extend(args),
+			None => base.extend(args),
+		}
+	}
+	(base, profiles)
+}
+
+// prints every profile in the config file with its options
+pub fn list_profiles() {
+	let (_, profiles) = read_config();
+	if profiles.is_empty() {
+		println!("no profiles, add them to the config file under a [name] line");
+	}
+	for (name, args) in profiles {
+		println!("{:<12} {}", name, args.join(" "));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parsed(args: &[&str]) -> Result<Options, String> {
+		let mut options = Options::default();
+		options.parse(args.iter().map(|&arg| arg.to_string()))?;
+		Ok(options)
+	}
+
+	#[test]
+	fn shift_mines_takes_an_optional_count() {
+		assert_eq!(parsed(&["--shift-mines"]).unwrap().shift_mines, Some(10));
+		assert_eq!(parsed(&["--shift-mines", "--liar"]).unwrap().shift_mines, Some(10));
+		assert_eq!(parsed(&["--shift-mines", "3", "--liar"]).unwrap().shift_mines, Some(3));
+		assert_eq!(parsed(&["--shift-mines", "x"]).err(), Some("invalid number 'x' for '--shift-mines'".into()));
+	}
+}
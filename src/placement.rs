@@ -0,0 +1,369 @@
+use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
+use rand::Rng;
+
+// how the mines are spread over the board
+#[derive(Clone, PartialEq)]
+pub enum Placement {
+	// every arrangement is equally likely
+	Uniform,
+	// no two mines closer than this many steps, diagonals included
+	Spaced(usize),
+	// the board split into a grid of this many columns and rows of zones,
+	// each getting its share of the mines give or take one
+	Balanced(usize, usize),
+	// tiles pinned as mines (Some(true)) or safe (Some(false)), the rest of the mines go in the free tiles
+	Template(Vec<Option<bool>>),
+	// the chance of a mine goes from one percentage at the left (or top) edge to another at the
+	// right (or bottom) edge, and the mine count follows from that
+	Gradient { from: u32, to: u32, vertical: bool },
+}
+
+// how many random fillings are tried before falling back to a packed one
+const DART_TRIES: usize = 100;
+
+impl Placement {
+	// parses the `--placement` value: uniform, balanced, or balanced:COLSxROWS
+	pub fn parse(text: &str) -> Result<Self, String> {
+		let zones = match text.split_once(':') {
+			None if text == "uniform" => return Ok(Self::Uniform),
+			None if text == "balanced" => (2, 2),
+			Some(("balanced", zones)) => zones
+				.split_once('x')
+				.and_then(|(cols, rows)| Some((cols.parse().ok()?, rows.parse().ok()?)))
+				.filter(|&(cols, rows)| cols > 0 && rows > 0)
+				.ok_or(format!("expected zones like 3x2, not '{}'", zones))?,
+			_ => return Err(format!("unknown placement '{}', expected uniform or balanced", text)),
+		};
+		Ok(Self::Balanced(zones.0, zones.1))
+	}
+
+	// parses the `--gradient` value: FROM:TO or FROM:TO:horizontal|vertical, in percent
+	pub fn parse_gradient(text: &str) -> Result<Self, String> {
+		let mut parts = text.split(':');
+		let mut percent = || -> Result<u32, String> {
+			parts
+				.next()
+				.and_then(|part| part.parse().ok())
+				.filter(|&percent| percent <= 100)
+				.ok_or(format!("expected a gradient like 8:30 in percent, not '{}'", text))
+		};
+		let (from, to) = (percent()?, percent()?);
+		let vertical = match parts.next() {
+			None | Some("horizontal") => false,
+			Some("vertical") => true,
+			Some(axis) => return Err(format!("unknown gradient direction '{}', expected horizontal or vertical", axis)),
+		};
+		Ok(Self::Gradient { from, to, vertical })
+	}
+
+	// the mine count a strategy decides itself, over the tiles that are part of the board
+	pub fn mines(&self, width: usize, height: usize, void: &[bool]) -> Option<usize> {
+		let Self::Gradient { from, to, vertical } = *self else {
+			return None;
+		};
+		let expected: f64 = playable(width, height, void)
+			.into_iter()
+			.map(|i| density(width, height, i, from, to, vertical))
+			.sum();
+		Some(expected.round() as usize)
+	}
+
+	// name for the records, None for uniform
+	pub fn name(&self) -> Option<String> {
+		match self {
+			Self::Uniform => None,
+			Self::Spaced(spacing) => Some(format!("spacing{}", spacing)),
+			Self::Balanced(cols, rows) => Some(format!("balanced{}x{}", cols, rows)),
+			Self::Template(_) => Some("template".into()),
+			Self::Gradient { from, to, vertical } => {
+				Some(format!("gradient{}to{}{}", from, to, if *vertical { "v" } else { "" }))
+			},
+		}
+	}
+
+	// fails with the largest mine count that fits, for strategies that can run out of room
+	pub fn check(&self, width: usize, height: usize, mines: usize, void: &[bool]) -> Result<(), String> {
+		match *self {
+			Self::Uniform | Self::Gradient { .. } => Ok(()),
+			Self::Template(ref pins) => {
+				let pinned = pins.iter().filter(|&&pin| pin == Some(true)).count();
+				let free = playable(width, height, void).into_iter().filter(|&i| pins[i].is_none()).count();
+				if pinned > mines {
+					return Err(format!("the template pins {} mines but there are only {}", pinned, mines));
+				}
+				if mines - pinned > free {
+					return Err(format!("at most {} mines fit in this template", pinned + free));
+				}
+				Ok(())
+			},
+			Self::Balanced(cols, rows) if cols > width || rows > height => {
+				Err(format!("a {}x{} board can't be split into {}x{} zones", width, height, cols, rows))
+			},
+			Self::Balanced(..) => Ok(()),
+			Self::Spaced(spacing) => {
+				let fit = packed(width, height, void, spacing).len();
+				if mines > fit {
+					return Err(format!("at most {} mines fit {} apart on this board", fit, spacing));
+				}
+				Ok(())
+			},
+		}
+	}
+
+	// which tiles get a mine, `void` is empty for a full rectangle.
+	// None for uniform placement, those boards are shuffled as a whole instead
+	pub fn mask(&self, width: usize, height: usize, mines: usize, void: &[bool], rng: &mut StdRng) -> Option<Vec<bool>> {
+		match *self {
+			Self::Uniform => None,
+			Self::Spaced(spacing) => Some(spaced(width, height, mines, void, spacing, rng)),
+			Self::Balanced(cols, rows) => Some(balanced(width, height, mines, void, (cols, rows), rng)),
+			Self::Template(ref pins) => Some(template(width, height, mines, void, pins, rng)),
+			Self::Gradient { from, to, vertical } => Some(gradient(width, height, mines, void, (from, to, vertical), rng)),
+		}
+	}
+}
+
+fn spaced(width: usize, height: usize, mines: usize, void: &[bool], spacing: usize, rng: &mut StdRng) -> Vec<bool> {
+	let mut cells = playable(width, height, void);
+	for _ in 0..DART_TRIES {
+		cells.shuffle(rng);
+		let mut mask = vec![false; width * height];
+		let mut placed = 0;
+		for &i in &cells {
+			if placed == mines {
+				break;
+			}
+			if fits(&mask, width, height, i, spacing) {
+				mask[i] = true;
+				placed += 1;
+			}
+		}
+		if placed == mines {
+			return mask;
+		}
+	}
+	// random fillings get stuck near the limit, pick from the tightest packing instead
+	let mut cells = packed(width, height, void, spacing);
+	cells.shuffle(rng);
+	let mut mask = vec![false; width * height];
+	for &i in cells.iter().take(mines) {
+		mask[i] = true;
+	}
+	mask
+}
+
+fn template(width: usize, height: usize, mines: usize, void: &[bool], pins: &[Option<bool>], rng: &mut StdRng) -> Vec<bool> {
+	let mut mask: Vec<_> = pins.iter().map(|&pin| pin == Some(true)).collect();
+	let pinned = mask.iter().filter(|&&mine| mine).count();
+	let mut free: Vec<_> = playable(width, height, void).into_iter().filter(|&i| pins[i].is_none()).collect();
+	free.shuffle(rng);
+	for &i in &free[..mines - pinned] {
+		mask[i] = true;
+	}
+	mask
+}
+
+// every tile gets a mine with its own chance, then mines are taken away at random or added with
+// those chances until there are exactly `mines`
+fn gradient(width: usize, height: usize, mines: usize, void: &[bool], (from, to, vertical): (u32, u32, bool), rng: &mut StdRng) -> Vec<bool> {
+	let cells = playable(width, height, void);
+	let chance = |i| density(width, height, i, from, to, vertical);
+	let mut mask = vec![false; width * height];
+	for &i in &cells {
+		mask[i] = rng.gen_bool(chance(i));
+	}
+	let (mut placed, mut empty): (Vec<_>, Vec<_>) = cells.into_iter().partition(|&i| mask[i]);
+	placed.shuffle(rng);
+	while placed.len() > mines {
+		mask[placed.pop().unwrap()] = false;
+	}
+	// the highest chance accepts every pick, the rest are accepted in proportion to theirs
+	let highest = from.max(to) as f64 / 100.0;
+	let mut left = mines - placed.len();
+	while left > 0 {
+		let pick = rng.gen_range(0..empty.len());
+		let i = empty[pick];
+		if highest == 0.0 || rng.gen_bool((chance(i) / highest).min(1.0)) {
+			mask[i] = true;
+			empty.swap_remove(pick);
+			left -= 1;
+		}
+	}
+	mask
+}
+
+// the chance of a mine at tile `i`, going evenly from `from` to `to` percent across the board
+fn density(width: usize, height: usize, i: usize, from: u32, to: u32, vertical: bool) -> f64 {
+	let (pos, len) = if vertical { (i / width, height) } else { (i % width, width) };
+	let t = if len > 1 { pos as f64 / (len - 1) as f64 } else { 0.0 };
+	(from as f64 + (to as f64 - from as f64) * t) / 100.0
+}
+
+// zone boundaries fall at i * width / cols, so on odd sizes the later zones are the wider ones
+fn balanced(width: usize, height: usize, mines: usize, void: &[bool], (cols, rows): (usize, usize), rng: &mut StdRng) -> Vec<bool> {
+	let mut zones = vec![Vec::new(); cols * rows];
+	for i in playable(width, height, void) {
+		let (x, y) = (i % width, i / width);
+		// the last zone whose start is at or before the tile
+		let col = (0..cols).rev().find(|&c| c * width / cols <= x).unwrap();
+		let row = (0..rows).rev().find(|&r| r * height / rows <= y).unwrap();
+		zones[col + row * cols].push(i);
+	}
+	let total: usize = zones.iter().map(Vec::len).sum();
+
+	// every zone gets the whole part of its share, the mines left over go to the zones with the
+	// largest remainders, ties broken at random. with fewer mines than zones that is all of them
+	let mut counts: Vec<_> = zones.iter().map(|zone| mines * zone.len() / total).collect();
+	let mut order: Vec<_> = (0..zones.len()).collect();
+	order.shuffle(rng);
+	order.sort_by_key(|&z| std::cmp::Reverse(mines * zones[z].len() % total));
+	// a zone with no remainder never gets one, so full zones stay full
+	let left = mines - counts.iter().sum::<usize>();
+	for &z in order.iter().take(left) {
+		counts[z] += 1;
+	}
+
+	let mut mask = vec![false; width * height];
+	for (zone, count) in zones.iter_mut().zip(counts) {
+		zone.shuffle(rng);
+		for &i in &zone[..count] {
+			mask[i] = true;
+		}
+	}
+	mask
+}
+
+// the tiles a greedy pass in reading order puts mines on, on a rectangle this is a grid `spacing` apart
+// and as many as can fit
+fn packed(width: usize, height: usize, void: &[bool], spacing: usize) -> Vec<usize> {
+	let mut mask = vec![false; width * height];
+	let mut cells = Vec::new();
+	for i in playable(width, height, void) {
+		if fits(&mask, width, height, i, spacing) {
+			mask[i] = true;
+			cells.push(i);
+		}
+	}
+	cells
+}
+
+// no mine in the mask is closer than `spacing` to tile `i`
+fn fits(mask: &[bool], width: usize, height: usize, i: usize, spacing: usize) -> bool {
+	let (x, y) = (i % width, i / width);
+	let r = spacing.saturating_sub(1);
+	(y.saturating_sub(r)..(y + r + 1).min(height))
+		.all(|ny| (x.saturating_sub(r)..(x + r + 1).min(width)).all(|nx| !mask[nx + ny * width]))
+}
+
+fn playable(width: usize, height: usize, void: &[bool]) -> Vec<usize> {
+	(0..width * height).filter(|&i| !void.get(i).copied().unwrap_or(false)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use rand::SeedableRng;
+
+	use super::*;
+
+	fn mines(mask: &[bool]) -> Vec<usize> {
+		(0..mask.len()).filter(|&i| mask[i]).collect()
+	}
+
+	// every pair of mines at least `spacing` steps apart, diagonals included
+	fn spaced_apart(mask: &[bool], width: usize, spacing: usize) -> bool {
+		let mines = mines(mask);
+		mines.iter().enumerate().all(|(n, &a)| {
+			mines[n + 1..].iter().all(|&b| (a % width).abs_diff(b % width).max((a / width).abs_diff(b / width)) >= spacing)
+		})
+	}
+
+	#[test]
+	fn spaced_mines_keep_their_distance() {
+		for seed in 0..50 {
+			let mut rng = StdRng::seed_from_u64(seed);
+			for (spacing, count) in [(2, 12), (2, 25), (3, 9), (1, 40)] {
+				assert!(Placement::Spaced(spacing).check(9, 9, count, &[]).is_ok());
+				let mask = Placement::Spaced(spacing).mask(9, 9, count, &[], &mut rng).unwrap();
+				assert_eq!(mines(&mask).len(), count);
+				assert!(spaced_apart(&mask, 9, spacing), "spacing {} seed {}", spacing, seed);
+			}
+		}
+	}
+
+	#[test]
+	fn too_many_spaced_mines_give_the_most_that_fit() {
+		// a 9x9 board fits a 5x5 grid of mines two apart, and a 3x3 grid three apart
+		assert_eq!(Placement::Spaced(2).check(9, 9, 26, &[]), Err("at most 25 mines fit 2 apart on this board".into()));
+		assert_eq!(Placement::Spaced(3).check(9, 9, 10, &[]), Err("at most 9 mines fit 3 apart on this board".into()));
+	}
+
+	#[test]
+	fn spacing_skips_void_tiles() {
+		let void: Vec<_> = (0..25).map(|i| i % 5 < 2).collect();
+		let mut rng = StdRng::seed_from_u64(3);
+		let mask = Placement::Spaced(2).mask(5, 5, 6, &void, &mut rng).unwrap();
+		assert!(mines(&mask).iter().all(|&i| !void[i]));
+		assert!(spaced_apart(&mask, 5, 2));
+	}
+
+	#[test]
+	fn templates_keep_their_pins() {
+		let template = crate::boardfile::load_template("templates/one-two-one.txt").unwrap();
+		let placement = Placement::Template(template.pins.clone());
+		let (width, height) = (template.width, template.height);
+		assert!(placement.check(width, height, 12, &template.void).is_ok());
+		for seed in 0..50 {
+			let mask = placement.mask(width, height, 12, &template.void, &mut StdRng::seed_from_u64(seed)).unwrap();
+			assert_eq!(mines(&mask).len(), 12);
+			for (i, pin) in template.pins.iter().enumerate() {
+				if let Some(mine) = *pin {
+					assert_eq!(mask[i], mine, "tile {} with seed {}", i, seed);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn templates_need_room_for_their_mines() {
+		// two pinned mines, two free tiles
+		let pins = [Some(true), Some(true), None, None, Some(false), Some(false)];
+		let placement = Placement::Template(pins.to_vec());
+		assert_eq!(placement.check(3, 2, 1, &[]), Err("the template pins 2 mines but there are only 1".into()));
+		assert_eq!(placement.check(3, 2, 5, &[]), Err("at most 4 mines fit in this template".into()));
+		assert!(placement.check(3, 2, 4, &[]).is_ok());
+	}
+
+	#[test]
+	fn gradients_are_parsed() {
+		assert!(matches!(Placement::parse_gradient("8:30"), Ok(Placement::Gradient { from: 8, to: 30, vertical: false })));
+		assert!(matches!(Placement::parse_gradient("30:0:vertical"), Ok(Placement::Gradient { from: 30, to: 0, vertical: true })));
+		assert!(Placement::parse_gradient("8:130").is_err());
+		assert!(Placement::parse_gradient("8").is_err());
+		assert!(Placement::parse_gradient("8:30:diagonal").is_err());
+	}
+
+	// over many boards every column holds about as many mines as its density gives it
+	#[test]
+	fn gradient_density_rises_across_the_board() {
+		let placement = Placement::Gradient { from: 0, to: 45, vertical: false };
+		let (width, height, boards) = (10, 12, 400);
+		// 5% more every column, 27 mines expected in total
+		let count = placement.mines(width, height, &[]).unwrap();
+		assert_eq!(count, 27);
+		let mut columns = vec![0; width];
+		for seed in 0..boards {
+			let mask = placement.mask(width, height, count, &[], &mut StdRng::seed_from_u64(seed)).unwrap();
+			assert_eq!(mines(&mask).len(), count);
+			for i in mines(&mask) {
+				columns[i % width] += 1;
+			}
+		}
+		assert_eq!(columns[0], 0);
+		for (x, &count) in columns.iter().enumerate() {
+			let average = count as f64 / boards as f64;
+			let expected = height as f64 * x as f64 * 0.05;
+			assert!((average - expected).abs() < 0.3, "column {} has {} mines on average, expected {}", x, average, expected);
+		}
+	}
+}
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+
+use crate::solver::{BoardView, Cell};
+
+/// Groups of connected frontier tiles up to this size are counted exactly,
+/// bigger ones get a rough estimate from their numbers alone.
+pub const EXACT_LIMIT: usize = 20;
+
+/// How likely every tile is to be a mine, given the opened numbers and the total number of mines.
+#[derive(Clone, Debug)]
+pub struct Probabilities {
+	width: usize,
+	// None for tiles that aren't hidden
+	chances: Vec<Option<f64>>,
+	/// False if some group of frontier tiles was too big to count exactly.
+	pub exact: bool,
+}
+
+/// The hidden tile least likely to be a mine.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Guess {
+	pub at: (usize, usize),
+	pub mine_chance: f64,
+}
+
+impl Probabilities {
+	/// Chances for a board `width` tiles wide, one for every tile in reading order and None for
+	/// the ones that aren't hidden. For solvers with their own way of working them out.
+	pub fn new(width: usize, chances: Vec<Option<f64>>, exact: bool) -> Self {
+		Self { width, chances, exact }
+	}
+
+	/// The chance that the tile is a mine, None if it isn't hidden.
+	pub fn get(&self, x: usize, y: usize) -> Option<f64> {
+		self.chances[x + y * self.width]
+	}
+
+	/// The safest tile, the first in reading order when several are equally safe.
+	/// Flagged tiles are left out, the player has already decided about those.
+	pub fn best_guess(&self, view: &BoardView) -> Option<Guess> {
+		let mut best: Option<Guess> = None;
+		for (i, chance) in self.chances.iter().enumerate() {
+			let at = (i % self.width, i / self.width);
+			let Some(chance) = *chance else {
+				continue;
+			};
+			if view.get(at.0, at.1) == Cell::Flagged {
+				continue;
+			}
+			if best.is_none_or(|best| chance < best.mine_chance) {
+				best = Some(Guess { at, mine_chance: chance });
+			}
+		}
+		best
+	}
+}
+
+// a set of frontier tiles tied together by the numbers around them
+struct Group {
+	cells: Vec<(usize, usize)>,
+	// indices into `cells` and how many of those are mines
+	constraints: Vec<(Vec<usize>, usize)>,
+}
+
+// the ways a group can be filled, by how many mines it has
+struct Fillings {
+	// fillings with k mines
+	count: Vec<f64>,
+	// for every cell, fillings with k mines where the cell is one of them
+	cell: Vec<Vec<f64>>,
+}
+
+/// Works out the mine chance of every hidden tile. Flags are treated like any other hidden tile.
+/// Frontier tiles are counted over every filling that fits the numbers, and the tiles away from
+/// the frontier share whatever mines are left, weighted by how many ways they can be placed.
+pub fn probabilities(view: &BoardView, mines: usize) -> Probabilities {
+	let (width, height) = (view.width(), view.height());
+	let hidden = |x, y| matches!(view.get(x, y), Cell::Hidden | Cell::Flagged);
+	let groups = groups(view);
+	let mut chances = vec![None; width * height];
+
+	let (exact, rough): (Vec<_>, Vec<_>) = groups.into_iter().partition(|group| group.cells.len() <= EXACT_LIMIT);
+	// big groups get the highest density of any number around each tile, and keep that many mines to themselves
+	let mut rough_mines = 0.0;
+	for group in &rough {
+		for (i, &(x, y)) in group.cells.iter().enumerate() {
+			let density = group
+				.constraints
+				.iter()
+				.filter(|(cells, _)| cells.contains(&i))
+				.map(|(cells, n)| *n as f64 / cells.len() as f64)
+				.fold(0.0, f64::max);
+			chances[x + y * width] = Some(density);
+			rough_mines += density;
+		}
+	}
+
+	let on_frontier: usize = exact.iter().chain(&rough).map(|group| group.cells.len()).sum();
+	let total_hidden = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).filter(|&(x, y)| hidden(x, y)).count();
+	let floating = total_hidden - on_frontier;
+	let mines = mines.saturating_sub(rough_mines.round() as usize);
+
+	let fillings: Vec<_> = exact.iter().map(fill).collect();
+	// ways to put t mines on the tiles away from the frontier after the groups took theirs, relative to the most likely t
+	let ln_factorial = ln_factorials(floating);
+	let ln_choose = |t: usize| -> Option<f64> {
+		let left = mines.checked_sub(t).filter(|&left| left <= floating)?;
+		Some(ln_factorial[floating] - ln_factorial[left] - ln_factorial[floating - left])
+	};
+	let max_frontier: usize = fillings.iter().map(|f| f.count.len() - 1).sum();
+	let peak = (0..=max_frontier).filter_map(ln_choose).fold(f64::MIN, f64::max);
+	let rest = |t: usize| ln_choose(t).map_or(0.0, |ln| (ln - peak).exp());
+
+	// fillings of every group but one, by how many mines they hold together
+	let combined = |skip: Option<usize>| {
+		let mut total = vec![1.0];
+		for (i, filling) in fillings.iter().enumerate() {
+			if Some(i) != skip {
+				total = convolve(&total, &filling.count);
+			}
+		}
+		total
+	};
+	let all = combined(None);
+	let weight: f64 = all.iter().enumerate().map(|(t, &ways)| ways * rest(t)).sum();
+	if weight > 0.0 {
+		for (i, (group, filling)) in exact.iter().zip(&fillings).enumerate() {
+			let others = combined(Some(i));
+			for (c, &(x, y)) in group.cells.iter().enumerate() {
+				let mut mine = 0.0;
+				for (k, &ways) in filling.cell[c].iter().enumerate() {
+					for (s, &other) in others.iter().enumerate() {
+						mine += ways * other * rest(k + s);
+					}
+				}
+				chances[x + y * width] = Some(mine / weight);
+			}
+		}
+	}
+	if floating > 0 {
+		let floating_mines: f64 = all.iter().enumerate().map(|(t, &ways)| ways * rest(t) * mines.saturating_sub(t) as f64).sum();
+		let chance = if weight > 0.0 { floating_mines / weight / floating as f64 } else { 0.0 };
+		for y in 0..height {
+			for x in 0..width {
+				if hidden(x, y) && chances[x + y * width].is_none() {
+					chances[x + y * width] = Some(chance.min(1.0));
+				}
+			}
+		}
+	}
+
+	Probabilities {
+		width,
+		chances,
+		exact: rough.is_empty(),
+	}
+}
+
+// splits the hidden tiles next to opened numbers into groups that share numbers
+fn groups(view: &BoardView) -> Vec<Group> {
+	let mut constraints = Vec::new();
+	for y in 0..view.height() {
+		for x in 0..view.width() {
+			let Cell::Open(n) = view.get(x, y) else {
+				continue;
+			};
+			let cells: Vec<_> = view
+				.neighbors(x, y)
+				.filter(|&(nx, ny)| matches!(view.get(nx, ny), Cell::Hidden | Cell::Flagged))
+				.collect();
+			if !cells.is_empty() {
+				constraints.push((cells, n as usize));
+			}
+		}
+	}
+
+	// union find over the numbers, joined when they share a tile
+	let mut parent: Vec<_> = (0..constraints.len()).collect();
+	fn root(parent: &mut [usize], mut i: usize) -> usize {
+		while parent[i] != i {
+			parent[i] = parent[parent[i]];
+			i = parent[i];
+		}
+		i
+	}
+	let mut owner = HashMap::new();
+	for (i, (cells, _)) in constraints.iter().enumerate() {
+		for &cell in cells {
+			if let Some(&other) = owner.get(&cell) {
+				let (a, b) = (root(&mut parent, i), root(&mut parent, other));
+				parent[a] = b;
+			} else {
+				owner.insert(cell, i);
+			}
+		}
+	}
+
+	let mut by_root: HashMap<usize, Group> = HashMap::new();
+	let mut roots = Vec::new();
+	for (i, (cells, n)) in constraints.into_iter().enumerate() {
+		let r = root(&mut parent, i);
+		let group = by_root.entry(r).or_insert_with(|| {
+			roots.push(r);
+			Group { cells: Vec::new(), constraints: Vec::new() }
+		});
+		let indices = cells
+			.into_iter()
+			.map(|cell| match group.cells.iter().position(|&c| c == cell) {
+				Some(index) => index,
+				None => {
+					group.cells.push(cell);
+					group.cells.len() - 1
+				},
+			})
+			.collect();
+		group.constraints.push((indices, n));
+	}
+	roots.into_iter().map(|r| by_root.remove(&r).unwrap()).collect()
+}
+
+// every filling of the group that fits its numbers, by backtracking over the cells in order
+fn fill(group: &Group) -> Fillings {
+	let size = group.cells.len();
+	let mut of_cell = vec![Vec::new(); size];
+	for (c, (cells, _)) in group.constraints.iter().enumerate() {
+		for &cell in cells {
+			of_cell[cell].push(c);
+		}
+	}
+	let mut fillings = Fillings {
+		count: vec![0.0; size + 1],
+		cell: vec![vec![0.0; size + 1]; size],
+	};
+	// mines placed and cells still open for every number
+	let mut placed = vec![0; group.constraints.len()];
+	let mut unset: Vec<_> = group.constraints.iter().map(|(cells, _)| cells.len()).collect();
+	let mut mine = vec![false; size];
+
+	fn step(
+		i: usize,
+		group: &Group,
+		of_cell: &[Vec<usize>],
+		placed: &mut [usize],
+		unset: &mut [usize],
+		mine: &mut [bool],
+		fillings: &mut Fillings,
+	) {
+		if i == mine.len() {
+			let k = mine.iter().filter(|&&m| m).count();
+			fillings.count[k] += 1.0;
+			for (c, _) in mine.iter().enumerate().filter(|(_, &m)| m) {
+				fillings.cell[c][k] += 1.0;
+			}
+			return;
+		}
+		for is_mine in [false, true] {
+			let fits = of_cell[i].iter().all(|&c| {
+				let placed = placed[c] + is_mine as usize;
+				let n = group.constraints[c].1;
+				// not too many, and the cells after this one can still make up the rest
+				placed <= n && placed + unset[c] > n
+			});
+			if !fits {
+				continue;
+			}
+			for &c in &of_cell[i] {
+				placed[c] += is_mine as usize;
+				unset[c] -= 1;
+			}
+			mine[i] = is_mine;
+			step(i + 1, group, of_cell, placed, unset, mine, fillings);
+			for &c in &of_cell[i] {
+				placed[c] -= is_mine as usize;
+				unset[c] += 1;
+			}
+		}
+		mine[i] = false;
+	}
+	step(0, group, &of_cell, &mut placed, &mut unset, &mut mine, &mut fillings);
+	fillings
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+	let mut out = vec![0.0; a.len() + b.len() - 1];
+	for (i, &x) in a.iter().enumerate() {
+		for (j, &y) in b.iter().enumerate() {
+			out[i + j] += x * y;
+		}
+	}
+	out
+}
+
+fn ln_factorials(n: usize) -> Vec<f64> {
+	let mut table = vec![0.0; n + 1];
+	for i in 1..=n {
+		table[i] = table[i - 1] + (i as f64).ln();
+	}
+	table
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// one string per row: `#` hidden, `F` flagged, `X` void and digits for opened numbers
+	fn view(rows: &[&str]) -> BoardView {
+		let mut view = BoardView::new(rows[0].len(), rows.len());
+		for (y, row) in rows.iter().enumerate() {
+			for (x, c) in row.chars().enumerate() {
+				let cell = match c {
+					'#' => Cell::Hidden,
+					'F' => Cell::Flagged,
+					'X' => Cell::Void,
+					_ => Cell::Open(c.to_digit(10).expect("a digit") as u8),
+				};
+				view.set(x, y, cell);
+			}
+		}
+		view
+	}
+
+	fn assert_chance(probabilities: &Probabilities, at: (usize, usize), expected: f64) {
+		let chance = probabilities.get(at.0, at.1).expect("a hidden tile");
+		assert!((chance - expected).abs() < 1e-9, "{:?} is {}, not {}", at, chance, expected);
+	}
+
+	#[test]
+	fn nothing_opened_shares_the_mines_evenly() {
+		let probabilities = probabilities(&view(&["##", "##"]), 1);
+		for at in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+			assert_chance(&probabilities, at, 0.25);
+		}
+		assert!(probabilities.exact);
+	}
+
+	#[test]
+	fn opened_tiles_have_no_chance() {
+		let probabilities = probabilities(&view(&["##", "11"]), 1);
+		assert_eq!(probabilities.get(0, 1), None);
+		assert_chance(&probabilities, (0, 0), 0.5);
+		assert_chance(&probabilities, (1, 0), 0.5);
+	}
+
+	#[test]
+	fn one_two_one() {
+		let probabilities = probabilities(&view(&["###", "121"]), 2);
+		assert_chance(&probabilities, (0, 0), 1.0);
+		assert_chance(&probabilities, (1, 0), 0.0);
+		assert_chance(&probabilities, (2, 0), 1.0);
+	}
+
+	#[test]
+	fn fillings_are_weighted_by_the_tiles_away_from_the_frontier() {
+		// the middle tile alone leaves 1 of the 2 mines for the 5 tiles in the bottom row, 5 ways.
+		// the two outer tiles leave none, 1 way. so the middle is a mine 5 times out of 6,
+		// and the bottom row holds 5/6 of a mine on average, 1/6 per tile
+		let probabilities = probabilities(&view(&["#1#1#", "XXXXX", "#####"]), 2);
+		assert_chance(&probabilities, (2, 0), 5.0 / 6.0);
+		assert_chance(&probabilities, (0, 0), 1.0 / 6.0);
+		assert_chance(&probabilities, (4, 0), 1.0 / 6.0);
+		for x in 0..5 {
+			assert_chance(&probabilities, (x, 2), 1.0 / 6.0);
+		}
+	}
+
+	#[test]
+	fn flags_count_as_hidden() {
+		let probabilities = probabilities(&view(&["F#", "11"]), 1);
+		assert_chance(&probabilities, (0, 0), 0.5);
+		assert_chance(&probabilities, (1, 0), 0.5);
+	}
+
+	#[test]
+	fn best_guess_skips_flags_and_takes_the_first_of_equals() {
+		let first = view(&["#1#1#", "XXXXX", "F####"]);
+		let probabilities = probabilities(&first, 2);
+		assert_eq!(probabilities.best_guess(&first).map(|guess| guess.at), Some((0, 0)));
+		let flagged = view(&["F1#1#", "XXXXX", "#####"]);
+		assert_eq!(probabilities.best_guess(&flagged).map(|guess| guess.at), Some((4, 0)));
+	}
+
+	#[test]
+	fn big_groups_are_estimated() {
+		let hidden = "#".repeat(EXACT_LIMIT + 5);
+		let numbers = "1".repeat(EXACT_LIMIT + 5);
+		let probabilities = probabilities(&view(&[&hidden, &numbers]), 9);
+		assert!(!probabilities.exact);
+	}
+}
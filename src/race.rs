@@ -0,0 +1,186 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use crate::lang::{tr, trf, Msg};
+use crate::MSGame;
+
+// two players on the same seeded board. the host sends the board settings as
+// `board <width> <height> <mines> <seed>`, then both sides send `progress <cleared> <total>`
+// after moves and `won` or `died` when their game ends
+pub struct Race {
+	stream: Option<TcpStream>,
+	updates: Receiver<Update>,
+	opponent: Opponent,
+	// Some(true) once we have won the race, Some(false) once we have lost it
+	pub outcome: Option<bool>,
+	sent_cleared: usize,
+}
+
+enum Update {
+	Progress(usize, usize),
+	Won,
+	Died,
+	Disconnected,
+}
+
+enum Opponent {
+	Playing(usize, usize),
+	Won,
+	Died,
+	Disconnected,
+}
+
+// waits for the other player on `address` and sends them the board settings
+pub fn host(address: &str, port: u16, game: &MSGame) -> Result<Race, String> {
+	let Some(seed) = game.seed else {
+		return Err("races need a seeded board, not a board file".into());
+	};
+	let listener = TcpListener::bind((address, port)).map_err(|err| format!("could not listen on {} port {}: {}", address, port, err))?;
+	println!("waiting for a player on {} port {}...", address, port);
+	let (stream, _) = listener.accept().map_err(|err| format!("could not accept a player: {}", err))?;
+	let mut race = Race::new(stream)?;
+	race.send(&format!("board {} {} {} {}", game.width, game.height, game.mines, seed));
+	Ok(race)
+}
+
+// connects to a host, returning the race and the board it picked
+pub fn connect(addr: &str) -> Result<(Race, MSGame), String> {
+	let stream = TcpStream::connect(addr).map_err(|err| format!("could not connect to {}: {}", addr, err))?;
+	let mut reader = BufReader::new(stream.try_clone().map_err(|err| err.to_string())?);
+	let mut line = String::new();
+	reader.read_line(&mut line).map_err(|err| format!("could not read the board from {}: {}", addr, err))?;
+	let fields: Vec<_> = line.split_whitespace().collect();
+	let game = match fields.as_slice() {
+		["board", width, height, mines, seed] => {
+			let number = |value: &str| value.parse::<u64>().map_err(|_| format!("invalid board settings from {}", addr));
+			let (width, height, mines) = (number(width)? as usize, number(height)? as usize, number(mines)? as usize);
+			MSGame::new(width, height, mines, number(seed)?).map_err(|err| format!("invalid board from {}: {}", addr, err))?
+		},
+		_ => return Err(format!("unexpected reply from {}", addr)),
+	};
+	Ok((Race::new(stream)?, game))
+}
+
+impl Race {
+	fn new(stream: TcpStream) -> Result<Self, String> {
+		let _ = stream.set_nodelay(true);
+		let reader = stream.try_clone().map_err(|err| err.to_string())?;
+		let (sender, updates) = mpsc::channel();
+		thread::spawn(move || {
+			for line in BufReader::new(reader).lines() {
+				let Ok(line) = line else {
+					break;
+				};
+				let fields: Vec<_> = line.split_whitespace().collect();
+				let update = match fields.as_slice() {
+					["progress", cleared, total] => match (cleared.parse(), total.parse()) {
+						(Ok(cleared), Ok(total)) => Update::Progress(cleared, total),
+						_ => continue,
+					},
+					["won"] => Update::Won,
+					["died"] => Update::Died,
+					_ => continue,
+				};
+				if sender.send(update).is_err() {
+					return;
+				}
+			}
+			let _ = sender.send(Update::Disconnected);
+		});
+		Ok(Self {
+			stream: Some(stream),
+			updates,
+			opponent: Opponent::Playing(0, 1),
+			outcome: None,
+			sent_cleared: 0,
+		})
+	}
+
+	fn send(&mut self, line: &str) {
+		if let Some(stream) = &mut self.stream {
+			if writeln!(stream, "{}", line).is_err() {
+				self.stream = None;
+			}
+		}
+	}
+
+	// handles what the opponent sent since the last call, returns a message to show if something happened
+	pub fn poll(&mut self) -> Option<String> {
+		let mut message = None;
+		loop {
+			let update = match self.updates.try_recv() {
+				Ok(update) => update,
+				Err(TryRecvError::Empty) => break,
+				Err(TryRecvError::Disconnected) => Update::Disconnected,
+			};
+			match update {
+				Update::Progress(cleared, total) => {
+					self.opponent = Opponent::Playing(cleared, total.max(1));
+					continue;
+				},
+				Update::Won => {
+					self.opponent = Opponent::Won;
+					if self.outcome.is_none() {
+						self.outcome = Some(false);
+						message = Some(tr(Msg::OpponentWon).into());
+					}
+				},
+				Update::Died => {
+					self.opponent = Opponent::Died;
+					if self.outcome.is_none() {
+						self.outcome = Some(true);
+						message = Some(tr(Msg::OpponentDied).into());
+					}
+				},
+				Update::Disconnected => {
+					if !matches!(self.opponent, Opponent::Disconnected) {
+						self.opponent = Opponent::Disconnected;
+						self.stream = None;
+						// the end of their game is more interesting than them leaving after it
+						message = message.or(Some(tr(Msg::OpponentLeft).into()));
+					}
+					break;
+				},
+			}
+		}
+		message
+	}
+
+	pub fn send_progress(&mut self, game: &MSGame) {
+		let total = game.width * game.height - game.mines;
+		let cleared = total - game.safe_left;
+		if cleared != self.sent_cleared {
+			self.sent_cleared = cleared;
+			self.send(&format!("progress {} {}", cleared, total));
+		}
+	}
+
+	// tells the opponent how our game ended, quitting counts as dying
+	pub fn finish(&mut self, won: bool) {
+		self.send(if won { "won" } else { "died" });
+		if self.outcome.is_none() {
+			self.outcome = match self.opponent {
+				Opponent::Disconnected => None,
+				// being the last one alive wins
+				Opponent::Died => Some(true),
+				_ => Some(won),
+			};
+		}
+	}
+
+	pub fn status(&self) -> String {
+		match self.opponent {
+			Opponent::Playing(cleared, total) => trf(Msg::OpponentProgress, &[&(cleared * 100 / total)]),
+			Opponent::Won => tr(Msg::OpponentCleared).into(),
+			Opponent::Died => tr(Msg::OpponentDead).into(),
+			Opponent::Disconnected => tr(Msg::OpponentGone).into(),
+		}
+	}
+
+	// the line for the end screen
+	pub fn result(&self) -> Option<String> {
+		self.outcome.map(|won| tr(if won { Msg::RaceWon } else { Msg::RaceLost }).into())
+	}
+}
@@ -0,0 +1,171 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+// best times, arcade high scores and speed records, one line per difficulty:
+// <width>x<height>x<mines> <best time or -> <high score or -> <best 3BV/s or -> <best efficiency % or ->.
+// files from before the speed records have only the first three fields, those read as no record.
+// the high score of a marathon is the boards cleared in hundredths
+pub struct Records {
+	entries: Vec<Record>,
+}
+
+struct Record {
+	difficulty: String,
+	best_time: Option<u64>,
+	high_score: Option<u64>,
+	best_rate: Option<f64>,
+	best_efficiency: Option<u64>,
+}
+
+impl Records {
+	pub fn load() -> Self {
+		Self::read().unwrap_or(Self { entries: Vec::new() })
+	}
+
+	// fails if the file is missing or can't be read, for exporting
+	pub fn read() -> io::Result<Self> {
+		let path = records_path().ok_or(io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+		let text = fs::read_to_string(path)?;
+		let mut entries = Vec::new();
+		for line in text.lines() {
+			let mut fields = line.split_whitespace();
+			let (Some(difficulty), Some(time), Some(score)) = (fields.next(), fields.next(), fields.next()) else {
+				continue;
+			};
+			entries.push(Record {
+				difficulty: difficulty.to_string(),
+				best_time: time.parse().ok(),
+				high_score: score.parse().ok(),
+				best_rate: fields.next().and_then(|rate| rate.parse().ok()),
+				best_efficiency: fields.next().and_then(|efficiency| efficiency.parse().ok()),
+			});
+		}
+		Ok(Self { entries })
+	}
+
+	// written to a temporary file first and moved into place, a crash while writing keeps the old records
+	pub fn save(&self) {
+		let Some(path) = records_path() else {
+			return;
+		};
+		if let Some(dir) = path.parent() {
+			let _ = fs::create_dir_all(dir);
+		}
+		let mut text = String::new();
+		for record in &self.entries {
+			text.push_str(&format!(
+				"{} {} {} {} {}\n",
+				record.difficulty,
+				field(record.best_time),
+				field(record.high_score),
+				record.best_rate.map_or("-".into(), |rate| format!("{:.2}", rate)),
+				field(record.best_efficiency),
+			));
+		}
+		let temp = path.with_extension(format!("{}.tmp", process::id()));
+		if fs::write(&temp, text).is_ok() && fs::rename(&temp, &path).is_err() {
+			let _ = fs::remove_file(&temp);
+		}
+
+		fn field(value: Option<u64>) -> String {
+			value.map_or("-".into(), |v| v.to_string())
+		}
+	}
+
+	// every difficulty with a best time
+	pub fn best_times(&self) -> impl Iterator<Item = (&str, u64)> {
+		self.entries.iter().filter_map(|r| Some((r.difficulty.as_str(), r.best_time?)))
+	}
+
+	pub fn best_time(&self, difficulty: &str) -> Option<u64> {
+		self.find(difficulty)?.best_time
+	}
+
+	pub fn high_score(&self, difficulty: &str) -> Option<u64> {
+		self.find(difficulty)?.high_score
+	}
+
+	// returns true if this is a new best
+	pub fn submit_time(&mut self, difficulty: &str, time: u64) -> bool {
+		let record = self.find_or_insert(difficulty);
+		if record.best_time.is_none_or(|best| time < best) {
+			record.best_time = Some(time);
+			return true;
+		}
+		false
+	}
+
+	pub fn best_rate(&self, difficulty: &str) -> Option<f64> {
+		self.find(difficulty)?.best_rate
+	}
+
+	pub fn best_efficiency(&self, difficulty: &str) -> Option<u64> {
+		self.find(difficulty)?.best_efficiency
+	}
+
+	// returns true if this is a new best
+	pub fn submit_rate(&mut self, difficulty: &str, rate: f64) -> bool {
+		let record = self.find_or_insert(difficulty);
+		if record.best_rate.is_none_or(|best| rate > best) {
+			record.best_rate = Some(rate);
+			return true;
+		}
+		false
+	}
+
+	// returns true if this is a new best
+	pub fn submit_efficiency(&mut self, difficulty: &str, efficiency: u64) -> bool {
+		let record = self.find_or_insert(difficulty);
+		if record.best_efficiency.is_none_or(|best| efficiency > best) {
+			record.best_efficiency = Some(efficiency);
+			return true;
+		}
+		false
+	}
+
+	// returns true if this is a new best
+	pub fn submit_score(&mut self, difficulty: &str, score: u64) -> bool {
+		let record = self.find_or_insert(difficulty);
+		if record.high_score.is_none_or(|best| score > best) {
+			record.high_score = Some(score);
+			return true;
+		}
+		false
+	}
+
+	fn find(&self, difficulty: &str) -> Option<&Record> {
+		self.entries.iter().find(|r| r.difficulty == difficulty)
+	}
+
+	fn find_or_insert(&mut self, difficulty: &str) -> &mut Record {
+		let index = match self.entries.iter().position(|r| r.difficulty == difficulty) {
+			Some(i) => i,
+			None => {
+				self.entries.push(Record {
+					difficulty: difficulty.to_string(),
+					best_time: None,
+					high_score: None,
+					best_rate: None,
+					best_efficiency: None,
+				});
+				self.entries.len() - 1
+			}
+		};
+		&mut self.entries[index]
+	}
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+	let base = match env::var_os("XDG_DATA_HOME") {
+		Some(dir) => PathBuf::from(dir),
+		None => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+	};
+	Some(base.join("minesweeper-rs"))
+}
+
+fn records_path() -> Option<PathBuf> {
+	Some(data_dir()?.join("records"))
+}
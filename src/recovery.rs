@@ -0,0 +1,364 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::panic;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use console::Term;
+
+use crate::board::Board;
+use crate::date;
+use crate::json;
+use crate::lang::{tr, trf, Msg};
+use crate::options::Options;
+use crate::records::data_dir;
+use crate::{MSGame, TileContents, TileVis};
+
+// the game as it was after the last move, only turned into json if the game panics.
+// None when no game is going on
+static SNAPSHOT: Mutex<Option<Snapshot>> = Mutex::new(None);
+// set by `install`, the modes that play several boards in a row can't be resumed and keep no snapshot
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+// a copy of what the recovery file needs, cheap enough to take after every move
+struct Snapshot {
+	args: Vec<String>,
+	seed: Option<u64>,
+	elapsed: Duration,
+	width: usize,
+	cursor: (usize, usize),
+	partner: Option<(usize, usize)>,
+	mines: usize,
+	flags: usize,
+	moves: usize,
+	clicks: usize,
+	board: Board,
+}
+
+// a crashed game is saved as json in the data directory, to be picked up by the next start:
+//
+//   {"version": 1, "options": ["--width", "3", ...], "seed": "1234", "elapsed": 75.2,
+//    "cursor": [2, 0], "partner": null, "mines": 1, "flags": 1, "moves": 4, "clicks": 3,
+//    "contents": ["1*1", "111"], "visibility": ["OFO", "OO#"]}
+//
+// contents have `*` for a mine, `o` for an anti-mine and the count of a safe tile in base 36.
+// visibility has `#` for hidden, `O` for opened, `F` for a flag and `A` for an anti-flag
+pub struct Recovery {
+	path: PathBuf,
+	args: Vec<String>,
+	seed: Option<u64>,
+	pub elapsed: Duration,
+	cursor: (usize, usize),
+	partner: Option<(usize, usize)>,
+	mines: usize,
+	flags: usize,
+	moves: usize,
+	clicks: usize,
+	contents: Vec<char>,
+	visibility: Vec<char>,
+}
+
+// saves the last snapshot when the game panics, after the message of the hooks set up before it
+pub fn install() {
+	INSTALLED.store(true, Ordering::Relaxed);
+	let previous = panic::take_hook();
+	panic::set_hook(Box::new(move |info| {
+		previous(info);
+		// the lock is poisoned if the panic came from inside it, then there's nothing safe to save
+		let Ok(snapshot) = SNAPSHOT.try_lock() else {
+			return;
+		};
+		let (Some(snapshot), Some(dir)) = (snapshot.as_ref(), data_dir()) else {
+			return;
+		};
+		let path = dir.join(format!("recovery-{}.json", date::now()));
+		if fs::create_dir_all(&dir).is_ok() && fs::write(&path, snapshot.to_json()).is_ok() {
+			eprintln!("{}", trf(Msg::RecoverySaved, &[&path.display()]));
+		}
+	}));
+}
+
+// keeps the game as it is now for the panic hook
+pub fn update(game: &MSGame, options: &Options, elapsed: Duration) {
+	if !INSTALLED.load(Ordering::Relaxed) {
+		return;
+	}
+	let Ok(mut current) = SNAPSHOT.lock() else {
+		return;
+	};
+	// the options stay the same until the game is over
+	let args = match current.take() {
+		Some(snapshot) => snapshot.args,
+		None => options.game_args(),
+	};
+	*current = Some(Snapshot::new(game, args, elapsed));
+}
+
+// the game is over, a panic from here on has nothing to save
+pub fn clear() {
+	if let Ok(mut current) = SNAPSHOT.lock() {
+		*current = None;
+	}
+}
+
+impl Snapshot {
+	fn new(game: &MSGame, args: Vec<String>, elapsed: Duration) -> Self {
+		Self {
+			args,
+			seed: game.seed,
+			elapsed,
+			width: game.width,
+			cursor: (game.cursor_x, game.cursor_y),
+			partner: game.partner,
+			mines: game.mines,
+			flags: game.flags,
+			moves: game.moves,
+			clicks: game.clicks,
+			board: game.board.clone(),
+		}
+	}
+
+	fn to_json(&self) -> String {
+		let quote = |text: &str| format!("\"{}\"", json::escape(text));
+		let pos = |pos: Option<(usize, usize)>| pos.map_or("null".into(), |(x, y)| format!("[{}, {}]", x, y));
+		let args: Vec<_> = self.args.iter().map(|arg| quote(arg)).collect();
+		let (mut contents, mut visibility) = (Vec::new(), Vec::new());
+		for row in 0..self.board.len() / self.width {
+			let (mut row_contents, mut row_visibility) = (String::new(), String::new());
+			for i in row * self.width..(row + 1) * self.width {
+				let tile = self.board.get(i);
+				row_contents.push(match tile.contents {
+					TileContents::Mine => '*',
+					TileContents::AntiMine => 'o',
+					TileContents::Safe(n) => char::from_digit(n as u32, 36).unwrap_or('0'),
+				});
+				row_visibility.push(match tile.visibility {
+					TileVis::Hidden => '#',
+					TileVis::Open => 'O',
+					TileVis::Flag => 'F',
+					TileVis::AntiFlag => 'A',
+				});
+			}
+			contents.push(quote(&row_contents));
+			visibility.push(quote(&row_visibility));
+		}
+		let mut text = String::new();
+		writeln!(
+			text,
+			"{{\"version\": 1, \"options\": [{}], \"seed\": {}, \"elapsed\": {:.3}, \"cursor\": {}, \"partner\": {}, \
+			\"mines\": {}, \"flags\": {}, \"moves\": {}, \"clicks\": {}, \"contents\": [{}], \"visibility\": [{}]}}",
+			args.join(", "),
+			self.seed.map_or("null".into(), |seed| quote(&seed.to_string())),
+			self.elapsed.as_secs_f64(),
+			pos(Some(self.cursor)),
+			pos(self.partner),
+			self.mines,
+			self.flags,
+			self.moves,
+			self.clicks,
+			contents.join(", "),
+			visibility.join(", "),
+		)
+		.unwrap();
+		text
+	}
+}
+
+// the newest saved game, if there is one
+fn find() -> Option<PathBuf> {
+	let mut found: Vec<_> = fs::read_dir(data_dir()?)
+		.ok()?
+		.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+		.filter(|path| {
+			let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+			name.starts_with("recovery-") && name.ends_with(".json")
+		})
+		.collect();
+	found.sort();
+	found.pop()
+}
+
+// asks whether to resume a game saved by a crash and sets up the options for it.
+// the file is deleted either way, so it is only offered once
+pub fn offer(options: &mut Options) -> Option<Recovery> {
+	if !io::stdin().is_terminal() {
+		return None;
+	}
+	let path = find()?;
+	let recovery = load(path.clone());
+	let _ = fs::remove_file(&path);
+	let recovery = match recovery {
+		Ok(recovery) => recovery,
+		Err(err) => {
+			eprintln!("{}", err);
+			return None;
+		},
+	};
+	let term = Term::stdout();
+	term.write_line(&trf(Msg::RecoveryFound, &[&recovery.path.display()])).ok()?;
+	if !matches!(term.read_char(), Ok('y' | 'Y' | 'j' | 'J')) {
+		return None;
+	}
+	if let Err(err) = options.set_game(&recovery.args) {
+		eprintln!("{}: {}", recovery.path.display(), err);
+		return None;
+	}
+	options.seed = recovery.seed;
+	Some(recovery)
+}
+
+fn load(path: PathBuf) -> Result<Recovery, String> {
+	let text = fs::read_to_string(&path).map_err(|err| format!("could not read '{}': {}", path.display(), err))?;
+	let bad = || format!("'{}' {}", path.display(), tr(Msg::RecoveryInvalid));
+	let state = json::parse(&text).map_err(|_| bad())?;
+	if state.get("version") != Some(&json::Value::Number(1.0)) {
+		return Err(bad());
+	}
+	let field = |name: &str| state.get(name).and_then(json::Value::as_usize).ok_or_else(bad);
+	let rows = |name: &str| -> Result<Vec<char>, String> {
+		let rows = state.get(name).and_then(json::Value::as_array).ok_or_else(bad)?;
+		let mut cells = Vec::new();
+		for row in rows {
+			cells.extend(row.as_str().ok_or_else(bad)?.chars());
+		}
+		Ok(cells)
+	};
+	let args = state.get("options").and_then(json::Value::as_array).ok_or_else(bad)?;
+	let args = args.iter().map(|arg| arg.as_str().map(String::from)).collect::<Option<_>>().ok_or_else(bad)?;
+	let elapsed = match state.get("elapsed") {
+		Some(json::Value::Number(secs)) if *secs >= 0.0 => Duration::from_secs_f64(*secs),
+		_ => return Err(bad()),
+	};
+	// seeds don't fit in a json number, so they are strings
+	let seed = match state.get("seed") {
+		Some(json::Value::Null) => None,
+		Some(json::Value::String(seed)) => Some(seed.parse().map_err(|_| bad())?),
+		_ => return Err(bad()),
+	};
+	Ok(Recovery {
+		args,
+		seed,
+		elapsed,
+		cursor: state.get("cursor").and_then(json::Value::as_pos).ok_or_else(bad)?,
+		partner: state.get("partner").and_then(json::Value::as_pos),
+		mines: field("mines")?,
+		flags: field("flags")?,
+		moves: field("moves")?,
+		clicks: field("clicks")?,
+		contents: rows("contents")?,
+		visibility: rows("visibility")?,
+		path,
+	})
+}
+
+impl Recovery {
+	// puts the saved tiles on a game made from the same options
+	pub fn apply(&self, game: &mut MSGame) -> Result<(), String> {
+		let bad = || format!("'{}' {}", self.path.display(), tr(Msg::RecoveryInvalid));
+		let tiles = game.width * game.height;
+		if self.contents.len() != tiles || self.visibility.len() != tiles || !game.in_bounds(self.cursor.0, self.cursor.1) {
+			return Err(bad());
+		}
+		for i in 0..tiles {
+			let mut tile = game.board.get(i);
+			tile.contents = match self.contents[i] {
+				'*' => TileContents::Mine,
+				'o' => TileContents::AntiMine,
+				c => TileContents::Safe(c.to_digit(36).ok_or_else(bad)? as u8),
+			};
+			tile.visibility = match self.visibility[i] {
+				'#' => TileVis::Hidden,
+				'O' => TileVis::Open,
+				'F' => TileVis::Flag,
+				'A' => TileVis::AntiFlag,
+				_ => return Err(bad()),
+			};
+			game.board.set(i, tile);
+		}
+		(game.cursor_x, game.cursor_y) = self.cursor;
+		if game.partner.is_some() {
+			game.partner = self.partner.filter(|&(x, y)| game.in_bounds(x, y));
+		}
+		game.mines = self.mines;
+		game.flags = self.flags;
+		game.moves = self.moves;
+		game.clicks = self.clicks;
+		game.safe_left = game.safe_hidden();
+		// the rest is worked out from the tiles. when the tiles were opened is lost, they count as opened at the start
+		game.exploded = (0..tiles)
+			.find(|&i| game.board.is_mine(i) && matches!(game.board.get(i).visibility, TileVis::Open))
+			.map(|i| (i % game.width, i / game.width));
+		for i in 0..tiles {
+			let opened = matches!(game.board.get(i).visibility, TileVis::Open) && !game.is_void(i % game.width, i / game.width);
+			game.opened_at[i] = opened as u32;
+		}
+		if game.line_totals.is_some() {
+			game.count_lines();
+		}
+		if game.forced.is_some() {
+			game.forced = Some(game.solver.deduce(&game.view()));
+		}
+		game.conflicts.clear();
+		game.frontier_cycle = None;
+		game.reveal_order.clear();
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::action::Action;
+	use crate::boardfile;
+
+	fn save_and_load(game: &MSGame, name: &str) -> Recovery {
+		let snapshot = Snapshot::new(game, vec!["--width".into(), game.width.to_string()], Duration::from_millis(75_200));
+		let path = std::env::temp_dir().join(format!("minesweeper-recovery-{}-{}.json", name, std::process::id()));
+		fs::write(&path, snapshot.to_json()).unwrap();
+		let recovery = load(path.clone()).unwrap();
+		fs::remove_file(path).unwrap();
+		recovery
+	}
+
+	#[test]
+	fn a_saved_game_resumes_where_it_was() {
+		let text = "*...\n....\n...*\n";
+		let mut game = boardfile::parse("test", text).unwrap();
+		game.line_totals = Some((Vec::new(), Vec::new()));
+		for action in [Action::BoardEnd, Action::Flag, Action::RowStart, Action::Open] {
+			game.act(action);
+		}
+		let recovery = save_and_load(&game, "resume");
+		assert_eq!(recovery.args, ["--width", "4"]);
+		assert_eq!(recovery.elapsed, Duration::from_millis(75_200));
+
+		let mut resumed = boardfile::parse("test", text).unwrap();
+		resumed.line_totals = Some((Vec::new(), Vec::new()));
+		resumed.forced = Some(Default::default());
+		recovery.apply(&mut resumed).unwrap();
+		assert_eq!(boardfile::to_text(&resumed), boardfile::to_text(&game));
+		for i in 0..game.board.len() {
+			assert_eq!(std::mem::discriminant(&resumed.board.get(i).visibility), std::mem::discriminant(&game.board.get(i).visibility), "tile {}", i);
+			assert_eq!(resumed.opened_at[i] != 0, game.opened_at[i] != 0, "tile {}", i);
+		}
+		assert_eq!((resumed.cursor_x, resumed.cursor_y), (0, 2));
+		assert_eq!((resumed.flags, resumed.moves, resumed.clicks, resumed.safe_left), (game.flags, game.moves, game.clicks, game.safe_left));
+		assert!(resumed.exploded.is_none());
+		assert_eq!(resumed.line_totals, Some((vec![1, 0, 1], vec![1, 0, 0, 1])));
+		let forced = resumed.forced.as_ref().unwrap();
+		let fresh = resumed.solver.deduce(&resumed.view());
+		assert_eq!((&forced.safe, &forced.mines), (&fresh.safe, &fresh.mines));
+	}
+
+	#[test]
+	fn a_game_saved_on_the_losing_move_keeps_the_mine() {
+		let mut game = boardfile::parse("test", "*...\n....\n...*\n").unwrap();
+		game.act(Action::Open);
+		let mut resumed = boardfile::parse("test", "*...\n....\n...*\n").unwrap();
+		save_and_load(&game, "loss").apply(&mut resumed).unwrap();
+		assert_eq!(resumed.exploded, Some((0, 0)));
+	}
+}
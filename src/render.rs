@@ -0,0 +1,896 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::env;
+use std::fmt::{self, Write};
+use std::io::{self, IsTerminal};
+
+use console::Term;
+use console::style;
+use console::StyledObject;
+use console::measure_text_width;
+use console::truncate_str;
+
+use crate::endscreen;
+use crate::glyphs::Glyphs;
+use crate::{FlagWin, Lattice, MSGame, Tile, TileContents, TileVis};
+use crate::lang::{tr, trf, Msg};
+use crate::theme::Theme;
+
+pub trait Renderer {
+	// returns the number of lines drawn
+	fn draw(&self, game: &MSGame, view: &View, status: &Status, stdout: &Term) -> usize;
+}
+
+// display settings that can be toggled while playing
+#[derive(Clone, Copy, Default)]
+pub struct View {
+	// use the braille renderer
+	pub compact: bool,
+	pub minimap: bool,
+	// dim numbers with as many flags around them as their value
+	pub dim_satisfied: bool,
+	// tint the tiles related to the one under the cursor
+	pub highlight: bool,
+	// shade the hidden tiles next to an opened number
+	pub frontier: bool,
+	// live 3BV/s and efficiency in the status bar
+	pub rates: bool,
+	// draw the rows of the board as columns, for terminals taller than they are wide
+	pub transpose: bool,
+}
+
+impl View {
+	// triangles only line up in rows, so those boards are never transposed
+	pub fn transposes(&self, game: &MSGame) -> bool {
+		self.transpose && game.lattice == Lattice::Square
+	}
+}
+
+// whether a board reads better transposed on a terminal of `size`: the terminal is taller than it is
+// wide (a cell is about twice as tall as it is wide) and the board is the other way round
+pub fn prefer_transposed((rows, cols): (usize, usize), width: usize, height: usize) -> bool {
+	rows * 2 > cols && width > height
+}
+
+// the board the way it is drawn, mirrored along its diagonal when transposed. positions on the screen are
+// turned into positions on the board as they are drawn, so the numbers of the liar variant stay as they were shown
+struct Drawn<'a> {
+	game: &'a MSGame,
+	transposed: bool,
+}
+
+impl<'a> Drawn<'a> {
+	fn new(game: &'a MSGame, view: &View) -> Self {
+		Self { game, transposed: view.transposes(game) }
+	}
+
+	// the same swap goes from the screen to the board and back
+	fn flip(&self, (a, b): (usize, usize)) -> (usize, usize) {
+		match self.transposed {
+			true => (b, a),
+			false => (a, b),
+		}
+	}
+
+	fn width(&self) -> usize {
+		if self.transposed { self.game.height } else { self.game.width }
+	}
+
+	fn height(&self) -> usize {
+		if self.transposed { self.game.width } else { self.game.height }
+	}
+
+	fn cursor(&self) -> (usize, usize) {
+		self.flip((self.game.cursor_x, self.game.cursor_y))
+	}
+
+	fn partner(&self) -> Option<(usize, usize)> {
+		self.game.partner.map(|partner| self.flip(partner))
+	}
+
+	// the totals of the rows and columns on the screen
+	fn line_totals(&self) -> Option<(&'a Vec<usize>, &'a Vec<usize>)> {
+		let (rows, cols) = self.game.line_totals.as_ref()?;
+		Some(if self.transposed { (cols, rows) } else { (rows, cols) })
+	}
+}
+
+// the row and column hints, dimmed once the line has as many flags as it has mines
+fn line_hint(game: &MSGame, total: usize, tiles: impl Iterator<Item = (usize, usize)>, text: String) -> StyledObject<String> {
+	let flags = tiles.filter(|&(x, y)| matches!(game.get(x, y).visibility, TileVis::Flag)).count();
+	match flags == total {
+		true => style(text).dim(),
+		false => style(text),
+	}
+}
+
+// state outside the game itself that is shown in the status bar
+#[derive(Default)]
+pub struct Status {
+	pub elapsed: u64,
+	// replaces the key hints when set
+	pub message: Option<String>,
+	// extra counters shown before the timer
+	pub items: Vec<String>,
+	// draw the board inverted
+	pub flash: bool,
+	// during the reveal animation, the last flood fill layer to show as opened
+	pub reveal_layer: Option<usize>,
+	// show the last flood fill through the fog
+	pub fog_flash: bool,
+	// show the whole board through the fog
+	pub fog_lifted: bool,
+	// draw the status bar inverted, after a mine was added
+	pub counter_flash: bool,
+	// the timer is paused after a while without keys, the board is drawn dim
+	pub idle: bool,
+	// color opened tiles by when they were opened, on the end screen
+	pub heatmap: bool,
+	// where the cursor of the best run on this board was at this time
+	pub ghost: Option<(usize, usize)>,
+}
+
+// tiles the reveal animation hasn't reached yet
+fn unrevealed(game: &MSGame, status: &Status) -> HashSet<(usize, usize)> {
+	match status.reveal_layer {
+		Some(shown) => game.reveal_order
+			.iter()
+			.filter(|&&(_, _, layer)| layer > shown)
+			.map(|&(x, y, _)| (x, y))
+			.collect(),
+		None => HashSet::new(),
+	}
+}
+
+// tiles covered by fog are drawn blank
+fn fogged(game: &MSGame, status: &Status, fog: Option<usize>, x: usize, y: usize) -> bool {
+	let Some(radius) = fog else {
+		return false;
+	};
+	if status.fog_lifted || status.fog_flash && game.reveal_order.iter().any(|&(rx, ry, _)| (rx, ry) == (x, y)) {
+		return false;
+	}
+	game.fogged(radius, x, y)
+}
+
+// plain output for --plain, when the output isn't a terminal and on terminals that can't do more
+pub fn plain_output(plain: bool) -> bool {
+	is_plain(plain, io::stdout().is_terminal(), env::var("TERM").ok().as_deref())
+}
+
+fn is_plain(plain: bool, terminal: bool, term: Option<&str>) -> bool {
+	plain || !terminal || term == Some("dumb")
+}
+
+// lines below the board used by the status bar
+const STATUS_LINES: usize = 2;
+
+// frames are built up as a string and written in one go to avoid flicker.
+// plain output keeps the previous frames and just adds a blank line
+fn write_frame(stdout: &Term, plain: bool, frame: &str) {
+	if plain {
+		stdout.write_line("").unwrap();
+	} else {
+		stdout.clear_screen().unwrap();
+	}
+	stdout.write_str(frame).unwrap();
+	stdout.flush().unwrap();
+}
+
+// (rows, columns) available for drawing, None for plain output where nothing has to fit
+fn term_size(stdout: &Term, plain: bool) -> Option<(usize, usize)> {
+	let (rows, cols) = stdout.size();
+	(!plain).then_some((rows as usize, cols as usize))
+}
+
+// adds the board to the frame, moved to the middle of the terminal when there is a size,
+// with `status_lines` left for the status bar below it. returns the number of blank lines added above it
+fn push_centered(out: &mut String, board: &str, size: Option<(usize, usize)>, status_lines: usize) -> usize {
+	let Some((term_rows, term_cols)) = size else {
+		out.push_str(board);
+		return 0;
+	};
+	let width = board.lines().map(measure_text_width).max().unwrap_or(0);
+	let left = " ".repeat(term_cols.saturating_sub(width) / 2);
+	let top = term_rows.saturating_sub(board.lines().count() + status_lines) / 2;
+	out.push_str(&"\n".repeat(top));
+	for line in board.lines() {
+		out.push_str(&left);
+		out.push_str(line);
+		out.push('\n');
+	}
+	top
+}
+
+// the counters on the right of the status bar
+fn status_counters(game: &MSGame, view: &View, status: &Status) -> String {
+	let mut right = status.items.clone();
+	right.push(format!("{}s", status.elapsed));
+	if view.rates {
+		let (done, _) = game.three_bv_progress();
+		let rate = format!("{:.2}", done as f64 / status.elapsed.max(1) as f64);
+		let apm = game.moves as u64 * 60 / status.elapsed.max(1);
+		right.push(trf(Msg::LiveRates, &[&rate, &endscreen::efficiency(done, game.moves), &apm]));
+	}
+	if game.liar.is_some() {
+		right.push(tr(Msg::LiarMode).into());
+	}
+	if game.anti {
+		right.push(tr(Msg::AntiMode).into());
+	}
+	match game.flag_win {
+		Some(FlagWin::Also) => right.push(tr(Msg::WinByFlags).into()),
+		Some(FlagWin::Only) => right.push(tr(Msg::WinByFlagsOnly).into()),
+		None => (),
+	}
+	if let Some(count) = game.forced_moves() {
+		right.push(trf(Msg::ForcedMoves, &[&count]));
+	}
+	if let Some(budget) = game.click_budget {
+		right.push(trf(Msg::Clicks, &[&game.clicks, &budget]));
+	}
+	if game.blind {
+		right.push(trf(Msg::SafeLeft, &[&"?"]));
+		right.push(trf(Msg::BlindCounter, &[&game.flags]));
+	} else {
+		right.push(trf(Msg::SafeLeft, &[&game.safe_left]));
+		right.push(trf(Msg::MineCounter, &[&(game.mines as isize - game.flags as isize), &game.mines]));
+	}
+	right.join("  ")
+}
+
+// a message too long to fit next to the counters, like a row read out, broken at the spaces into
+// lines of its own above them instead of being cut off. the key hints are only ever cut
+fn wrapped_message(status: &Status, right: &str, glyphs: &Glyphs, width: Option<usize>) -> Vec<String> {
+	let (Some(message), Some(width)) = (&status.message, width) else {
+		return Vec::new();
+	};
+	let message = glyphs.text(message);
+	if width == 0 || message.chars().count() < width.saturating_sub(right.chars().count()) {
+		return Vec::new();
+	}
+	let mut lines = vec![String::new()];
+	for word in message.split(' ') {
+		let line = lines.last_mut().unwrap();
+		if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+			lines.push(String::new());
+		}
+		let line = lines.last_mut().unwrap();
+		if !line.is_empty() {
+			line.push(' ');
+		}
+		line.push_str(word);
+	}
+	// only a single word longer than the width is cut
+	for line in lines.iter_mut().filter(|line| line.chars().count() > width) {
+		*line = truncate_str(line, width, glyphs.ellipsis).into_owned();
+	}
+	lines
+}
+
+// lines below the board used by the status bar and a message wrapped above it
+fn status_lines(game: &MSGame, view: &View, status: &Status, glyphs: &Glyphs, width: Option<usize>) -> usize {
+	STATUS_LINES + wrapped_message(status, &status_counters(game, view, status), glyphs, width).len()
+}
+
+// one line with the key hints (or a message) on the left and the counters on the right,
+// filling the whole width if there is one
+fn draw_status_bar(out: &mut String, game: &MSGame, view: &View, status: &Status, theme: &Theme, glyphs: &Glyphs, width: Option<usize>) {
+	let right = status_counters(game, view, status);
+	let wrapped = wrapped_message(status, &right, glyphs, width);
+	let message = match wrapped.is_empty() {
+		true => status.message.as_deref().unwrap_or(tr(Msg::KeyHints)),
+		false => "",
+	};
+	let left = glyphs.text(message);
+
+	let (left, right, gap) = match width {
+		Some(width) => {
+			let right = truncate_str(&right, width, glyphs.ellipsis);
+			let left_width = width.saturating_sub(right.chars().count() + 1);
+			let left = truncate_str(&left, left_width, glyphs.ellipsis);
+			let gap = width.saturating_sub(left.chars().count() + right.chars().count());
+			(left, right, gap)
+		},
+		None => (left.into_owned().into(), right.into(), 2),
+	};
+	out.push('\n');
+	for line in wrapped {
+		out.push_str(&theme.status.apply_to(line).to_string());
+		out.push('\n');
+	}
+	let style = match status.counter_flash {
+		true => theme.status.clone().reverse(),
+		false => theme.status.clone(),
+	};
+	out.push_str(&style.apply_to(format!("{}{}{}", left, " ".repeat(gap), right)).to_string());
+	out.push('\n');
+}
+
+// one cell per tile, scrolled to keep the cursor in view when the board doesn't fit.
+// normal cells are one column with a gap column between them that holds the cursor brackets,
+// wide cells are two columns with no gaps and the cursor is shown by inverting the cell
+pub struct GridRenderer {
+	pub theme: Theme,
+	pub glyphs: Glyphs,
+	// no screen clearing or scrolling
+	pub plain: bool,
+	pub wide: bool,
+	// alternate the background of opened zero tiles
+	pub checkerboard: bool,
+	// separator lines every GRID_SPACING rows and columns
+	pub grid_lines: bool,
+	// put the board in the middle of the terminal
+	pub center: bool,
+	// radius around the cursor that isn't covered by fog
+	pub fog: Option<usize>,
+	pub minimap: MinimapCache,
+}
+
+const GRID_SPACING: usize = 5;
+const CHECKER_SHADE: u8 = 236;
+const HIGHLIGHT_SHADE: u8 = 238;
+// from the first opened tiles to the last, blue through green and yellow to red
+const HEAT_RAMP: [u8; 8] = [27, 33, 37, 41, 148, 220, 208, 196];
+
+impl Renderer for GridRenderer {
+	fn draw(&self, game: &MSGame, view: &View, status: &Status, stdout: &Term) -> usize {
+		let (out, lines) = self.frame(game, view, status, term_size(stdout, self.plain));
+		write_frame(stdout, self.plain, &out);
+		lines
+	}
+}
+
+impl GridRenderer {
+	// the whole frame for a terminal of `size`, and the number of lines in it
+	pub fn frame(&self, game: &MSGame, view: &View, status: &Status, size: Option<(usize, usize)>) -> (String, usize) {
+		let mut board = String::new();
+		let drawn = Drawn::new(game, view);
+
+		let triangles = game.lattice == Lattice::Triangle;
+		let (cell_w, gap_w) = if self.wide || game.wide_numbers() { (2, 0) } else { (1, 1) };
+		let (term_rows, term_cols) = size.unwrap_or((usize::MAX, usize::MAX));
+		let mut view_w = term_cols.saturating_sub(gap_w) / (cell_w + gap_w);
+		let status_lines = status_lines(game, view, status, &self.glyphs, size.map(|(_, cols)| cols));
+		let mut view_h = term_rows.saturating_sub(status_lines);
+		// the totals of the rows go to their right, a space and the number. the totals of the columns
+		// go below them with one digit per line, so they fit under cells of any width
+		let (hint_w, hint_lines) = match drawn.line_totals() {
+			Some((rows, cols)) => {
+				let digits = |totals: &Vec<usize>| totals.iter().max().map_or(1, |max| max.to_string().len());
+				(1 + digits(rows), digits(cols))
+			},
+			None => (0, 0),
+		};
+		view_w = view_w.saturating_sub(hint_w.div_ceil(cell_w + gap_w));
+		view_h = view_h.saturating_sub(hint_lines);
+		if self.grid_lines && size.is_some() {
+			// one extra column/row for every GRID_SPACING cells
+			view_w = view_w * GRID_SPACING * (cell_w + gap_w) / (GRID_SPACING * (cell_w + gap_w) + 1);
+			view_h = view_h * GRID_SPACING / (GRID_SPACING + 1);
+		}
+		let mut minimap = Minimap::new(&drawn, view_h.min(MINIMAP_ROWS));
+		if view.minimap && (view_w < drawn.width() || view_h < drawn.height()) {
+			// the map goes to the right of the board, with a column of space
+			view_w = view_w.saturating_sub((minimap.cols + 1).div_ceil(cell_w + gap_w));
+		} else {
+			minimap.rows = 0;
+		}
+		let (cursor_col, cursor_row) = drawn.cursor();
+		let (first_col, cols) = view_range(cursor_col, drawn.width(), view_w);
+		let (first_row, rows) = view_range(cursor_row, drawn.height(), view_h);
+		let minimap = minimap.lines(&drawn, &self.minimap, &self.theme, &self.glyphs, (first_col, cols), (first_row, rows));
+		let unrevealed = unrevealed(game, status);
+		let highlighted = if view.highlight { highlighted(game) } else { Vec::new() };
+		let mut lines = status_lines;
+		let is_separator = |i: usize, last: usize| self.grid_lines && i % GRID_SPACING == GRID_SPACING - 1 && i != last;
+
+		for row in first_row..(first_row + rows) {
+			if gap_w > 0 {
+				write!(board, "{}", cell_gap(&self.theme, &drawn, status.ghost, first_col.wrapping_sub(1), row)).unwrap();
+			}
+
+			for col in first_col..(first_col + cols) {
+				let (x, y) = drawn.flip((col, row));
+				let mut tile = game.shown(x, y);
+				if !unrevealed.is_empty() && unrevealed.contains(&(x, y)) {
+					tile.visibility = TileVis::Hidden;
+				}
+				let fogged = fogged(game, status, self.fog, x, y);
+				let mut cell = if fogged {
+					style(Glyph::Text(" ", cell_w))
+				} else if triangles && matches!(tile.visibility, TileVis::Hidden) {
+					let glyph = if game.points_up(x, y) { self.glyphs.up_triangle } else { self.glyphs.down_triangle };
+					self.theme.hidden.apply_to(Glyph::Char(glyph, 2))
+				} else if let (true, TileVis::Open, TileContents::Safe(1..)) = (game.anti, tile.visibility, tile.contents) {
+					number_glyph(game.number(x, y), cell_w, &self.theme)
+				} else {
+					tile.styled(cell_w, &self.theme)
+				};
+				if self.checkerboard && (x + y) % 2 == 0 && !game.is_void(x, y) {
+					if let (TileVis::Open, TileContents::Safe(0)) = (tile.visibility, tile.contents) {
+						cell = cell.on_color256(CHECKER_SHADE);
+					}
+				}
+				if view.dim_satisfied && game.satisfied(x, y) && matches!(tile.visibility, TileVis::Open) {
+					cell = cell.dim();
+				}
+				// tiles still being revealed by the animation count as hidden, but not as frontier
+				if view.frontier && !fogged && matches!(tile.visibility, TileVis::Hidden) && game.on_frontier(x, y) {
+					cell = cell.on_color256(self.theme.frontier);
+				}
+				if highlighted.contains(&(x, y)) {
+					cell = cell.on_color256(HIGHLIGHT_SHADE);
+				}
+				if game.conflicts.contains(&(x, y)) {
+					cell = cell.on_red();
+				}
+				if status.heatmap && matches!(tile.visibility, TileVis::Open) && !game.is_void(x, y) {
+					if let Some(shade) = heat(game, x, y) {
+						cell = cell.black().on_color256(shade);
+					}
+				}
+				if status.flash || gap_w == 0 && (x, y) == (game.cursor_x, game.cursor_y) {
+					cell = cell.reverse();
+				} else if gap_w == 0 && game.partner == Some((x, y)) {
+					cell = cell.on_blue();
+				} else if gap_w == 0 && status.ghost == Some((x, y)) {
+					cell = cell.underlined();
+				}
+				if status.idle {
+					cell = cell.dim();
+				}
+				write!(board, "{}", cell).unwrap();
+				if gap_w > 0 {
+					write!(board, "{}", cell_gap(&self.theme, &drawn, status.ghost, col, row)).unwrap();
+				}
+				if is_separator(col, first_col + cols - 1) {
+					write!(board, "{}", style(self.glyphs.vertical).dim()).unwrap();
+				}
+			}
+			if let Some((totals, _)) = drawn.line_totals() {
+				let text = format!(" {:<1$}", totals[row], hint_w - 1);
+				let tiles = (0..drawn.width()).map(|col| drawn.flip((col, row)));
+				write!(board, "{}", line_hint(game, totals[row], tiles, text)).unwrap();
+			}
+			if let Some(line) = minimap.get(row - first_row) {
+				board.push(' ');
+				board.push_str(line);
+			}
+			board.push('\n');
+			lines += 1;
+
+			if is_separator(row, first_row + rows - 1) {
+				let horizontal = self.glyphs.horizontal.to_string();
+				let mut line = horizontal.repeat(gap_w);
+				for col in first_col..(first_col + cols) {
+					line.push_str(&horizontal.repeat(cell_w + gap_w));
+					if is_separator(col, first_col + cols - 1) {
+						line.push(self.glyphs.cross);
+					}
+				}
+				board.push_str(&style(line).dim().to_string());
+				board.push('\n');
+				lines += 1;
+			}
+		}
+		if let Some((_, totals)) = drawn.line_totals() {
+			for digit in 0..hint_lines {
+				board.push_str(&" ".repeat(gap_w));
+				for (col, &total) in totals.iter().enumerate().skip(first_col).take(cols) {
+					let number = format!("{:>1$}", total, hint_lines);
+					let text = format!("{:>1$}", &number[digit..digit + 1], cell_w);
+					let tiles = (0..drawn.height()).map(|row| drawn.flip((col, row)));
+					write!(board, "{}", line_hint(game, total, tiles, text)).unwrap();
+					board.push_str(&" ".repeat(gap_w));
+					if is_separator(col, first_col + cols - 1) {
+						board.push(' ');
+					}
+				}
+				board.push('\n');
+				lines += 1;
+			}
+		}
+		let mut out = String::new();
+		lines += push_centered(&mut out, &board, size.filter(|_| self.center), status_lines);
+		draw_status_bar(&mut out, game, view, status, &self.theme, &self.glyphs, size.map(|(_, cols)| cols));
+		(out, lines)
+	}
+}
+
+// on an opened number, the tiles around it. on an unopened tile, the numbers next to it
+fn highlighted(game: &MSGame) -> Vec<(usize, usize)> {
+	let (x, y) = (game.cursor_x, game.cursor_y);
+	let tile = game.shown(x, y);
+	match (tile.visibility, tile.contents) {
+		(TileVis::Open, TileContents::Safe(1..)) => game.neighbors(x, y).collect(),
+		(TileVis::Open, _) => Vec::new(),
+		_ => game.surroundings(x, y).numbers.into_iter().map(|(at, _)| at).collect(),
+	}
+}
+
+// the color for when the tile was opened, on a ramp from the first move to the last
+fn heat(game: &MSGame, x: usize, y: usize) -> Option<u8> {
+	let opened = game.opened_at[game.index_of(x, y)] as usize;
+	let last = game.moves.max(1);
+	(opened > 0).then(|| HEAT_RAMP[((opened - 1) * HEAT_RAMP.len() / last).min(HEAT_RAMP.len() - 1)])
+}
+
+// the gap after `col`, with the brackets of a cursor next to it. the second player in co-op
+// gets square brackets and the ghost curly ones, the first player's win where they touch
+fn cell_gap(theme: &Theme, drawn: &Drawn, ghost: Option<(usize, usize)>, col: usize, row: usize) -> StyledObject<&'static str> {
+	let cursors = [(drawn.cursor(), ["(", ")"])]
+		.into_iter()
+		.chain(drawn.partner().map(|partner| (partner, ["[", "]"])))
+		.chain(ghost.map(|ghost| (drawn.flip(ghost), ["{", "}"])));
+	for ((x, y), [open, close]) in cursors {
+		if y != row {
+			continue;
+		}
+		// the ghost is only a hint of where the best run was
+		let style = if open == "{" { theme.cursor.clone().dim() } else { theme.cursor.clone() };
+		match x.wrapping_sub(col) {
+			1 => return style.apply_to(open),
+			0 => return style.apply_to(close),
+			_ => (),
+		}
+	}
+	style(" ")
+}
+
+const MINIMAP_COLS: usize = 24;
+const MINIMAP_ROWS: usize = 12;
+
+// an overview of the whole board, one character per block of tiles
+// the hidden tiles of every minimap block, kept until the board or the blocks change
+#[derive(Default)]
+pub struct MinimapCache(RefCell<Option<HiddenCounts>>);
+
+struct HiddenCounts {
+	version: u64,
+	transposed: bool,
+	block_w: usize,
+	block_h: usize,
+	counts: Vec<usize>,
+}
+
+struct Minimap {
+	block_w: usize,
+	block_h: usize,
+	cols: usize,
+	rows: usize,
+}
+
+impl Minimap {
+	fn new(drawn: &Drawn, max_rows: usize) -> Self {
+		let block_w = drawn.width().div_ceil(MINIMAP_COLS);
+		let block_h = drawn.height().div_ceil(max_rows.max(1));
+		Self {
+			block_w,
+			block_h,
+			cols: drawn.width().div_ceil(block_w),
+			rows: drawn.height().div_ceil(block_h),
+		}
+	}
+
+	// one line per map row, each block shows how much of it is still hidden.
+	// blocks in the visible area are highlighted
+	fn lines(
+		&self,
+		drawn: &Drawn,
+		cache: &MinimapCache,
+		theme: &Theme,
+		glyphs: &Glyphs,
+		(first_col, cols): (usize, usize),
+		(first_row, rows): (usize, usize),
+	) -> Vec<String> {
+		if self.rows == 0 {
+			return Vec::new();
+		}
+		let mut cache = cache.0.borrow_mut();
+		let current = |counts: &HiddenCounts| {
+			(counts.version, counts.transposed, counts.block_w, counts.block_h)
+				== (drawn.game.board.version(), drawn.transposed, self.block_w, self.block_h)
+		};
+		if !cache.as_ref().is_some_and(current) {
+			*cache = Some(self.count_hidden(drawn));
+		}
+		let (cursor_x, cursor_y) = drawn.cursor();
+		let hidden = &cache.as_ref().expect("just counted").counts;
+		let in_view = |start: usize, len: usize, first: usize, size: usize| start < first + len && first < start + size;
+		(0..self.rows)
+			.map(|map_y| {
+				let mut line = String::new();
+				for map_x in 0..self.cols {
+					let (x, y) = (map_x * self.block_w, map_y * self.block_h);
+					let tiles = self.block_w.min(drawn.width() - x) * self.block_h.min(drawn.height() - y);
+					let level = match hidden[map_y * self.cols + map_x] {
+						0 => 0,
+						n if n == tiles => 3,
+						n if n * 2 <= tiles => 1,
+						_ => 2,
+					};
+					let mut cell = if cursor_x / self.block_w == map_x && cursor_y / self.block_h == map_y {
+						theme.cursor.apply_to('@')
+					} else if drawn.partner().is_some_and(|(x, y)| x / self.block_w == map_x && y / self.block_h == map_y) {
+						theme.cursor.apply_to('&')
+					} else {
+						theme.hidden.apply_to(glyphs.shades[level])
+					};
+					if in_view(x, self.block_w, first_col, cols) && in_view(y, self.block_h, first_row, rows) {
+						cell = cell.on_color256(CHECKER_SHADE);
+					}
+					line.push_str(&cell.to_string());
+				}
+				line
+			})
+			.collect()
+	}
+
+	// a single pass over the board
+	fn count_hidden(&self, drawn: &Drawn) -> HiddenCounts {
+		let mut counts = vec![0; self.cols * self.rows];
+		for row in 0..drawn.height() {
+			let row_start = row / self.block_h * self.cols;
+			for col in 0..drawn.width() {
+				let (x, y) = drawn.flip((col, row));
+				if !matches!(drawn.game.get(x, y).visibility, TileVis::Open) {
+					counts[row_start + col / self.block_w] += 1;
+				}
+			}
+		}
+		HiddenCounts {
+			version: drawn.game.board.version(),
+			transposed: drawn.transposed,
+			block_w: self.block_w,
+			block_h: self.block_h,
+			counts,
+		}
+	}
+}
+
+// returns the first index and length of the range of size `view` centered on `cursor`
+fn view_range(cursor: usize, size: usize, view: usize) -> (usize, usize) {
+	if view >= size {
+		return (0, size);
+	}
+	let first = cursor.saturating_sub(view / 2).min(size - view);
+	(first, view)
+}
+
+// each character is a 2x4 block of tiles, with a dot for every unopened tile.
+// without unicode the blocks are shaded by how many tiles are unopened instead
+pub struct BrailleRenderer {
+	pub theme: Theme,
+	pub glyphs: Glyphs,
+	pub plain: bool,
+	pub center: bool,
+	pub fog: Option<usize>,
+}
+
+const BRAILLE_BASE: u32 = 0x2800;
+// dot bit for each (x, y) in a 2x4 braille cell
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+impl Renderer for BrailleRenderer {
+	fn draw(&self, game: &MSGame, view: &View, status: &Status, stdout: &Term) -> usize {
+		let mut board = String::new();
+		let drawn = Drawn::new(game, view);
+		let (cursor_x, cursor_y) = drawn.cursor();
+		let unrevealed = unrevealed(game, status);
+		for block_y in 0..drawn.height().div_ceil(4) {
+			for block_x in 0..drawn.width().div_ceil(2) {
+				let mut dots = 0;
+				let mut flagged = false;
+				let mut exploded = false;
+				for (dx, column) in BRAILLE_DOTS.iter().enumerate() {
+					for (dy, dot) in column.iter().enumerate() {
+						let (x, y) = drawn.flip((block_x * 2 + dx, block_y * 4 + dy));
+						if !game.valid_pos(x, y) {
+							continue;
+						}
+						let mut tile = game.get(x, y);
+						if unrevealed.contains(&(x, y)) || fogged(game, status, self.fog, x, y) {
+							tile.visibility = TileVis::Hidden;
+						}
+						match (tile.visibility, tile.contents) {
+							(TileVis::Hidden, _) => dots |= dot,
+							(TileVis::Flag | TileVis::AntiFlag, _) => {
+								dots |= dot;
+								flagged = true;
+							},
+							(TileVis::Open, TileContents::Mine | TileContents::AntiMine) => exploded = true,
+							(TileVis::Open, _) => (),
+						}
+					}
+				}
+				let glyph = if self.glyphs.unicode {
+					char::from_u32(BRAILLE_BASE + dots).unwrap()
+				} else {
+					match dots.count_ones() {
+						0 => ' ',
+						1..=2 => '.',
+						3..=5 => ':',
+						_ => '#',
+					}
+				};
+				let mut cell = if exploded {
+					self.theme.mine.apply_to(glyph)
+				} else if flagged {
+					self.theme.flag.apply_to(glyph)
+				} else {
+					self.theme.hidden.apply_to(glyph)
+				};
+				if status.flash || cursor_x / 2 == block_x && cursor_y / 4 == block_y {
+					cell = cell.reverse();
+				} else if drawn.partner().is_some_and(|(x, y)| x / 2 == block_x && y / 4 == block_y) {
+					cell = cell.on_blue();
+				}
+				if status.idle {
+					cell = cell.dim();
+				}
+				board.push_str(&cell.to_string());
+			}
+			board.push('\n');
+		}
+		let size = term_size(stdout, self.plain);
+		let mut out = String::new();
+		let status_lines = status_lines(game, view, status, &self.glyphs, size.map(|(_, cols)| cols));
+		let top = push_centered(&mut out, &board, size.filter(|_| self.center), status_lines);
+		draw_status_bar(&mut out, game, view, status, &self.theme, &self.glyphs, size.map(|(_, cols)| cols));
+		write_frame(stdout, self.plain, &out);
+		top + drawn.height().div_ceil(4) + status_lines
+	}
+}
+
+impl Tile {
+	// the tile glyph centered in a cell `width` columns wide
+	fn styled(&self, width: usize, theme: &Theme) -> StyledObject<Glyph> {
+		match self.visibility {
+			TileVis::Hidden => theme.hidden.apply_to(Glyph::Text("#", width)),
+			TileVis::Open => {
+				match self.contents {
+					TileContents::Mine => theme.mine.apply_to(Glyph::Text("*", width)),
+					TileContents::AntiMine => theme.mine.apply_to(Glyph::Text("o", width)),
+					TileContents::Safe(0) => style(Glyph::Text(" ", width)),
+					TileContents::Safe(num) => number_glyph(num as i16, width, theme),
+				}
+			},
+			TileVis::Flag => theme.flag.apply_to(Glyph::Text("F", width)),
+			TileVis::AntiFlag => theme.flag.apply_to(Glyph::Text("A", width)),
+		}
+	}
+}
+
+// a number colored by its size, negative ones from the anti-mine variant like their positive value
+fn number_glyph(n: i16, width: usize, theme: &Theme) -> StyledObject<Glyph> {
+	match n.unsigned_abs() {
+		0 => style(Glyph::Number(0, width)).dim(),
+		size => theme.numbers[(size as usize - 1).min(7)].apply_to(Glyph::Number(n, width)),
+	}
+}
+
+// what a cell shows and how many columns it is centered in, formatted straight into the frame
+// so drawing doesn't allocate a string per tile
+#[derive(Copy, Clone)]
+enum Glyph {
+	Text(&'static str, usize),
+	Char(char, usize),
+	Number(i16, usize),
+}
+
+impl fmt::Display for Glyph {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Self::Text(text, width) => write!(f, "{:^width$}", text),
+			Self::Char(c, width) => write!(f, "{:^width$}", c),
+			Self::Number(n, width) => write!(f, "{:^width$}", n),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::{self, File};
+
+	use super::*;
+
+	#[test]
+	fn detects_plain_output() {
+		assert!(!is_plain(false, true, Some("xterm-256color")));
+		assert!(!is_plain(false, true, None));
+		assert!(is_plain(true, true, Some("xterm")));
+		assert!(is_plain(false, false, Some("xterm")));
+		assert!(is_plain(false, true, Some("dumb")));
+	}
+
+	fn grid(plain: bool) -> GridRenderer {
+		GridRenderer {
+			theme: Theme::by_name("classic").unwrap(),
+			glyphs: Glyphs::by_name("ascii").unwrap(),
+			plain,
+			wide: false,
+			checkerboard: false,
+			grid_lines: false,
+			center: false,
+			fog: None,
+			minimap: MinimapCache::default(),
+		}
+	}
+
+	// draws a board into a file standing in for the terminal and returns what was written
+	fn captured(name: &str, grid: &GridRenderer) -> String {
+		let path = env::temp_dir().join(format!("minesweeper-{}-{}", name, std::process::id()));
+		let term = Term::read_write_pair(File::open("/dev/null").unwrap(), File::create(&path).unwrap());
+		let game = MSGame::new(4, 3, 2, 1).unwrap();
+		let view = View::default();
+		let status = Status {
+			elapsed: 3,
+			message: Some("hello".into()),
+			..Default::default()
+		};
+		grid.draw(&game, &view, &status, &term);
+		grid.draw(&game, &view, &status, &term);
+		drop(term);
+		let written = fs::read_to_string(&path).unwrap();
+		fs::remove_file(path).unwrap();
+		written
+	}
+
+	#[test]
+	fn plain_frames_are_appended_text() {
+		console::set_colors_enabled(false);
+		let written = captured("plain", &grid(true));
+		assert!(!written.contains('\x1b'), "escape sequences in {:?}", written);
+		// every frame starts with a blank line after the one before it
+		let frame = "\n(#)# # # \n # # # # \n # # # # \n\nhello  3s  Safe left: 10  Mines: 2/2\n";
+		assert_eq!(written, frame.repeat(2));
+	}
+
+	// a board file with the flags at `flags`, the cursor and partner where they are given and the line hints on
+	fn board(text: &str, flags: &[(usize, usize)], cursor: (usize, usize), partner: (usize, usize)) -> MSGame {
+		let mut game = crate::boardfile::parse("test", text).unwrap();
+		for &(x, y) in flags {
+			let i = game.index_of(x, y);
+			let mut tile = game.board.get(i);
+			tile.visibility = TileVis::Flag;
+			game.board.set(i, tile);
+		}
+		game.count_lines();
+		(game.cursor_x, game.cursor_y) = cursor;
+		game.partner = Some(partner);
+		game
+	}
+
+	#[test]
+	fn transposed_boards_are_drawn_turned_over() {
+		console::set_colors_enabled(false);
+		let game = board("*..O.O.\n.O.*..*\nO...*X.\n", &[(0, 0), (3, 1)], (5, 1), (2, 2));
+		let turned = board("*.O\n.O.\n...\nO*.\n..*\nO.X\n.*.\n", &[(0, 0), (1, 3)], (1, 5), (2, 2));
+		let mut view = View {
+			minimap: true,
+			dim_satisfied: true,
+			highlight: true,
+			frontier: true,
+			..Default::default()
+		};
+		let status = Status {
+			elapsed: 3,
+			ghost: Some((4, 0)),
+			..Default::default()
+		};
+		let ghost = Status { message: None, items: Vec::new(), ghost: Some((0, 4)), ..status };
+		for size in [None, Some((6, 40))] {
+			let expected = grid(true).frame(&turned, &view, &ghost, size);
+			view.transpose = true;
+			assert_eq!(grid(true).frame(&game, &view, &status, size), expected);
+			view.transpose = false;
+		}
+	}
+
+	#[test]
+	fn terminal_frames_clear_the_screen() {
+		let written = captured("terminal", &grid(false));
+		assert_eq!(written.matches("\x1b[2J").count(), 2);
+	}
+}
@@ -0,0 +1,353 @@
+use std::fs;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use console::Key;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::action::Action;
+use crate::coop::Coop;
+use crate::input::{command_key, key_command};
+use crate::options::{apply_variants, new_game, Options};
+use crate::{MSGame, TurnResult};
+
+const MAGIC: &str = "minesweeper-replay 1";
+
+// a replay file holds the options that shape the game, the seed, how it ended, and every move:
+//
+//   minesweeper-replay 1
+//   options --width 9 --height 9 --mines 10
+//   seed 1234
+//   result win
+//   time 12.345
+//   moves
+//   310 right 71
+//   802 open 52
+//   20004 rush 52
+//
+// moves are milliseconds since the start, the key as an input command (or rush for a mine added
+// in rush mode), and how many safe tiles were still hidden after it, to find where a replay goes wrong
+pub struct Replay {
+	args: Vec<String>,
+	seed: Option<u64>,
+	result: String,
+	time: f64,
+	moves: Vec<Move>,
+}
+
+struct Move {
+	ms: u64,
+	// None for a mine added in rush mode
+	key: Option<Key>,
+	safe_left: usize,
+}
+
+impl Replay {
+	pub fn new(options: &Options, game: &MSGame) -> Self {
+		Self {
+			args: options.game_args(),
+			seed: game.seed,
+			result: String::new(),
+			time: 0.0,
+			moves: Vec::new(),
+		}
+	}
+
+	pub fn key(&mut self, elapsed: Duration, key: Key, game: &MSGame) {
+		self.moves.push(Move {
+			ms: elapsed.as_millis() as u64,
+			key: Some(key),
+			safe_left: game.safe_left,
+		});
+	}
+
+	pub fn rush(&mut self, elapsed: Duration, game: &MSGame) {
+		self.moves.push(Move {
+			ms: elapsed.as_millis() as u64,
+			key: None,
+			safe_left: game.safe_left,
+		});
+	}
+
+	pub fn save(&mut self, path: &str, result: &TurnResult, elapsed: Duration) -> Result<(), String> {
+		self.result = result_name(result).into();
+		self.time = elapsed.as_secs_f64();
+		fs::write(path, self.to_text()).map_err(|err| format!("could not write '{}': {}", path, err))
+	}
+
+	fn to_text(&self) -> String {
+		let mut text = format!(
+			"{}\noptions {}\nseed {}\nresult {}\ntime {:.3}\nmoves\n",
+			MAGIC,
+			self.args.join(" "),
+			self.seed.map_or("-".into(), |seed| seed.to_string()),
+			self.result,
+			self.time,
+		);
+		for m in &self.moves {
+			let command = m.key.as_ref().and_then(key_command).unwrap_or("rush".into());
+			text.push_str(&format!("{} {} {}\n", m.ms, command, m.safe_left));
+		}
+		text
+	}
+
+	// sets up the options to play the same board with the same rules
+	pub fn configure(&self, options: &mut Options) -> Result<(), String> {
+		options.set_game(&self.args)?;
+		options.seed = self.seed;
+		// without a seed the board can only come from a file
+		if options.seed.is_none() && options.board.is_none() {
+			return Err("the replay has no seed".into());
+		}
+		Ok(())
+	}
+
+	// the milliseconds and key of every move, None for a rush mine
+	pub fn moves(&self) -> impl Iterator<Item = (u64, Option<&Key>)> {
+		self.moves.iter().map(|m| (m.ms, m.key.as_ref()))
+	}
+
+	// the milliseconds into the game when at most `safe_left` safe tiles were still hidden
+	pub fn reached(&self, safe_left: usize) -> Option<u64> {
+		self.moves.iter().find(|m| m.safe_left <= safe_left).map(|m| m.ms)
+	}
+
+	// sends the keys at the times they were pressed, rush mines come from the game's own timer
+	pub fn spawn_player(&self) -> Receiver<Key> {
+		let (sender, receiver) = mpsc::channel();
+		let keys: Vec<_> = self.moves.iter().filter_map(|m| Some((m.ms, m.key.clone()?))).collect();
+		thread::spawn(move || {
+			let start = Instant::now();
+			for (ms, key) in keys {
+				let at = start + Duration::from_millis(ms);
+				thread::sleep(at.saturating_duration_since(Instant::now()));
+				if sender.send(key).is_err() {
+					return;
+				}
+			}
+		});
+		receiver
+	}
+
+	pub fn load(path: &str) -> Result<Self, String> {
+		let text = fs::read_to_string(path).map_err(|err| format!("could not read '{}': {}", path, err))?;
+		let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+		if lines.next().map(|(_, line)| line) != Some(MAGIC) {
+			return Err(format!("'{}' is not a replay", path));
+		}
+		let mut field = |name: &str| -> Result<String, String> {
+			match lines.next() {
+				Some((_, line)) if line == name => Ok(String::new()),
+				Some((_, line)) => match line.split_once(' ') {
+					Some((key, value)) if key == name => Ok(value.into()),
+					_ => Err(format!("{}: expected '{}'", path, name)),
+				},
+				None => Err(format!("{}: expected '{}'", path, name)),
+			}
+		};
+		let args = field("options")?.split_whitespace().map(String::from).collect();
+		let seed = match field("seed")?.as_str() {
+			"-" => None,
+			seed => Some(seed.parse().map_err(|_| format!("{}: invalid seed '{}'", path, seed))?),
+		};
+		let result = field("result")?;
+		let time = field("time")?;
+		let time = time.parse().map_err(|_| format!("{}: invalid time '{}'", path, time))?;
+		field("moves")?;
+
+		let mut moves = Vec::new();
+		for (n, line) in lines.filter(|(_, line)| !line.is_empty()) {
+			let bad = || format!("{}:{}: expected '<ms> <command> <safe tiles left>'", path, n);
+			let mut fields = line.split_whitespace();
+			let (Some(ms), Some(command), Some(safe_left), None) = (fields.next(), fields.next(), fields.next(), fields.next())
+			else {
+				return Err(bad());
+			};
+			let key = match command {
+				"rush" => None,
+				command => Some(command_key(command).ok_or_else(bad)?),
+			};
+			moves.push(Move {
+				ms: ms.parse().map_err(|_| bad())?,
+				key,
+				safe_left: safe_left.parse().map_err(|_| bad())?,
+			});
+		}
+		Ok(Self { args, seed, result, time, moves })
+	}
+}
+
+pub fn result_name(result: &TurnResult) -> &'static str {
+	match result {
+		TurnResult::Win => "win",
+		TurnResult::Lose => "loss",
+		TurnResult::Surrender => "surrender",
+		_ => "quit",
+	}
+}
+
+// does what a key from a replay did in the game, the second player's keys are theirs in co-op
+pub fn play_key(game: &mut MSGame, coop: Option<&Coop>, key: &Key) -> TurnResult {
+	if let Some(action) = coop.and_then(|coop| coop.action(key)) {
+		return game.act_as_partner(action);
+	}
+	match Action::from_key(key) {
+		Some(action) => game.act(action),
+		None => TurnResult::Continue,
+	}
+}
+
+// plays the replay again without drawing anything and checks that it ends the way it says, returns the exit code
+pub fn verify(path: &str) -> i32 {
+	let replay = match Replay::load(path) {
+		Ok(replay) => replay,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	match check(&replay) {
+		Ok(()) => {
+			println!("PASS {} in {:.3}s, {} moves", replay.result, replay.time, replay.moves.len());
+			0
+		},
+		Err(reason) => {
+			println!("FAIL {}", reason);
+			1
+		},
+	}
+}
+
+fn check(replay: &Replay) -> Result<(), String> {
+	let mut options = Options::default();
+	replay.configure(&mut options)?;
+	let mut game = new_game(&options).map_err(|err| err.to_string())?;
+	apply_variants(&mut game, &options).map_err(|err| err.to_string())?;
+	// the same generator the game uses for rush mode
+	let mut rush_rng = StdRng::seed_from_u64(game.seed.unwrap_or_default());
+	let coop = options.coop.then(|| Coop::new(&options.coop_keys));
+
+	let mut result = TurnResult::Continue;
+	let mut last_ms = 0;
+	for (i, m) in replay.moves.iter().enumerate() {
+		let n = i + 1;
+		if !matches!(result, TurnResult::Continue) {
+			return Err(format!("move {}: the game was already over", n));
+		}
+		if m.ms < last_ms {
+			return Err(format!("move {}: goes back in time from {}ms to {}ms", n, last_ms, m.ms));
+		}
+		last_ms = m.ms;
+		match &m.key {
+			Some(key) => result = play_key(&mut game, coop.as_ref(), key),
+			None => {
+				// rush mines only come at whole multiples of the interval
+				let every = options.rush.ok_or(format!("move {}: a rush mine outside rush mode", n))?;
+				let due = replay.moves[..i].iter().filter(|m| m.key.is_none()).count() as u64 + 1;
+				if (m.ms as u128) < every.as_millis() * due as u128 {
+					return Err(format!("move {}: a rush mine at {}ms, too early", n, m.ms));
+				}
+				game.add_mine(&mut rush_rng);
+			},
+		}
+		game.events.clear();
+		if game.safe_left != m.safe_left {
+			return Err(format!(
+				"move {} ({}ms): {} safe tiles left instead of {}",
+				n, m.ms, game.safe_left, m.safe_left
+			));
+		}
+	}
+	let ended = result_name(&result);
+	if matches!(result, TurnResult::Continue) && replay.result != "quit" {
+		return Err(format!("the moves run out before the game ends, but it claims a {}", replay.result));
+	}
+	if !matches!(result, TurnResult::Continue) && ended != replay.result {
+		return Err(format!("the game ends in a {} but claims a {}", ended, replay.result));
+	}
+	// the time is taken right after the last move, and can't be before it
+	let time_ms = (replay.time * 1000.0).round() as u64;
+	if time_ms < last_ms {
+		return Err(format!("the time {:.3}s is before the last move at {}ms", replay.time, last_ms));
+	}
+	if matches!(result, TurnResult::Win) && time_ms - last_ms > 1000 {
+		return Err(format!("the time {:.3}s is long after the winning move at {}ms", replay.time, last_ms));
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// plays the commands on the board the options make the way the game does, `None` for a rush mine,
+	// and keeps them as a replay of the game
+	fn record(options: &Options, moves: &[(u64, Option<&str>)]) -> Replay {
+		let mut game = new_game(options).unwrap();
+		apply_variants(&mut game, options).unwrap();
+		let mut rush_rng = StdRng::seed_from_u64(game.seed.unwrap_or_default());
+		let mut replay = Replay::new(options, &game);
+		let mut result = TurnResult::Continue;
+		for &(ms, command) in moves {
+			let elapsed = Duration::from_millis(ms);
+			match command {
+				Some(command) => {
+					let key = command_key(command).unwrap();
+					result = play_key(&mut game, None, &key);
+					replay.key(elapsed, key, &game);
+				},
+				None => {
+					game.add_mine(&mut rush_rng);
+					replay.rush(elapsed, &game);
+				},
+			}
+			game.events.clear();
+		}
+		replay.result = result_name(&result).into();
+		replay.time = moves.last().map_or(0.0, |&(ms, _)| ms as f64 / 1000.0);
+		replay
+	}
+
+	fn options() -> Options {
+		Options {
+			width: 9,
+			height: 9,
+			mines: 10,
+			seed: Some(7),
+			rush: Some(Duration::from_secs(5)),
+			..Options::default()
+		}
+	}
+
+	const MOVES: [(u64, Option<&str>); 5] = [(300, Some("open")), (900, Some("right")), (1400, Some("flag")), (5000, None), (6100, Some("flag"))];
+
+	#[test]
+	fn recorded_games_pass() {
+		let replay = record(&options(), &MOVES);
+		assert_eq!(check(&replay), Ok(()));
+		// and the same after being written out and read back
+		let path = std::env::temp_dir().join(format!("minesweeper-replay-{}.msr", std::process::id()));
+		let path = path.to_str().unwrap();
+		fs::write(path, replay.to_text()).unwrap();
+		assert_eq!(check(&Replay::load(path).unwrap()), Ok(()));
+		fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn tampered_moves_fail_where_they_go_wrong() {
+		let mut replay = record(&options(), &MOVES);
+		replay.moves[2].safe_left += 1;
+		let safe_left = replay.moves[2].safe_left;
+		assert_eq!(check(&replay), Err(format!("move 3 (1400ms): {} safe tiles left instead of {}", safe_left - 1, safe_left)));
+
+		let mut replay = record(&options(), &MOVES);
+		replay.moves[3].ms = 4000;
+		assert_eq!(check(&replay), Err("move 4: a rush mine at 4000ms, too early".into()));
+
+		let mut replay = record(&options(), &MOVES);
+		replay.result = "win".into();
+		assert_eq!(check(&replay), Err("the moves run out before the game ends, but it claims a win".into()));
+	}
+}
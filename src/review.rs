@@ -0,0 +1,103 @@
+use crate::lang::{tr, trf, Msg};
+use crate::pos_label;
+use crate::solver::{BoardView, Cell, Solver};
+
+// what the solver thought of an open just before it happened
+#[derive(Clone, Copy, PartialEq)]
+pub enum Verdict {
+	// certainly safe
+	Safe,
+	// nothing was certainly safe, so a guess was needed
+	Guess,
+	// the numbers proved the tile was a mine
+	ProvenMine,
+	// a guess while this tile was certainly safe
+	MissedSafe(usize, usize),
+}
+
+// an open of hidden tiles by the player: the tile opened, None for opening everything unflagged at once,
+// and how many tiles had been opened before it
+#[derive(Clone, Copy)]
+pub struct Step {
+	pub at: Option<(usize, usize)>,
+	pub after: usize,
+}
+
+// the opens leading up to a loss judged one by one, for the end screen and the history
+#[derive(Clone, Default)]
+pub struct Review {
+	// opens that weren't certainly safe while something else was, or that were certainly mines
+	pub risky: usize,
+	// the verdict on the open that hit the mine, None when it wasn't a single tile
+	pub last: Option<Verdict>,
+}
+
+impl Review {
+	// plays the opens again from `view`, the board before any of them,
+	// showing the tiles in `opened` as each open reaches them
+	pub fn after_loss(solver: &dyn Solver, mut view: BoardView, opened: &[((usize, usize), u8)], steps: &[Step]) -> Self {
+		let mut review = Self::default();
+		let mut shown = 0;
+		for step in steps {
+			for &((x, y), n) in &opened[shown..step.after] {
+				view.set(x, y, Cell::Open(n));
+			}
+			shown = step.after;
+			review.last = step.at.map(|at| review.judge(solver, &view, at));
+		}
+		review
+	}
+
+	fn judge(&mut self, solver: &dyn Solver, view: &BoardView, (x, y): (usize, usize)) -> Verdict {
+		let deductions = solver.deduce(view);
+		let verdict = if deductions.safe.contains(&(x, y)) {
+			Verdict::Safe
+		} else if deductions.mines.contains(&(x, y)) {
+			Verdict::ProvenMine
+		} else {
+			// the closest certainly safe tile is the one the player most likely overlooked
+			let distance = |&&(sx, sy): &&(usize, usize)| sx.abs_diff(x).max(sy.abs_diff(y));
+			match deductions.safe.iter().min_by_key(distance) {
+				Some(&(sx, sy)) => Verdict::MissedSafe(sx, sy),
+				None => Verdict::Guess,
+			}
+		};
+		if matches!(verdict, Verdict::ProvenMine | Verdict::MissedSafe(..)) {
+			self.risky += 1;
+		}
+		verdict
+	}
+
+	// lines for the end screen after hitting a mine
+	pub fn loss_lines(&self) -> Vec<String> {
+		let mut lines = Vec::new();
+		let mut earlier = self.risky;
+		match self.last {
+			Some(Verdict::ProvenMine) => {
+				lines.push(tr(Msg::ProvenMine).into());
+				earlier -= 1;
+			},
+			Some(Verdict::MissedSafe(x, y)) => {
+				lines.push(trf(Msg::MissedSafe, &[&pos_label(x, y)]));
+				earlier -= 1;
+			},
+			Some(Verdict::Guess) => lines.push(tr(Msg::ForcedGuess).into()),
+			_ => (),
+		}
+		if earlier > 0 {
+			lines.push(trf(Msg::RiskyOpens, &[&earlier]));
+		}
+		lines
+	}
+
+	// the verdict on the last open and the tile that was certainly safe, for the history
+	pub fn columns(&self) -> (&'static str, String) {
+		match self.last {
+			Some(Verdict::Safe) => ("safe", String::new()),
+			Some(Verdict::Guess) => ("guess", String::new()),
+			Some(Verdict::ProvenMine) => ("proven-mine", String::new()),
+			Some(Verdict::MissedSafe(x, y)) => ("missed-safe", format!("{} {}", x, y)),
+			None => ("", String::new()),
+		}
+	}
+}
@@ -0,0 +1,71 @@
+use crate::lang::{trf, Msg};
+use crate::GameEvent;
+
+const TILE_POINTS: u64 = 10;
+// every this many tiles opened in one go adds 1 to the fill multiplier
+const FILL_STEP: usize = 10;
+const MAX_FILL_MULT: u64 = 5;
+// every this many opens in a row without flagging adds 1 to the streak multiplier
+const STREAK_STEP: u64 = 5;
+const MAX_STREAK_MULT: u64 = 4;
+const TIME_BONUS_PER_SEC: u64 = 50;
+
+#[derive(Default)]
+pub struct Score {
+	tile_points: u64,
+	fill_bonus: u64,
+	streak_bonus: u64,
+	time_bonus: u64,
+	streak: u64,
+}
+
+impl Score {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn handle(&mut self, event: &GameEvent) {
+		match *event {
+			GameEvent::Opened(count) => {
+				let base = count as u64 * TILE_POINTS;
+				let fill_mult = (1 + (count / FILL_STEP) as u64).min(MAX_FILL_MULT);
+				let streak_mult = self.streak_mult();
+				let filled = base * fill_mult;
+
+				self.tile_points += base;
+				self.fill_bonus += filled - base;
+				self.streak_bonus += filled * streak_mult - filled;
+				self.streak += 1;
+			},
+			GameEvent::Flagged => self.streak = 0,
+		}
+	}
+
+	pub fn win(&mut self, seconds: u64, par: u64) {
+		self.time_bonus = par.saturating_sub(seconds) * TIME_BONUS_PER_SEC;
+	}
+
+	pub fn total(&self) -> u64 {
+		self.tile_points + self.fill_bonus + self.streak_bonus + self.time_bonus
+	}
+
+	pub fn streak_mult(&self) -> u64 {
+		(1 + self.streak / STREAK_STEP).min(MAX_STREAK_MULT)
+	}
+
+	pub fn status(&self) -> String {
+		trf(Msg::ScoreStatus, &[&self.total(), &self.streak_mult()])
+	}
+
+	pub fn breakdown(&self) -> String {
+		trf(
+			Msg::ScoreBreakdown,
+			&[&self.total(), &self.tile_points, &self.fill_bonus, &self.streak_bonus, &self.time_bonus],
+		)
+	}
+}
+
+// par time in seconds, roughly one second for every two safe tiles on the board
+pub fn par_time(width: usize, height: usize, mines: usize) -> u64 {
+	((width * height).saturating_sub(mines) / 2) as u64
+}
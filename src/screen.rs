@@ -0,0 +1,32 @@
+use std::panic;
+
+use console::Term;
+
+// console has no way to switch screens, so these are written directly
+const ENTER_ALT_SCREEN: &str = "\x1b[?1049h";
+const LEAVE_ALT_SCREEN: &str = "\x1b[?1049l";
+
+// switches to the alternate screen so the game doesn't end up in the scrollback,
+// a panic switches back before the message is printed
+pub fn enter(stdout: &Term) {
+	stdout.write_str(ENTER_ALT_SCREEN).unwrap();
+	stdout.flush().unwrap();
+	let default_hook = panic::take_hook();
+	panic::set_hook(Box::new(move |info| {
+		let term = Term::stdout();
+		let _ = term.write_str(LEAVE_ALT_SCREEN);
+		let _ = term.show_cursor();
+		default_hook(info);
+	}));
+	// console raises SIGINT itself after reading ctrl+c, which would kill the game without
+	// switching back. ignoring it lets the key reader stop and the game quit normally instead
+	#[cfg(unix)]
+	unsafe {
+		libc::signal(libc::SIGINT, libc::SIG_IGN);
+	}
+}
+
+pub fn leave(stdout: &Term) {
+	stdout.write_str(LEAVE_ALT_SCREEN).unwrap();
+	stdout.flush().unwrap();
+}
@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::probability::{self, Probabilities};
+
+/// What a player can see of a single tile.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cell {
+	Hidden,
+	Flagged,
+	Open(u8),
+	/// Not part of the board, for boards that aren't rectangles.
+	Void,
+}
+
+/// The player-visible state of a board, the only input the solver gets.
+#[derive(Clone, Debug)]
+pub struct BoardView {
+	width: usize,
+	height: usize,
+	radius: usize,
+	cells: Vec<Cell>,
+}
+
+impl BoardView {
+	/// A board with every tile hidden.
+	pub fn new(width: usize, height: usize) -> Self {
+		Self::with_radius(width, height, 1)
+	}
+
+	/// A board where numbers count the mines up to `radius` tiles away, diagonals included.
+	pub fn with_radius(width: usize, height: usize, radius: usize) -> Self {
+		Self {
+			width,
+			height,
+			radius,
+			cells: vec![Cell::Hidden; width * height],
+		}
+	}
+
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	pub fn get(&self, x: usize, y: usize) -> Cell {
+		self.cells[x + y * self.width]
+	}
+
+	pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+		self.cells[x + y * self.width] = cell;
+	}
+
+	pub fn radius(&self) -> usize {
+		self.radius
+	}
+
+	pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+		self.within(x, y, self.radius).filter(move |&pos| pos != (x, y))
+	}
+
+	// every tile at most `r` steps away, including the center
+	fn within(&self, x: usize, y: usize, r: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+		(y.saturating_sub(r)..(y + r + 1).min(self.height))
+			.flat_map(move |ny| (x.saturating_sub(r)..(x + r + 1).min(self.width)).map(move |nx| (nx, ny)))
+	}
+}
+
+/// Hidden tiles that are certainly safe or certainly mines, in reading order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Deductions {
+	pub safe: Vec<(usize, usize)>,
+	pub mines: Vec<(usize, usize)>,
+}
+
+impl Deductions {
+	pub fn is_empty(&self) -> bool {
+		self.safe.is_empty() && self.mines.is_empty()
+	}
+}
+
+/// Reasoning about a board for the hints, the assists, `--analyze` and no-guess boards, so a stronger
+/// solver can take the place of the built-in [`Standard`] one.
+///
+/// A solver only ever gets a [`BoardView`], what the player can see: opened numbers, flags and hidden
+/// tiles, never where the mines are. Flags are the player's guesses and can be wrong, so nothing a
+/// solver proves may rest on them.
+pub trait Solver {
+	/// Every hidden tile that can be proven safe or mined, in reading order.
+	fn deduce(&self, view: &BoardView) -> Deductions;
+
+	/// The chance of every hidden tile being a mine when the board has `mines` mines in all,
+	/// None for solvers that don't estimate chances.
+	fn estimate(&self, _view: &BoardView, _mines: usize) -> Option<Probabilities> {
+		None
+	}
+
+	/// The same as [`Solver::deduce`] after the tiles in `changed` were opened, given the deductions
+	/// from before. By default it starts over.
+	fn update(&self, view: &BoardView, _previous: &Deductions, _changed: &[(usize, usize)]) -> Deductions {
+		self.deduce(view)
+	}
+
+	/// Whether the tile at `x`, `y` is certainly a mine (`Some(true)`) or certainly safe (`Some(false)`).
+	/// By default it deduces the whole board.
+	fn classify(&self, view: &BoardView, x: usize, y: usize) -> Option<bool> {
+		let deductions = self.deduce(view);
+		if deductions.mines.contains(&(x, y)) {
+			Some(true)
+		} else if deductions.safe.contains(&(x, y)) {
+			Some(false)
+		} else {
+			None
+		}
+	}
+}
+
+/// The built-in solver: single-number and subset reasoning from [`deduce`], and chances from
+/// [`probabilities`](probability::probabilities).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Standard;
+
+impl Solver for Standard {
+	fn deduce(&self, view: &BoardView) -> Deductions {
+		deduce(view)
+	}
+
+	fn estimate(&self, view: &BoardView, mines: usize) -> Option<Probabilities> {
+		Some(probability::probabilities(view, mines))
+	}
+
+	fn update(&self, view: &BoardView, previous: &Deductions, changed: &[(usize, usize)]) -> Deductions {
+		update(view, previous, changed)
+	}
+
+	fn classify(&self, view: &BoardView, x: usize, y: usize) -> Option<bool> {
+		classify(view, x, y)
+	}
+}
+
+// the hidden tiles around an opened number and how many of them are mines
+struct Constraint {
+	at: (usize, usize),
+	cells: Vec<(usize, usize)>,
+	mines: usize,
+}
+
+/// Finds every hidden tile that can be proven safe or mined from the opened numbers,
+/// using single-number reasoning and subset reasoning between pairs of numbers.
+/// Flags are player guesses and are treated like any other hidden tile.
+pub fn deduce(view: &BoardView) -> Deductions {
+	let everything: Vec<_> = (0..view.height).flat_map(|y| (0..view.width).map(move |x| (x, y))).collect();
+	update(view, &Deductions::default(), &everything)
+}
+
+/// The same as [`deduce`] after the tiles in `changed` were opened, given the deductions from before.
+/// Only the numbers close enough to the changes to be affected are looked at again.
+pub fn update(view: &BoardView, previous: &Deductions, changed: &[(usize, usize)]) -> Deductions {
+	// more numbers never disprove anything, so earlier deductions hold for the tiles still hidden
+	let hidden = |&&(x, y): &&(usize, usize)| !matches!(view.get(x, y), Cell::Open(_) | Cell::Void);
+	let mut safe: HashSet<_> = previous.safe.iter().filter(hidden).copied().collect();
+	let mut mines: HashSet<_> = previous.mines.iter().filter(hidden).copied().collect();
+	let mut dirty: HashSet<_> = changed.iter().copied().collect();
+
+	while !dirty.is_empty() {
+		// numbers that see a changed tile, and the ones close enough to share hidden tiles with those
+		let reach = 3 * view.radius;
+		let numbers: HashSet<_> = dirty.iter().flat_map(|&(x, y)| view.within(x, y, reach)).collect();
+		let constraints = constraints(view, &safe, &mines, numbers);
+		let mut newly = HashSet::new();
+		let mut mark = |cells: &[(usize, usize)], is_mine: bool| {
+			for &cell in cells {
+				let set = if is_mine { &mut mines } else { &mut safe };
+				if set.insert(cell) {
+					newly.insert(cell);
+				}
+			}
+		};
+
+		for constraint in &constraints {
+			if constraint.mines == 0 {
+				mark(&constraint.cells, false);
+			} else if constraint.mines == constraint.cells.len() {
+				mark(&constraint.cells, true);
+			}
+		}
+
+		// only numbers at most two radii apart can share hidden neighbors
+		let reach = 2 * view.radius;
+		let by_pos: HashMap<_, _> = constraints.iter().map(|c| (c.at, c)).collect();
+		for small in &constraints {
+			let (x, y) = small.at;
+			let nearby = (y.saturating_sub(reach)..=y + reach)
+				.flat_map(|ny| (x.saturating_sub(reach)..=x + reach).map(move |nx| (nx, ny)))
+				.filter_map(|pos| by_pos.get(&pos));
+			for big in nearby {
+				if small.cells.len() >= big.cells.len() || !small.cells.iter().all(|c| big.cells.contains(c)) {
+					continue;
+				}
+				let rest: Vec<_> = big.cells.iter().filter(|c| !small.cells.contains(c)).copied().collect();
+				let rest_mines = big.mines.saturating_sub(small.mines);
+				if rest_mines == 0 {
+					mark(&rest, false);
+				} else if rest_mines == rest.len() {
+					mark(&rest, true);
+				}
+			}
+		}
+
+		dirty = newly;
+	}
+
+	Deductions {
+		safe: sorted(safe),
+		mines: sorted(mines),
+	}
+}
+
+/// Whether the tile at `x`, `y` is certainly a mine (`Some(true)`) or certainly safe (`Some(false)`).
+/// Only the numbers near the tile are used, so it is quick but can miss long chains of reasoning.
+pub fn classify(view: &BoardView, x: usize, y: usize) -> Option<bool> {
+	let deductions = update(view, &Deductions::default(), &[(x, y)]);
+	if deductions.mines.contains(&(x, y)) {
+		Some(true)
+	} else if deductions.safe.contains(&(x, y)) {
+		Some(false)
+	} else {
+		None
+	}
+}
+
+fn constraints(
+	view: &BoardView,
+	safe: &HashSet<(usize, usize)>,
+	mines: &HashSet<(usize, usize)>,
+	numbers: HashSet<(usize, usize)>,
+) -> Vec<Constraint> {
+	let mut numbers: Vec<_> = numbers.into_iter().collect();
+	// reading order keeps the results the same from run to run
+	numbers.sort_by_key(|&(x, y)| (y, x));
+	let mut constraints = Vec::new();
+	for (x, y) in numbers {
+		let Cell::Open(n) = view.get(x, y) else {
+			continue;
+		};
+		let mut cells = Vec::new();
+		let mut known_mines = 0;
+		for (nx, ny) in view.neighbors(x, y) {
+			if let Cell::Open(_) | Cell::Void = view.get(nx, ny) {
+				continue;
+			}
+			if mines.contains(&(nx, ny)) {
+				known_mines += 1;
+			} else if !safe.contains(&(nx, ny)) {
+				cells.push((nx, ny));
+			}
+		}
+		if !cells.is_empty() {
+			constraints.push(Constraint {
+				at: (x, y),
+				cells,
+				mines: (n as usize).saturating_sub(known_mines),
+			});
+		}
+	}
+	constraints
+}
+
+fn sorted(cells: HashSet<(usize, usize)>) -> Vec<(usize, usize)> {
+	let mut cells: Vec<_> = cells.into_iter().collect();
+	cells.sort_by_key(|&(x, y)| (y, x));
+	cells
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{MSGame, TileContents};
+
+	// one string per row: `#` hidden, `F` flagged, `X` void and digits for opened numbers
+	fn view(rows: &[&str]) -> BoardView {
+		let mut view = BoardView::new(rows[0].len(), rows.len());
+		for (y, row) in rows.iter().enumerate() {
+			for (x, c) in row.chars().enumerate() {
+				let cell = match c {
+					'#' => Cell::Hidden,
+					'F' => Cell::Flagged,
+					'X' => Cell::Void,
+					_ => Cell::Open(c.to_digit(10).expect("a digit") as u8),
+				};
+				view.set(x, y, cell);
+			}
+		}
+		view
+	}
+
+	#[test]
+	fn single_number() {
+		// the 1 on the right has one hidden tile, which makes the other one by the left 1 safe
+		let deductions = deduce(&view(&["#1#1"]));
+		assert_eq!(deductions.mines, [(2, 0)]);
+		assert_eq!(deductions.safe, [(0, 0)]);
+	}
+
+	#[test]
+	fn zero_opens_everything_around() {
+		let deductions = deduce(&view(&["###", "#0#", "###"]));
+		assert_eq!(deductions.safe.len(), 8);
+		assert!(deductions.mines.is_empty());
+	}
+
+	#[test]
+	fn one_two_one() {
+		// neither number decides anything alone, the 2 minus either 1 does
+		let deductions = deduce(&view(&["###", "121"]));
+		assert_eq!(deductions.mines, [(0, 0), (2, 0)]);
+		assert_eq!(deductions.safe, [(1, 0)]);
+	}
+
+	#[test]
+	fn one_two_two_one() {
+		let deductions = deduce(&view(&["####", "1221"]));
+		assert_eq!(deductions.mines, [(1, 0), (2, 0)]);
+		assert_eq!(deductions.safe, [(0, 0), (3, 0)]);
+	}
+
+	#[test]
+	fn no_deduction() {
+		assert!(deduce(&view(&["###", "#1#", "###"])).is_empty());
+		assert!(deduce(&view(&["####", "#11#"])).is_empty());
+		assert!(deduce(&view(&["###", "###"])).is_empty());
+	}
+
+	#[test]
+	fn fifty_fifty_is_a_forced_guess() {
+		let view = view(&["##", "11"]);
+		assert!(deduce(&view).is_empty());
+		assert_eq!(classify(&view, 0, 0), None);
+		assert_eq!(classify(&view, 1, 0), None);
+		assert_eq!(Standard.classify(&view, 0, 0), None);
+	}
+
+	#[test]
+	fn flags_are_not_trusted() {
+		// a flag doesn't satisfy the 1, so the other tile stays unknown
+		assert!(deduce(&view(&["#1F"])).is_empty());
+		// and a flagged tile can still be proven a mine
+		assert_eq!(deduce(&view(&["F1"])).mines, [(0, 0)]);
+	}
+
+	#[test]
+	fn void_tiles_are_not_neighbors() {
+		let deductions = deduce(&view(&["X1#"]));
+		assert_eq!(deductions.mines, [(2, 0)]);
+	}
+
+	#[test]
+	fn classify_agrees_with_deduce() {
+		let view = view(&["#1#1"]);
+		assert_eq!(classify(&view, 2, 0), Some(true));
+		assert_eq!(Standard.classify(&view, 0, 0), Some(false));
+	}
+
+	#[test]
+	fn update_matches_starting_over() {
+		let mut view = view(&["####", "####", "####"]);
+		view.set(0, 2, Cell::Open(1));
+		view.set(1, 2, Cell::Open(2));
+		let before = deduce(&view);
+		view.set(2, 2, Cell::Open(1));
+		assert_eq!(update(&view, &before, &[(2, 2)]), deduce(&view));
+	}
+
+	// every deduction on real boards has to hold for the mines that are actually there
+	#[test]
+	fn deductions_are_sound() {
+		for seed in 0..300 {
+			let mut game = MSGame::new(6, 5, 7, seed).unwrap();
+			let safe = |game: &MSGame, x, y| matches!(game.get(x, y).contents, TileContents::Safe(_));
+			if !safe(&game, 2, 2) {
+				continue;
+			}
+			game.open_at(2, 2).unwrap();
+			loop {
+				let deductions = deduce(&game.view());
+				for &(x, y) in &deductions.mines {
+					assert!(!safe(&game, x, y), "seed {}", seed);
+				}
+				let Some(&(x, y)) = deductions.safe.first() else {
+					break;
+				};
+				assert!(safe(&game, x, y), "seed {}", seed);
+				game.open_at(x, y).unwrap();
+			}
+		}
+	}
+}
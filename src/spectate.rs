@@ -0,0 +1,228 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+
+use console::{Key, Term};
+
+use crate::glyphs::Glyphs;
+use crate::json;
+use crate::lang::{tr, Msg};
+use crate::options::Options;
+use crate::render::{self, GridRenderer, MinimapCache, Renderer, Status, View};
+use crate::replay;
+use crate::theme::Theme;
+use crate::{input, screen, MSGame, TileContents, TileVis, TurnResult};
+
+// how often the spectator looks at the file
+const POLL_TIME: Duration = Duration::from_millis(100);
+
+// the game as someone watching from another terminal sees it, rewritten after every move:
+//
+//   {"version": 1, "width": 3, "height": 2, "mines": 1, "flags": 1, "safe_left": 2,
+//    "elapsed": 7, "result": "playing", "cursor": [0, 1], "partner": null, "exploded": null,
+//    "rows": ["1F#", "1##"]}
+//
+// rows have a character per tile: `#` hidden, `F` flag, `A` anti-flag, `*` opened mine, `o` opened
+// anti-mine, the number on an opened tile (a space for none) and `X` for tiles outside the board.
+// hidden tiles don't say what is under them
+pub fn state(game: &MSGame, elapsed: u64, result: &TurnResult) -> String {
+	let result = match result {
+		TurnResult::Continue => "playing",
+		result => replay::result_name(result),
+	};
+	let pos = |pos: Option<(usize, usize)>| pos.map_or("null".into(), |(x, y)| format!("[{}, {}]", x, y));
+	let rows: Vec<_> = (0..game.height)
+		.map(|y| format!("\"{}\"", json::escape(&row(game, y))))
+		.collect();
+	let mut text = String::new();
+	writeln!(
+		text,
+		"{{\"version\": 1, \"width\": {}, \"height\": {}, \"mines\": {}, \"flags\": {}, \"safe_left\": {}, \"elapsed\": {}, \
+		\"result\": \"{}\", \"cursor\": {}, \"partner\": {}, \"exploded\": {}, \"rows\": [{}]}}",
+		game.width,
+		game.height,
+		game.mines,
+		game.flags,
+		game.safe_left,
+		elapsed,
+		result,
+		pos(Some((game.cursor_x, game.cursor_y))),
+		pos(game.partner),
+		pos(game.exploded),
+		rows.join(", "),
+	)
+	.unwrap();
+	text
+}
+
+fn row(game: &MSGame, y: usize) -> String {
+	(0..game.width)
+		.map(|x| {
+			let tile = game.shown(x, y);
+			match (tile.visibility, tile.contents) {
+				_ if game.is_void(x, y) => 'X',
+				(TileVis::Hidden, _) => '#',
+				(TileVis::Flag, _) => 'F',
+				(TileVis::AntiFlag, _) => 'A',
+				(TileVis::Open, TileContents::Mine) => '*',
+				(TileVis::Open, TileContents::AntiMine) => 'o',
+				// numbers below zero from the anti-mine variant can't be shown in one character
+				(TileVis::Open, TileContents::Safe(_)) => match game.number(x, y) {
+					n @ 1..=9 => char::from(b'0' + n as u8),
+					_ => ' ',
+				},
+			}
+		})
+		.collect()
+}
+
+// writes the state next to `path` and moves it over the old one, so the spectator never reads half a file
+pub fn publish(path: &str, game: &MSGame, elapsed: u64, result: &TurnResult) -> io::Result<()> {
+	let temp = format!("{}.tmp", path);
+	fs::write(&temp, state(game, elapsed, result))?;
+	fs::rename(&temp, path)
+}
+
+// a board that looks like the published one, with nothing under the hidden tiles.
+// None for anything that isn't a whole state file
+fn load(text: &str) -> Option<(MSGame, u64, String)> {
+	let state = json::parse(text).ok()?;
+	// a newer game could write something this one would draw wrong
+	if state.get("version") != Some(&json::Value::Number(1.0)) {
+		return None;
+	}
+	let field = |name: &str| state.get(name).and_then(json::Value::as_usize);
+	let (width, height) = (field("width")?, field("height")?);
+	let rows = state.get("rows")?.as_array()?;
+	if width == 0 || rows.len() != height {
+		return None;
+	}
+	let mut cells = Vec::with_capacity(width * height);
+	for row in rows {
+		let row: Vec<char> = row.as_str()?.chars().collect();
+		if row.len() != width {
+			return None;
+		}
+		cells.extend(row);
+	}
+	let void = if cells.contains(&'X') {
+		cells.iter().map(|&c| c == 'X').collect()
+	} else {
+		Vec::new()
+	};
+	let mut game = MSGame::from_mask(width, height, &vec![false; width * height], void);
+	for (i, &c) in cells.iter().enumerate() {
+		let (x, y) = (i % width, i / width);
+		if c == 'X' {
+			continue;
+		}
+		let mut tile = game.get(x, y);
+		(tile.visibility, tile.contents) = match c {
+			'#' => (TileVis::Hidden, TileContents::Safe(0)),
+			'F' => (TileVis::Flag, TileContents::Safe(0)),
+			'A' => (TileVis::AntiFlag, TileContents::Safe(0)),
+			'*' => (TileVis::Open, TileContents::Mine),
+			'o' => (TileVis::Open, TileContents::AntiMine),
+			' ' => (TileVis::Open, TileContents::Safe(0)),
+			c => (TileVis::Open, TileContents::Safe(c.to_digit(10)? as u8)),
+		};
+		game.set(x, y, tile);
+	}
+	game.mines = field("mines")?;
+	game.flags = field("flags")?;
+	game.safe_left = field("safe_left")?;
+	(game.cursor_x, game.cursor_y) = state.get("cursor")?.as_pos().filter(|&(x, y)| game.in_bounds(x, y))?;
+	game.partner = state.get("partner").and_then(json::Value::as_pos).filter(|&(x, y)| game.in_bounds(x, y));
+	game.exploded = state.get("exploded").and_then(json::Value::as_pos);
+	// the solver would try to judge a board it only sees the outside of
+	game.reviewable = false;
+	let result = state.get("result")?.as_str()?.to_string();
+	Some((game, field("elapsed")? as u64, result))
+}
+
+// draws the game published to `path` until q is pressed, returns the exit code
+pub fn run(path: &str, options: &Options, glyphs: Glyphs) -> i32 {
+	let theme = match Theme::by_name(&options.theme) {
+		Ok(theme) => theme,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	let plain = render::plain_output(options.plain);
+	if plain {
+		console::set_colors_enabled(false);
+	}
+	let grid = GridRenderer {
+		theme,
+		glyphs,
+		plain,
+		wide: options.wide,
+		checkerboard: options.checkerboard,
+		grid_lines: options.grid_lines,
+		center: options.center,
+		fog: None,
+		minimap: MinimapCache::default(),
+	};
+	let view = View {
+		minimap: options.minimap,
+		dim_satisfied: options.dim_satisfied,
+		frontier: options.frontier,
+		transpose: options.transpose.unwrap_or(false),
+		..Default::default()
+	};
+	let stdout = Term::buffered_stdout();
+	let altscreen = options.altscreen && !plain;
+	if altscreen {
+		screen::enter(&stdout);
+	}
+	let keys = input::spawn_reader();
+	// the text last drawn, and whether anything has been drawn from it
+	let mut last_text = None;
+	let mut drawn = false;
+	loop {
+		match keys.recv_timeout(POLL_TIME) {
+			Ok(Key::Char('q') | Key::Escape) | Err(RecvTimeoutError::Disconnected) => break,
+			_ => (),
+		}
+		// a missing file is a game that hasn't started yet, or one being replaced
+		let text = fs::read_to_string(path).ok();
+		if text.is_some() && text == last_text {
+			continue;
+		}
+		match text.as_deref().and_then(load) {
+			Some((game, elapsed, result)) => {
+				let message = match result.as_str() {
+					"playing" => tr(Msg::SpectateHint),
+					"win" => tr(Msg::YouWin),
+					"quit" => tr(Msg::ResultQuit),
+					_ => tr(Msg::GameOver),
+				};
+				let status = Status {
+					elapsed,
+					message: Some(message.into()),
+					..Default::default()
+				};
+				grid.draw(&game, &view, &status, &stdout);
+				last_text = text;
+				drawn = true;
+			},
+			// a file that can't be read keeps the last board on screen until the next one comes
+			None if !drawn => {
+				if !plain {
+					stdout.clear_screen().unwrap();
+				}
+				stdout.write_line(&glyphs.text(tr(Msg::WaitingForGame))).unwrap();
+				stdout.flush().unwrap();
+				drawn = true;
+			},
+			None => (),
+		}
+	}
+	if altscreen {
+		screen::leave(&stdout);
+	}
+	0
+}
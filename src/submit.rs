@@ -0,0 +1,133 @@
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use crate::date;
+use crate::json;
+use crate::lang::{tr, trf, Msg};
+use crate::leaderboard;
+use crate::options::Options;
+
+// times on the daily board shared with a server the player picked. a win is sent as
+//
+//   POST <url>
+//   {"date": "2024-05-01", "board": "16x16x40:19844", "elapsed": 61.234, "three_bv": 120,
+//    "version": "0.1.0", "name": "someone"}
+//
+// and any 2xx status means it was taken. the best times come back from
+//
+//   GET <url>?date=2024-05-01&board=16x16x40:19844
+//
+// as an array of {"name": "someone", "elapsed": 61.234}. https urls are sent encrypted
+
+// the best times printed
+const SHOWN: usize = 10;
+
+// the seed of the daily board, the same for everyone on the same day in UTC
+pub fn daily_seed() -> u64 {
+	date::now() / 86400
+}
+
+// the day a daily board is for
+pub fn daily_date(seed: u64) -> String {
+	date::date(seed * 86400)
+}
+
+// sends a daily win on its own thread so a slow server doesn't hold up the end screen,
+// the line to show about how it went comes through the receiver
+pub fn send(url: &str, name: &str, code: &str, seed: u64, elapsed: f64, three_bv: usize) -> Receiver<String> {
+	let body = format!(
+		"{{\"date\": \"{}\", \"board\": \"{}\", \"elapsed\": {:.3}, \"three_bv\": {}, \"version\": \"{}\", \"name\": \"{}\"}}",
+		daily_date(seed),
+		json::escape(code),
+		elapsed,
+		three_bv,
+		env!("CARGO_PKG_VERSION"),
+		json::escape(name),
+	);
+	let url = url.to_string();
+	let (sender, receiver) = mpsc::channel();
+	thread::spawn(move || {
+		let line = match http::request("POST", &url, Some(&body)) {
+			Ok(_) => trf(Msg::SubmitDone, &[&url]),
+			Err(err) => trf(Msg::SubmitFailed, &[&err]),
+		};
+		let _ = sender.send(line);
+	});
+	receiver
+}
+
+// prints the best times on today's daily board of the size in the options, returns the exit code
+pub fn print_top(url: &str, options: &Options) -> i32 {
+	let mut difficulty = format!("{}x{}x{}", options.width, options.height, options.mines);
+	if let Some(mode) = options.mode() {
+		difficulty = format!("{}-{}", difficulty, mode);
+	}
+	let seed = daily_seed();
+	let code = leaderboard::code(&difficulty, seed);
+	let query = format!("{}{}date={}&board={}", url, if url.contains('?') { '&' } else { '?' }, daily_date(seed), code);
+	let times = match http::request("GET", &query, None).and_then(|body| parse_times(&body)) {
+		Ok(times) => times,
+		Err(err) => {
+			eprintln!("{}", trf(Msg::FetchFailed, &[&err]));
+			return 1;
+		},
+	};
+	println!("{}", trf(Msg::DailyTopTitle, &[&daily_date(seed), &code]));
+	if times.is_empty() {
+		println!("{}", tr(Msg::DailyTopEmpty));
+	}
+	for (rank, (name, secs)) in times.iter().take(SHOWN).enumerate() {
+		println!("{:>3}. {:>8.3}s  {}", rank + 1, secs, name);
+	}
+	0
+}
+
+fn parse_times(body: &str) -> Result<Vec<(String, f64)>, String> {
+	let bad = || "the server sent something that isn't a list of times".to_string();
+	let value = json::parse(body).map_err(|_| bad())?;
+	let mut times = value
+		.as_array()
+		.ok_or_else(bad)?
+		.iter()
+		.map(|entry| match (entry.get("name").and_then(json::Value::as_str), entry.get("elapsed")) {
+			(Some(name), Some(json::Value::Number(secs))) => Ok((name.to_string(), *secs)),
+			_ => Err(bad()),
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+	times.sort_by(|a, b| a.1.total_cmp(&b.1));
+	Ok(times)
+}
+
+#[cfg(feature = "submit")]
+mod http {
+	use std::time::Duration;
+
+	// for the whole request, connecting included
+	const TIMEOUT: Duration = Duration::from_secs(5);
+
+	// one request to an http:// or https:// url, returns the body of a 2xx response
+	pub fn request(method: &str, url: &str, body: Option<&str>) -> Result<String, String> {
+		let agent = ureq::AgentBuilder::new()
+			.timeout(TIMEOUT)
+			.user_agent(&format!("minesweeper/{}", env!("CARGO_PKG_VERSION")))
+			.build();
+		let request = agent.request(method, url);
+		let response = match body {
+			Some(body) => request.set("Content-Type", "application/json").send_string(body),
+			None => request.call(),
+		};
+		match response {
+			Ok(response) => response.into_string().map_err(|err| format!("no answer from {}: {}", url, err)),
+			Err(ureq::Error::Status(code, response)) => Err(format!("{} answered '{} {}'", url, code, response.status_text())),
+			// the url is in the message already
+			Err(ureq::Error::Transport(err)) => Err(err.to_string()),
+		}
+	}
+}
+
+#[cfg(not(feature = "submit"))]
+mod http {
+	pub fn request(_method: &str, _url: &str, _body: Option<&str>) -> Result<String, String> {
+		Err("this build has no HTTP client, it needs the submit feature".into())
+	}
+}
@@ -0,0 +1,278 @@
+use std::fmt::Write as _;
+use std::fs;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::coop::Coop;
+use crate::options::{apply_variants, new_game, Options};
+use crate::replay::{self, Replay};
+use crate::theme::{self, ThemeDef};
+use crate::{MSGame, TileContents, TileVis, TurnResult};
+
+// what the terminal behind the board would be
+const BACKGROUND: &str = "#1c1c1c";
+const FOREGROUND: &str = "#e5e5e5";
+// around the mine that ended the game
+const EXPLODED: &str = "#ffff00";
+
+// the 16 basic colors as xterm draws them, normal then bright
+const BASIC: [&str; 16] = [
+	"#000000", "#cd0000", "#00cd00", "#cdcd00", "#0000ee", "#cd00cd", "#00cdcd", "#e5e5e5",
+	"#7f7f7f", "#ff0000", "#00ff00", "#ffff00", "#5c5cff", "#ff00ff", "#00ffff", "#ffffff",
+];
+const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+// plays a replay to the end without drawing it and writes the board it ended on, returns the exit code
+pub fn export(replay: &Replay, options: &Options, path: &str) -> i32 {
+	let game = match final_board(replay, options) {
+		Ok(game) => game,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	match write(&game, options, path) {
+		Ok(()) => {
+			println!("wrote {}", path);
+			0
+		},
+		Err(err) => {
+			eprintln!("{}", err);
+			1
+		},
+	}
+}
+
+// writes the board as it is now
+pub fn write(game: &MSGame, options: &Options, path: &str) -> Result<(), String> {
+	let def = theme::def_by_name(&options.theme)?;
+	fs::write(path, svg(game, def, options.svg_cell)).map_err(|err| format!("could not write '{}': {}", path, err))
+}
+
+fn final_board(replay: &Replay, options: &Options) -> Result<MSGame, String> {
+	let mut game = new_game(options).map_err(|err| err.to_string())?;
+	apply_variants(&mut game, options).map_err(|err| err.to_string())?;
+	let coop = options.coop.then(|| Coop::new(&options.coop_keys));
+	let mut rush_rng = StdRng::seed_from_u64(game.seed.unwrap_or_default());
+	for (_, key) in replay.moves() {
+		let result = match key {
+			Some(key) => replay::play_key(&mut game, coop.as_ref(), key),
+			None => {
+				game.add_mine(&mut rush_rng);
+				TurnResult::Continue
+			},
+		};
+		if !matches!(result, TurnResult::Continue) {
+			break;
+		}
+	}
+	Ok(game)
+}
+
+// the board as rectangles `cell` pixels wide with the glyphs on top, colored like `def` in a terminal
+pub fn svg(game: &MSGame, def: &ThemeDef, cell: usize) -> String {
+	let (width, height) = (game.width * cell, game.height * cell);
+	let mut text = String::new();
+	writeln!(text, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+	writeln!(
+		text,
+		r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="monospace" font-size="{}" text-anchor="middle" dominant-baseline="central">"#,
+		cell * 2 / 3,
+		w = width,
+		h = height,
+	)
+	.unwrap();
+	writeln!(text, "<title>minesweeper {}</title>", escape(&game.difficulty())).unwrap();
+	writeln!(text, r#"<rect width="{}" height="{}" fill="{}"/>"#, width, height, BACKGROUND).unwrap();
+	let hidden = Look::parse(def.hidden);
+	let hidden_fill = hidden.fg.unwrap_or(FOREGROUND.into());
+	let hidden_opacity = if hidden.dim { 0.3 } else { 0.5 };
+	for y in 0..game.height {
+		for x in 0..game.width {
+			if game.is_void(x, y) {
+				continue;
+			}
+			let (px, py) = (x * cell, y * cell);
+			let tile = game.shown(x, y);
+			if !matches!(tile.visibility, TileVis::Open) {
+				// flags stand on hidden tiles
+				writeln!(
+					text,
+					r#"<rect x="{}" y="{}" width="{c}" height="{c}" fill="{}" fill-opacity="{}" stroke="{}"/>"#,
+					px, py, hidden_fill, hidden_opacity, BACKGROUND, c = cell,
+				)
+				.unwrap();
+			}
+			let (glyph, look) = match (tile.visibility, tile.contents) {
+				(TileVis::Hidden, _) => continue,
+				(TileVis::Flag, _) => ("F".to_string(), Look::parse(def.flag)),
+				(TileVis::AntiFlag, _) => ("A".to_string(), Look::parse(def.flag)),
+				(TileVis::Open, TileContents::Mine) => ("*".to_string(), Look::parse(def.mine)),
+				(TileVis::Open, TileContents::AntiMine) => ("o".to_string(), Look::parse(def.mine)),
+				(TileVis::Open, TileContents::Safe(_)) => match game.number(x, y) {
+					0 => continue,
+					n => (n.to_string(), Look::parse(def.numbers[(n.unsigned_abs() as usize - 1).min(7)])),
+				},
+			};
+			let (mut fg, mut bg) = (look.fg.unwrap_or(FOREGROUND.into()), look.bg);
+			if look.reverse {
+				let under = bg.unwrap_or(BACKGROUND.into());
+				(fg, bg) = (under, Some(fg));
+			}
+			if let Some(bg) = bg {
+				writeln!(text, r#"<rect x="{}" y="{}" width="{c}" height="{c}" fill="{}"/>"#, px, py, bg, c = cell).unwrap();
+			}
+			let mut attrs = String::new();
+			if look.bold {
+				attrs.push_str(r#" font-weight="bold""#);
+			}
+			if look.dim {
+				attrs.push_str(r#" fill-opacity="0.6""#);
+			}
+			writeln!(
+				text,
+				r#"<text x="{}" y="{}" fill="{}"{}>{}</text>"#,
+				px + cell / 2,
+				py + cell / 2,
+				fg,
+				attrs,
+				escape(&glyph),
+			)
+			.unwrap();
+		}
+	}
+	if let Some((x, y)) = game.exploded {
+		let stroke = (cell / 8).max(1);
+		writeln!(
+			text,
+			r#"<rect x="{}" y="{}" width="{c}" height="{c}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+			x * cell + stroke / 2,
+			y * cell + stroke / 2,
+			EXPLODED,
+			stroke,
+			c = cell - stroke,
+		)
+		.unwrap();
+	}
+	text.push_str("</svg>\n");
+	text
+}
+
+// the parts of a dotted console style that can be drawn in an image
+#[derive(Default)]
+struct Look {
+	fg: Option<String>,
+	bg: Option<String>,
+	bold: bool,
+	dim: bool,
+	reverse: bool,
+}
+
+impl Look {
+	fn parse(dotted: &str) -> Self {
+		let mut look = Self::default();
+		// bright goes with the basic color in front of it
+		let (mut fg, mut bg) = (None, None);
+		let (mut fg_bright, mut bg_bright) = (false, false);
+		for part in dotted.split('.').filter(|part| !part.is_empty()) {
+			match part {
+				"bold" => look.bold = true,
+				"dim" => look.dim = true,
+				"reverse" => look.reverse = true,
+				"bright" => fg_bright = true,
+				"on_bright" => bg_bright = true,
+				_ => match part.strip_prefix("on_") {
+					Some(color) => bg = color_index(color),
+					None => fg = color_index(part),
+				},
+			}
+		}
+		look.fg = fg.map(|i| palette(brighten(i, fg_bright)));
+		look.bg = bg.map(|i| palette(brighten(i, bg_bright)));
+		look
+	}
+}
+
+fn color_index(name: &str) -> Option<u8> {
+	match NAMES.iter().position(|&n| n == name) {
+		Some(i) => Some(i as u8),
+		None => name.parse().ok(),
+	}
+}
+
+fn brighten(index: u8, bright: bool) -> u8 {
+	if bright && index < 8 {
+		index + 8
+	} else {
+		index
+	}
+}
+
+// a 256-color palette index as a css color
+fn palette(index: u8) -> String {
+	match index {
+		0..=15 => BASIC[index as usize].into(),
+		16..=231 => {
+			let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+			let i = index - 16;
+			format!("#{:02x}{:02x}{:02x}", level(i / 36), level(i / 6 % 6), level(i % 6))
+		},
+		_ => {
+			let gray = 8 + (index - 232) * 10;
+			format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+		},
+	}
+}
+
+// text that can go between xml tags or in quotes
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::action::Action;
+	use crate::boardfile;
+
+	// a lost game: the mine that was opened, a wrong flag, a number and a void tile
+	const GOLDEN: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" width="48" height="24" viewBox="0 0 48 24" font-family="monospace" font-size="8" text-anchor="middle" dominant-baseline="central">
+<title>minesweeper 4x2x2</title>
+<rect width="48" height="24" fill="#1c1c1c"/>
+<rect x="0" y="0" width="12" height="12" fill="#cd0000"/>
+<text x="6" y="6" fill="#000000">*</text>
+<rect x="12" y="0" width="12" height="12" fill="#e5e5e5" fill-opacity="0.3" stroke="#1c1c1c"/>
+<text x="18" y="6" fill="#ff0000">F</text>
+<rect x="24" y="0" width="12" height="12" fill="#e5e5e5" fill-opacity="0.3" stroke="#1c1c1c"/>
+<rect x="0" y="12" width="12" height="12" fill="#e5e5e5" fill-opacity="0.3" stroke="#1c1c1c"/>
+<rect x="12" y="12" width="12" height="12" fill="#e5e5e5" fill-opacity="0.3" stroke="#1c1c1c"/>
+<text x="30" y="18" fill="#00cd00" fill-opacity="0.6">1</text>
+<rect x="36" y="12" width="12" height="12" fill="#cd0000"/>
+<text x="42" y="18" fill="#000000">*</text>
+<rect x="0" y="0" width="11" height="11" fill="none" stroke="#ffff00" stroke-width="1"/>
+</svg>
+"##;
+
+	#[test]
+	fn lost_board_matches_the_golden_image() {
+		let mut game = boardfile::parse("test", "*..X\n..O*\n").unwrap();
+		(game.cursor_x, game.cursor_y) = (1, 0);
+		game.act(Action::Flag);
+		(game.cursor_x, game.cursor_y) = (0, 0);
+		assert!(matches!(game.act(Action::Open), TurnResult::Lose));
+		assert_eq!(svg(&game, theme::def_by_name("classic").unwrap(), 12), GOLDEN);
+	}
+
+	#[test]
+	fn theme_styles_become_colors() {
+		let look = Look::parse("bright.red.on_blue.bold");
+		assert_eq!(look.fg.as_deref(), Some("#ff0000"));
+		assert_eq!(look.bg.as_deref(), Some("#0000ee"));
+		assert!(look.bold && !look.dim && !look.reverse);
+	}
+}
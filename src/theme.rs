@@ -0,0 +1,116 @@
+use console::Style;
+
+// styles are written the same way as console's dotted style strings,
+// numbers are 256-color palette indices
+pub struct ThemeDef {
+	pub name: &'static str,
+	pub numbers: [&'static str; 8],
+	pub hidden: &'static str,
+	pub flag: &'static str,
+	pub mine: &'static str,
+	pub cursor: &'static str,
+	pub status: &'static str,
+	// background of the hidden tiles next to an opened number, a palette index
+	pub frontier: u8,
+}
+
+const THEMES: [ThemeDef; 4] = [
+	ThemeDef {
+		name: "classic",
+		numbers: ["green.dim", "cyan.bright", "yellow.bright", "magenta", "magenta", "magenta", "magenta", "magenta"],
+		hidden: "dim",
+		flag: "red.bright",
+		mine: "black.on_red",
+		cursor: "",
+		status: "",
+		frontier: 236,
+	},
+	ThemeDef {
+		name: "solarized",
+		numbers: ["33", "64", "160", "61", "166", "37", "125", "245"],
+		hidden: "240",
+		flag: "166.bold",
+		mine: "230.on_160",
+		cursor: "136.bold",
+		status: "245",
+		frontier: 235,
+	},
+	ThemeDef {
+		name: "monochrome",
+		numbers: ["", "", "", "", "", "", "", ""],
+		hidden: "dim",
+		flag: "bold",
+		mine: "reverse",
+		cursor: "bold",
+		status: "",
+		frontier: 236,
+	},
+	ThemeDef {
+		name: "high-contrast",
+		numbers: ["blue.bright.bold", "green.bright.bold", "red.bright.bold", "magenta.bright.bold", "yellow.bright.bold", "cyan.bright.bold", "white.bright.bold", "white.bold"],
+		hidden: "white",
+		flag: "black.on_yellow.bold",
+		mine: "white.on_red.bold",
+		cursor: "yellow.bright.bold",
+		status: "white.bright",
+		frontier: 238,
+	},
+];
+
+#[derive(Clone)]
+pub struct Theme {
+	pub numbers: [Style; 8],
+	pub hidden: Style,
+	pub flag: Style,
+	pub mine: Style,
+	pub cursor: Style,
+	pub status: Style,
+	pub frontier: u8,
+}
+
+impl Theme {
+	pub fn by_name(name: &str) -> Result<Self, String> {
+		def_by_name(name).map(Self::from_def)
+	}
+
+	fn from_def(def: &ThemeDef) -> Self {
+		Self {
+			numbers: def.numbers.map(Style::from_dotted_str),
+			hidden: Style::from_dotted_str(def.hidden),
+			flag: Style::from_dotted_str(def.flag),
+			mine: Style::from_dotted_str(def.mine),
+			cursor: Style::from_dotted_str(def.cursor),
+			status: Style::from_dotted_str(def.status),
+			frontier: def.frontier,
+		}
+	}
+}
+
+// the style strings of a theme, for drawing it somewhere other than the terminal
+pub fn def_by_name(name: &str) -> Result<&'static ThemeDef, String> {
+	match THEMES.iter().find(|def| def.name == name) {
+		Some(def) => Ok(def),
+		None => Err(format!("unknown theme '{}', available themes: {}", name, names().join(", "))),
+	}
+}
+
+pub fn names() -> Vec<&'static str> {
+	THEMES.iter().map(|def| def.name).collect()
+}
+
+// print a sample row of every theme
+pub fn list_themes() {
+	for def in &THEMES {
+		let theme = Theme::from_def(def);
+		print!("{:<14}", def.name);
+		for (i, style) in theme.numbers.iter().enumerate() {
+			print!("{} ", style.apply_to(i + 1));
+		}
+		print!("{} ", theme.hidden.apply_to("#"));
+		print!("{} ", theme.hidden.apply_to("#").on_color256(theme.frontier));
+		print!("{} ", theme.flag.apply_to("F"));
+		print!("{} ", theme.mine.apply_to("*"));
+		print!("{}", theme.cursor.apply_to("( )"));
+		println!("  {}", theme.status.apply_to("Mines: 10"));
+	}
+}
@@ -0,0 +1,369 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::app::App;
+use crate::glyphs::Glyphs;
+use crate::json;
+use crate::lang::{tr, trf, Msg};
+use crate::leaderboard;
+use crate::options::Options;
+use crate::records::data_dir;
+use crate::TurnResult;
+
+// added to the score for every board that wasn't cleared
+const PENALTY: Duration = Duration::from_secs(300);
+
+// a tournament file lists the boards to play in order, one per line: a board code like
+// `16x16x40:1234`, or just a seed for a board of the size in the options. lines starting
+// with `#` are comments
+struct Board {
+	width: usize,
+	height: usize,
+	mines: usize,
+	seed: u64,
+}
+
+impl Board {
+	// with the variants the options pick, like the leaderboard
+	fn code(&self, mode: Option<&str>) -> String {
+		let mut difficulty = format!("{}x{}x{}", self.width, self.height, self.mines);
+		if let Some(mode) = mode {
+			difficulty = format!("{}-{}", difficulty, mode);
+		}
+		leaderboard::code(&difficulty, self.seed)
+	}
+}
+
+fn parse(path: &str, text: &str, options: &Options) -> Result<Vec<Board>, String> {
+	let mut boards = Vec::new();
+	for (n, line) in text.lines().enumerate().map(|(i, line)| (i + 1, line.trim())) {
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let bad = |what: &str| format!("{}:{}: {} '{}'", path, n, what, line);
+		let (size, seed) = line.rsplit_once(':').unwrap_or(("", line));
+		let seed = seed.parse().map_err(|_| bad("invalid seed in"))?;
+		let (width, height, mines) = if size.is_empty() {
+			(options.width, options.height, options.mines)
+		} else {
+			// the variants are picked with the options, the same for every board
+			let (size, mode) = match size.split_once('-') {
+				Some((size, mode)) => (size, Some(mode)),
+				None => (size, None),
+			};
+			if mode != options.mode().as_deref() {
+				let variants = mode.unwrap_or("no variants");
+				return Err(format!("{}:{}: '{}' is played with {}, the options have to pick the same", path, n, line, variants));
+			}
+			let numbers: Vec<_> = size.split('x').map(str::parse::<usize>).collect();
+			match numbers[..] {
+				[Ok(width), Ok(height), Ok(mines)] if width > 0 && height > 0 && width.checked_mul(height).is_some_and(|size| mines < size) => {
+					(width, height, mines)
+				},
+				_ => return Err(bad("invalid board size in")),
+			}
+		};
+		boards.push(Board { width, height, mines, seed });
+	}
+	if boards.is_empty() {
+		return Err(format!("'{}' has no boards", path));
+	}
+	Ok(boards)
+}
+
+// the score in milliseconds, lower is better: the time of every cleared board and the penalty for
+// every other one. whole milliseconds keep it the same for everyone who played the same way
+fn score(results: &[Option<Duration>]) -> u128 {
+	results.iter().map(|result| result.unwrap_or(PENALTY).as_millis()).sum()
+}
+
+fn secs(millis: u128) -> String {
+	format!("{}.{:03}", millis / 1000, millis % 1000)
+}
+
+// how far a tournament has come, kept in the data directory so it can be stopped and taken up again:
+//
+//   {"version": 1, "boards": ["16x16x40:1", "16x16x40:2"], "results": [61.234, null]}
+//
+// the results are the seconds each cleared board took, null for the ones that weren't.
+// it only carries on a tournament with the same boards
+struct Progress {
+	boards: Vec<String>,
+	results: Vec<Option<Duration>>,
+}
+
+impl Progress {
+	fn load(boards: &[String]) -> Result<Option<Self>, String> {
+		let Some(path) = path() else {
+			return Ok(None);
+		};
+		let Ok(text) = fs::read_to_string(&path) else {
+			return Ok(None);
+		};
+		let bad = || format!("'{}' {}", path.display(), tr(Msg::TournamentInvalid));
+		let state = json::parse(&text).map_err(|_| bad())?;
+		if state.get("version") != Some(&json::Value::Number(1.0)) {
+			return Err(bad());
+		}
+		let saved = state.get("boards").and_then(json::Value::as_array).ok_or_else(bad)?;
+		let saved: Option<Vec<_>> = saved.iter().map(json::Value::as_str).collect();
+		if saved.ok_or_else(bad)? != boards {
+			return Ok(None);
+		}
+		let results = state
+			.get("results")
+			.and_then(json::Value::as_array)
+			.ok_or_else(bad)?
+			.iter()
+			.map(|result| match result {
+				json::Value::Number(secs) if *secs >= 0.0 => Ok(Some(Duration::from_millis((secs * 1000.0).round() as u64))),
+				json::Value::Null => Ok(None),
+				_ => Err(bad()),
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		if results.len() >= boards.len() {
+			return Err(bad());
+		}
+		Ok(Some(Self { boards: boards.to_vec(), results }))
+	}
+
+	fn save(&self) -> Result<(), String> {
+		let path = path().ok_or("no data directory for the tournament")?;
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir).map_err(|err| format!("could not create '{}': {}", dir.display(), err))?;
+		}
+		let boards: Vec<_> = self.boards.iter().map(|code| format!("\"{}\"", json::escape(code))).collect();
+		let results: Vec<_> = self
+			.results
+			.iter()
+			.map(|result| result.map_or("null".into(), |elapsed| format!("{:.3}", elapsed.as_secs_f64())))
+			.collect();
+		let text = format!("{{\"version\": 1, \"boards\": [{}], \"results\": [{}]}}\n", boards.join(", "), results.join(", "));
+		fs::write(&path, text).map_err(|err| format!("could not write '{}': {}", path.display(), err))
+	}
+
+	// a finished tournament starts over next time
+	fn clear() {
+		if let Some(path) = path() {
+			let _ = fs::remove_file(path);
+		}
+	}
+
+	fn cleared(&self) -> usize {
+		self.results.iter().flatten().count()
+	}
+}
+
+fn path() -> Option<PathBuf> {
+	Some(data_dir()?.join("tournament.json"))
+}
+
+// plays the boards in the file in order from where a stopped run of the same list left off,
+// returns the exit code
+pub fn run(path: &str, mut options: Options, glyphs: Glyphs) -> i32 {
+	let boards = match fs::read_to_string(path).map_err(|err| format!("could not read '{}': {}", path, err)) {
+		Ok(text) => parse(path, &text, &options),
+		Err(err) => Err(err),
+	};
+	let boards = match boards {
+		Ok(boards) => boards,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	let mode = options.mode();
+	let codes: Vec<_> = boards.iter().map(|board| board.code(mode.as_deref())).collect();
+	let saved = match Progress::load(&codes) {
+		Ok(saved) => saved,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	let resumed = saved.is_some();
+	let mut progress = saved.unwrap_or(Progress {
+		boards: codes,
+		results: Vec::new(),
+	});
+	let board = &boards[progress.results.len()];
+	(options.width, options.height, options.mines, options.seed) = (board.width, board.height, board.mines, Some(board.seed));
+	let report = options.tournament_report.clone();
+	let mut app = match App::new(options, glyphs, None) {
+		Ok(app) => app,
+		Err(err) => {
+			eprintln!("{}", err);
+			return 2;
+		},
+	};
+	if resumed {
+		let line = trf(Msg::TournamentResumed, &[&(progress.results.len() + 1), &boards.len()]);
+		if !app.interlude(Duration::ZERO, &[line], Msg::PressContinue) {
+			app.close(&[]);
+			return 0;
+		}
+	}
+	loop {
+		// the boards only count for the tournament, not for the leaderboard of each seed
+		let board = &boards[progress.results.len()];
+		if let Err(err) = app.set_seeded_board(board.width, board.height, board.mines, board.seed) {
+			app.close(&[]);
+			eprintln!("{}", err);
+			return 2;
+		}
+		let number = progress.results.len() + 1;
+		app.set_label(Some(trf(Msg::TournamentStatus, &[&number, &boards.len(), &secs(score(&progress.results))])));
+		let outcome = app.run();
+		let elapsed = outcome.elapsed;
+		let result = match outcome.result {
+			TurnResult::Win => TurnResult::Win,
+			TurnResult::Quit => TurnResult::Quit,
+			// giving up fails the board like losing it
+			_ => TurnResult::Lose,
+		};
+		app.report(outcome);
+		let line = match result {
+			TurnResult::Quit => return stop(&app, &progress, path),
+			TurnResult::Win => {
+				// whole milliseconds, as in the score
+				let elapsed = Duration::from_millis(elapsed.as_millis() as u64);
+				progress.results.push(Some(elapsed));
+				trf(Msg::TournamentCleared, &[&number, &secs(elapsed.as_millis())])
+			},
+			_ => {
+				progress.results.push(None);
+				trf(Msg::TournamentFailed, &[&number, &PENALTY.as_secs()])
+			},
+		};
+		let standing = trf(Msg::TournamentStanding, &[&progress.results.len(), &secs(score(&progress.results)), &progress.cleared()]);
+		if progress.results.len() == boards.len() {
+			Progress::clear();
+			let mut lines = vec![line, trf(Msg::TournamentOver, &[&progress.cleared(), &boards.len(), &secs(score(&progress.results))])];
+			if let Some(out) = &report {
+				lines.push(match fs::write(out, report_text(&progress)) {
+					Ok(()) => trf(Msg::TournamentReport, &[out]),
+					Err(err) => format!("could not write '{}': {}", out, err),
+				});
+			}
+			app.interlude(elapsed, &lines, Msg::PressExit);
+			app.close(&lines);
+			return 0;
+		}
+		if let Err(err) = progress.save() {
+			eprintln!("{}", err);
+		}
+		if !app.interlude(elapsed, &[line, standing], Msg::PressContinue) {
+			return stop(&app, &progress, path);
+		}
+	}
+}
+
+// saves the tournament to go on with next time and puts the terminal back
+fn stop(app: &App, progress: &Progress, path: &str) -> i32 {
+	app.close(&[]);
+	match progress.save() {
+		Ok(()) => {
+			println!("{}", trf(Msg::TournamentSaved, &[&(progress.results.len() + 1), &progress.boards.len(), &path]));
+			0
+		},
+		Err(err) => {
+			eprintln!("{}", err);
+			1
+		},
+	}
+}
+
+// the boards, how each went and the score, to hold up against someone else's run of the same list:
+//
+//   16x16x40:1 cleared 61.234
+//   16x16x40:2 failed +300.000
+//   score 361.234 (1 of 2 cleared)
+fn report_text(progress: &Progress) -> String {
+	let mut text = String::new();
+	for (code, result) in progress.boards.iter().zip(&progress.results) {
+		match result {
+			Some(elapsed) => text.push_str(&format!("{} cleared {}\n", code, secs(elapsed.as_millis()))),
+			None => text.push_str(&format!("{} failed +{}\n", code, secs(PENALTY.as_millis()))),
+		}
+	}
+	let score = score(&progress.results);
+	text.push_str(&format!("score {} ({} of {} cleared)\n", secs(score), progress.cleared(), progress.boards.len()));
+	text
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn options() -> Options {
+		Options {
+			width: 9,
+			height: 9,
+			mines: 10,
+			..Options::default()
+		}
+	}
+
+	fn took(secs: f64) -> Option<Duration> {
+		Some(Duration::from_secs_f64(secs))
+	}
+
+	#[test]
+	fn boards_are_read_in_order() {
+		let text = "# round one\n16x16x40:1234\n\n  77  \n30x16x99:5\n";
+		let boards = parse("list", text, &options()).unwrap();
+		let boards: Vec<_> = boards.iter().map(|board| (board.width, board.height, board.mines, board.seed)).collect();
+		// a seed alone is played at the size in the options
+		assert_eq!(boards, [(16, 16, 40, 1234), (9, 9, 10, 77), (30, 16, 99, 5)]);
+	}
+
+	#[test]
+	fn broken_lists_are_rejected() {
+		let err = |text: &str| parse("list", text, &options()).err().unwrap();
+		assert_eq!(err("# nothing\n\n"), "'list' has no boards");
+		assert_eq!(err("12\nsoon\n"), "list:2: invalid seed in 'soon'");
+		assert_eq!(err("16x16:3\n"), "list:1: invalid board size in '16x16:3'");
+		assert_eq!(err("3x3x9:3\n"), "list:1: invalid board size in '3x3x9:3'");
+		assert_eq!(err("0x3x1:3\n"), "list:1: invalid board size in '0x3x1:3'");
+		assert_eq!(err("99999999999x99999999999x1:1\n"), "list:1: invalid board size in '99999999999x99999999999x1:1'");
+		assert_eq!(err("9x9x10-guard:3\n"), "list:1: '9x9x10-guard:3' is played with guard, the options have to pick the same");
+	}
+
+	#[test]
+	fn variants_have_to_match_the_options() {
+		let options = Options { guard: true, ..options() };
+		let boards = parse("list", "9x9x10-guard:3\n", &options).unwrap();
+		assert_eq!(boards[0].code(options.mode().as_deref()), "9x9x10-guard:3");
+		let err = parse("list", "9x9x10:3\n", &options).err().unwrap();
+		assert_eq!(err, "list:1: '9x9x10:3' is played with no variants, the options have to pick the same");
+	}
+
+	#[test]
+	fn failed_boards_cost_the_penalty() {
+		assert_eq!(score(&[]), 0);
+		assert_eq!(score(&[took(61.234), None, took(2.5)]), 61_234 + 300_000 + 2_500);
+		// parts of a millisecond don't count
+		assert_eq!(score(&[Some(Duration::from_micros(1_999))]), 1);
+	}
+
+	#[test]
+	fn scores_are_written_in_seconds() {
+		assert_eq!(secs(0), "0.000");
+		assert_eq!(secs(61_234), "61.234");
+		assert_eq!(secs(300_005), "300.005");
+	}
+
+	#[test]
+	fn reports_list_every_board() {
+		let progress = Progress {
+			boards: vec!["16x16x40:1".into(), "16x16x40:2".into()],
+			results: vec![took(61.234), None],
+		};
+		assert_eq!(progress.cleared(), 1);
+		assert_eq!(
+			report_text(&progress),
+			"16x16x40:1 cleared 61.234\n16x16x40:2 failed +300.000\nscore 361.234 (1 of 2 cleared)\n"
+		);
+	}
+}